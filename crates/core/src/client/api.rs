@@ -1,16 +1,22 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
 use eyre::Result;
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, H256, U128, U256, U64};
 use reth_rpc_types::{
-    BlockTransactions, CallRequest, FeeHistory, Index, RichBlock, SyncStatus, Transaction as EtherTransaction,
-    TransactionReceipt,
+    BlockTransactions, CallRequest, FeeHistory, Filter, Index, Log, RichBlock, SyncStatus,
+    Transaction as EtherTransaction,
 };
-use starknet::core::types::{BlockId as StarknetBlockId, BroadcastedInvokeTransactionV1, FieldElement};
+use starknet::core::types::{BlockId as StarknetBlockId, BroadcastedInvokeTransactionV1, FeeEstimate, FieldElement};
 use starknet::providers::sequencer::models::TransactionSimulationInfo;
 use starknet::providers::Provider;
 
+use super::cache::StarknetCacheStats;
 use super::errors::EthApiError;
+use super::submission_tracker::{StarknetTransactionStatus, TransactionLifecycle};
 use crate::models::balance::TokenBalances;
+use crate::models::block::BlockExecution;
+use crate::models::receipt::KakarotTransactionReceipt;
 use crate::models::transaction::StarknetTransactions;
 
 #[async_trait]
@@ -35,7 +41,10 @@ pub trait KakarotEthApi<P: Provider + Send + Sync>: KakarotStarknetApi<P> + Send
 
     async fn block_transaction_count_by_hash(&self, hash: H256) -> Result<U64, EthApiError<P::Error>>;
 
-    async fn transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>, EthApiError<P::Error>>;
+    async fn transaction_receipt(&self, hash: H256)
+    -> Result<Option<KakarotTransactionReceipt>, EthApiError<P::Error>>;
+
+    async fn get_logs(&self, filter: Filter) -> Result<Vec<Log>, EthApiError<P::Error>>;
 
     async fn nonce(&self, ethereum_address: Address, block_id: BlockId) -> Result<U256, EthApiError<P::Error>>;
 
@@ -56,10 +65,25 @@ pub trait KakarotEthApi<P: Provider + Send + Sync>: KakarotStarknetApi<P> + Send
 
     async fn send_transaction(&self, bytes: Bytes) -> Result<H256, EthApiError<P::Error>>;
 
+    /// Sends a batch of raw Ethereum transaction bytes of the same sender to Kakarot in a single
+    /// Starknet multicall, returning the hashes of the transactions.
+    async fn send_transaction_batch(&self, batch: Vec<Bytes>) -> Result<Vec<H256>, EthApiError<P::Error>>;
+
+    /// Sends raw Ethereum transaction bytes to Kakarot and waits for the receipt of the
+    /// transaction, or for its pending receipt when `include_pending` is set.
+    async fn send_transaction_sync(
+        &self,
+        bytes: Bytes,
+        timeout: Duration,
+        include_pending: bool,
+    ) -> Result<KakarotTransactionReceipt, EthApiError<P::Error>>;
+
     async fn get_transaction_count_by_block(&self, block_id: BlockId) -> Result<U64, EthApiError<P::Error>>;
 
     fn base_fee_per_gas(&self) -> U256;
 
+    fn block_gas_limit(&self) -> U256;
+
     fn max_priority_fee_per_gas(&self) -> U128;
 
     async fn fee_history(
@@ -72,6 +96,15 @@ pub trait KakarotEthApi<P: Provider + Send + Sync>: KakarotStarknetApi<P> + Send
     async fn estimate_gas(&self, request: CallRequest, block_id: BlockId) -> Result<U256, EthApiError<P::Error>>;
 
     async fn gas_price(&self) -> Result<U256, EthApiError<P::Error>>;
+
+    /// Returns the lifecycle of the Starknet submission of the transaction.
+    async fn transaction_status(&self, hash: H256) -> Result<Option<TransactionLifecycle>, EthApiError<P::Error>>;
+
+    fn cache_stats(&self) -> StarknetCacheStats;
+
+    /// Returns the hashes of the indexed transactions sent from or to the address, or `None` when
+    /// the local index is disabled.
+    fn transactions_by_address(&self, address: Address) -> Result<Option<Vec<H256>>, EthApiError<P::Error>>;
 }
 
 #[async_trait]
@@ -82,6 +115,13 @@ pub trait KakarotStarknetApi<P: Provider + Send + Sync>: Send + Sync {
 
     fn starknet_provider(&self) -> &P;
 
+    /// Returns the number of the Starknet block in which Kakarot was deployed, the `earliest`
+    /// block.
+    fn genesis_block_number(&self) -> u64;
+
+    /// Converts the Ethereum block id, resolving the `safe` and `finalized` tags.
+    fn starknet_block_id(&self, block_id: BlockId) -> Result<StarknetBlockId, EthApiError<P::Error>>;
+
     async fn map_block_id_to_block_number(&self, block_id: &StarknetBlockId) -> Result<u64, EthApiError<P::Error>>;
 
     async fn submit_starknet_transaction(
@@ -101,12 +141,30 @@ pub trait KakarotStarknetApi<P: Provider + Send + Sync>: Send + Sync {
         starknet_block_id: &StarknetBlockId,
     ) -> Result<Address, EthApiError<P::Error>>;
 
+    /// Returns true if the Starknet account is a Kakarot account, i.e. if its class is the Kakarot
+    /// proxy account class.
+    async fn is_kakarot_account(&self, starknet_address: FieldElement) -> Result<bool, EthApiError<P::Error>>;
+
+    /// Returns the Kakarot transactions among the given transactions, in the same order.
+    async fn filter_kakarot_transactions(
+        &self,
+        transactions: StarknetTransactions,
+    ) -> Result<StarknetTransactions, EthApiError<P::Error>>;
+
     async fn filter_starknet_into_eth_txs(
         &self,
         initial_transactions: StarknetTransactions,
         blockhash_opt: Option<H256>,
         blocknum_opt: Option<U256>,
-    ) -> BlockTransactions;
+    ) -> Result<BlockTransactions, EthApiError<P::Error>>;
+
+    /// Returns the header fields derived from the execution of the Kakarot transactions among the
+    /// given transactions of the block with the given hash and number, or of the pending block.
+    async fn block_execution(
+        &self,
+        transactions: StarknetTransactions,
+        block: Option<(FieldElement, u64)>,
+    ) -> Result<BlockExecution, EthApiError<P::Error>>;
 
     async fn get_eth_block_from_starknet_block(
         &self,
@@ -114,10 +172,30 @@ pub trait KakarotStarknetApi<P: Provider + Send + Sync>: Send + Sync {
         hydrated_tx: bool,
     ) -> Result<RichBlock, EthApiError<P::Error>>;
 
+    /// Returns the fee estimate of the transaction at the given block.
+    async fn estimate_fee(
+        &self,
+        request: BroadcastedInvokeTransactionV1,
+        block_id: StarknetBlockId,
+    ) -> Result<FeeEstimate, EthApiError<P::Error>>;
+
     async fn simulate_transaction(
         &self,
         request: BroadcastedInvokeTransactionV1,
         block_number: u64,
         skip_validate: bool,
     ) -> Result<TransactionSimulationInfo, EthApiError<P::Error>>;
+
+    /// Returns the status of the Starknet transaction, or `None` if Starknet doesn't know about
+    /// it.
+    async fn starknet_transaction_status(
+        &self,
+        starknet_hash: FieldElement,
+    ) -> Result<Option<StarknetTransactionStatus>, EthApiError<P::Error>>;
+
+    fn save_transaction_hash(&self, ethereum_hash: H256, starknet_hash: H256) -> Result<(), EthApiError<P::Error>>;
+
+    fn ethereum_transaction_hash(&self, starknet_hash: H256) -> Result<Option<H256>, EthApiError<P::Error>>;
+
+    fn starknet_transaction_hash(&self, hash: H256) -> Result<Option<FieldElement>, EthApiError<P::Error>>;
 }
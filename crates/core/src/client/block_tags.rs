@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use reth_primitives::BlockNumberOrTag;
+use starknet::core::types::{BlockId as StarknetBlockId, BlockStatus, MaybePendingBlockWithTxHashes};
+use starknet::providers::Provider;
+
+use super::api::KakarotEthApi;
+use super::constants::{BLOCK_TAGS_POLL_INTERVAL, DEFAULT_SAFE_BLOCK_CONFIRMATIONS};
+use super::errors::{ConfigError, EthApiError};
+use super::helpers::binary_search;
+
+/// Policy resolving the `safe` block tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafeBlockPolicy {
+    /// The block accepted on L2 with the given number of blocks on top of it.
+    Confirmations(u64),
+    /// The `finalized` block.
+    Finalized,
+}
+
+impl Default for SafeBlockPolicy {
+    fn default() -> Self {
+        Self::Confirmations(DEFAULT_SAFE_BLOCK_CONFIRMATIONS)
+    }
+}
+
+impl SafeBlockPolicy {
+    /// Reads the policy from `KAKAROT_SAFE_BLOCK_POLICY`, either `finalized` or a number of
+    /// confirmations.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_vars(&std::env::vars().collect())
+    }
+
+    /// Reads the policy from the `KAKAROT_SAFE_BLOCK_POLICY` variable of the given variables.
+    pub fn from_vars(vars: &HashMap<String, String>) -> Result<Self, ConfigError> {
+        match vars.get("KAKAROT_SAFE_BLOCK_POLICY") {
+            None => Ok(Self::default()),
+            Some(policy) if policy == "finalized" => Ok(Self::Finalized),
+            Some(policy) => policy.parse().map(Self::Confirmations).map_err(|_| {
+                ConfigError::EnvironmentVariableSetWrong(format!(
+                    "KAKAROT_SAFE_BLOCK_POLICY should be `finalized` or a number of confirmations, got {policy}"
+                ))
+            }),
+        }
+    }
+}
+
+/// Starknet block numbers of the `safe` and `finalized` tags, `None` while no block qualifies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResolvedBlockTags {
+    pub safe: Option<u64>,
+    pub finalized: Option<u64>,
+}
+
+impl ResolvedBlockTags {
+    /// Replaces the `safe` and `finalized` tags by the number of the block they resolve to, or
+    /// returns `None` if no block qualifies for the tag yet.
+    pub fn resolve(&self, block: BlockNumberOrTag) -> Option<BlockNumberOrTag> {
+        match block {
+            BlockNumberOrTag::Safe => self.safe.map(BlockNumberOrTag::Number),
+            BlockNumberOrTag::Finalized => self.finalized.map(BlockNumberOrTag::Number),
+            block => Some(block),
+        }
+    }
+}
+
+/// Resolves the `safe` and `finalized` tags in the background, so that every request using a tag
+/// sees the same block until the next resolution.
+///
+/// The `finalized` block is the newest block accepted on L1. Blocks are accepted on L1 in order,
+/// so it is found by a binary search above the previously finalized block.
+#[derive(Debug, Default)]
+pub struct BlockTagResolver {
+    policy: SafeBlockPolicy,
+    tags: Mutex<Option<ResolvedBlockTags>>,
+}
+
+impl BlockTagResolver {
+    pub fn new(policy: SafeBlockPolicy) -> Self {
+        Self { policy, tags: Mutex::default() }
+    }
+
+    /// Returns the tags of the latest resolution, `None` until the first resolution.
+    pub fn tags(&self) -> Option<ResolvedBlockTags> {
+        *self.tags.lock().expect("block tags lock poisoned")
+    }
+
+    /// Spawns the resolution of the tags on the Tokio runtime.
+    pub fn spawn<P: Provider + Send + Sync + 'static>(
+        resolver: Arc<Self>,
+        kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(BLOCK_TAGS_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(err) = resolver.resolve(kakarot_client.as_ref()).await {
+                    log::error!("Failed to resolve the safe and finalized blocks: {err}");
+                }
+            }
+        })
+    }
+
+    /// Resolves the tags against the current chain and records them.
+    pub async fn resolve<P: Provider + Send + Sync>(
+        &self,
+        kakarot_client: &dyn KakarotEthApi<P>,
+    ) -> Result<ResolvedBlockTags, EthApiError<P::Error>> {
+        let latest = kakarot_client.block_number().await?.as_u64();
+        let finalized = self.finalized_block(kakarot_client, latest).await?;
+        let safe = match self.policy {
+            SafeBlockPolicy::Confirmations(confirmations) => {
+                latest.checked_sub(confirmations).filter(|safe| *safe >= kakarot_client.genesis_block_number())
+            }
+            SafeBlockPolicy::Finalized => finalized,
+        };
+
+        let tags = ResolvedBlockTags { safe, finalized };
+        *self.tags.lock().expect("block tags lock poisoned") = Some(tags);
+        Ok(tags)
+    }
+
+    /// Returns the newest block accepted on L1, from the genesis block up to `latest`.
+    async fn finalized_block<P: Provider + Send + Sync>(
+        &self,
+        kakarot_client: &dyn KakarotEthApi<P>,
+        latest: u64,
+    ) -> Result<Option<u64>, EthApiError<P::Error>> {
+        let is_finalized = |number: u64| async move {
+            let block =
+                kakarot_client.starknet_provider().get_block_with_tx_hashes(StarknetBlockId::Number(number)).await?;
+            Ok::<_, EthApiError<P::Error>>(matches!(
+                block,
+                MaybePendingBlockWithTxHashes::Block(block) if block.status == BlockStatus::AcceptedOnL1
+            ))
+        };
+
+        if is_finalized(latest).await? {
+            return Ok(Some(latest));
+        }
+        let genesis = kakarot_client.genesis_block_number();
+        let lower =
+            match self.tags().and_then(|tags| tags.finalized).filter(|finalized| (genesis..latest).contains(finalized))
+            {
+                Some(finalized) => finalized,
+                None => {
+                    if !is_finalized(genesis).await? {
+                        return Ok(None);
+                    }
+                    genesis
+                }
+            };
+
+        // The lowest block above `lower` which isn't accepted on L1 follows the finalized block.
+        let first_not_finalized = binary_search(lower, latest, |number| async move {
+            Ok::<_, EthApiError<P::Error>>(!is_finalized(number).await?)
+        })
+        .await?;
+        Ok(Some(first_not_finalized - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_block_tags() {
+        // Given
+        let tags = ResolvedBlockTags { safe: Some(10), finalized: None };
+
+        // When
+        let safe = tags.resolve(BlockNumberOrTag::Safe);
+        let finalized = tags.resolve(BlockNumberOrTag::Finalized);
+        let latest = tags.resolve(BlockNumberOrTag::Latest);
+
+        // Then
+        assert_eq!(Some(BlockNumberOrTag::Number(10)), safe);
+        assert_eq!(None, finalized);
+        assert_eq!(Some(BlockNumberOrTag::Latest), latest);
+    }
+
+    #[test]
+    fn test_safe_block_policy_from_vars() {
+        // Given
+        let vars = |policy: &str| HashMap::from([("KAKAROT_SAFE_BLOCK_POLICY".to_string(), policy.to_string())]);
+
+        // When
+        let finalized = SafeBlockPolicy::from_vars(&vars("finalized"));
+        let confirmations = SafeBlockPolicy::from_vars(&vars("12"));
+        let invalid = SafeBlockPolicy::from_vars(&vars("latest"));
+        let unset = SafeBlockPolicy::from_vars(&HashMap::new());
+
+        // Then
+        assert_eq!(SafeBlockPolicy::Finalized, finalized.unwrap());
+        assert_eq!(SafeBlockPolicy::Confirmations(12), confirmations.unwrap());
+        assert!(invalid.is_err());
+        assert_eq!(SafeBlockPolicy::default(), unset.unwrap());
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use reth_primitives::{Address, Bytes, H256, U256};
+use reth_rpc_types::RichBlock;
+use serde::Serialize;
+use starknet::core::types::{FieldElement, TransactionStatus};
+use tokio::sync::broadcast::error::RecvError;
+
+use super::constants::DEFAULT_CACHE_CAPACITY;
+use super::errors::ConfigError;
+use super::head_tracker::{ChainEvent, HeadTrackerHandle};
+use crate::models::block::ExecutedTransaction;
+use crate::models::receipt::KakarotTransactionReceipt;
+
+/// Hit and miss counts of a cache, along with its number of entries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+/// A cache holding at most `capacity` entries, evicting the least recently used entries first.
+#[derive(Debug)]
+pub struct BoundedCache<K, V> {
+    capacity: usize,
+    entries: Mutex<Entries<K, V>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug)]
+struct Entries<K, V> {
+    /// Values along with the tick of their last use.
+    values: HashMap<K, (V, u64)>,
+    /// Keys by the tick of their last use, from the least to the most recently used.
+    uses: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+impl<K: Clone, V> Entries<K, V> {
+    /// Marks the key, last used at `last_use`, as the most recently used and returns its tick.
+    fn touch(&mut self, key: &K, last_use: Option<u64>) -> u64 {
+        if let Some(last_use) = last_use {
+            self.uses.remove(&last_use);
+        }
+        self.tick += 1;
+        self.uses.insert(self.tick, key.clone());
+        self.tick
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> BoundedCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(Entries { values: HashMap::new(), uses: BTreeMap::new(), tick: 0 }),
+            hits: AtomicU64::default(),
+            misses: AtomicU64::default(),
+        }
+    }
+
+    /// Returns the cached value of the key, counting a hit or a miss.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().expect("cache lock poisoned");
+        let value = match entries.values.get(key).map(|(value, last_use)| (value.clone(), *last_use)) {
+            Some((value, last_use)) => {
+                let tick = entries.touch(key, Some(last_use));
+                if let Some((_, last_use)) = entries.values.get_mut(key) {
+                    *last_use = tick;
+                }
+                Some(value)
+            }
+            None => None,
+        };
+        let counter = if value.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        value
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().expect("cache lock poisoned");
+        let last_use = entries.values.get(&key).map(|(_, last_use)| *last_use);
+        let tick = entries.touch(&key, last_use);
+        entries.values.insert(key, (value, tick));
+        while entries.values.len() > self.capacity {
+            let Some((_, least_recently_used)) = entries.uses.pop_first() else { break };
+            entries.values.remove(&least_recently_used);
+        }
+    }
+
+    /// Keeps only the entries for which `keep` returns true.
+    pub fn retain(&self, mut keep: impl FnMut(&K, &V) -> bool) {
+        let mut entries = self.entries.lock().expect("cache lock poisoned");
+        entries.values.retain(|key, (value, _)| keep(key, value));
+        let Entries { values, uses, .. } = &mut *entries;
+        uses.retain(|_, key| values.contains_key(key));
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.lock().expect("cache lock poisoned").values.len(),
+        }
+    }
+}
+
+/// Statistics of each cache of the [`StarknetCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StarknetCacheStats {
+    pub class_hashes: CacheStats,
+    pub evm_addresses: CacheStats,
+    pub starknet_addresses: CacheStats,
+    pub blocks: CacheStats,
+    pub receipts: CacheStats,
+    pub block_receipts: CacheStats,
+    pub bytecodes: CacheStats,
+}
+
+/// Size-bounded caches of the Starknet lookups which never change, or only change on reorgs.
+///
+/// Blocks and receipts are cached at non-pending heights only, receipts once their transaction is
+/// accepted on L1 as their status doesn't change anymore, except for the receipts of all the
+/// Kakarot transactions of a block, which are computed together and whose status is refreshed on
+/// lookup. The hashes of the blocks they belong to are recorded, so that they are invalidated from
+/// the height of the reorgs reported by the head tracker, along with the bytecodes read from that
+/// height, whose deployment might have been reorged out.
+#[derive(Debug)]
+pub struct StarknetCache {
+    class_hashes: BoundedCache<FieldElement, FieldElement>,
+    evm_addresses: BoundedCache<FieldElement, Address>,
+    starknet_addresses: BoundedCache<Address, FieldElement>,
+    blocks: BoundedCache<(u64, bool), RichBlock>,
+    receipts: BoundedCache<H256, KakarotTransactionReceipt>,
+    /// Kakarot transactions of blocks along with their receipts, by block hash, along with the
+    /// number of the block.
+    block_receipts: BoundedCache<H256, (Arc<Vec<ExecutedTransaction>>, u64)>,
+    /// Bytecodes along with the number of the block they were read at.
+    bytecodes: BoundedCache<Address, (Bytes, u64)>,
+    capacity: usize,
+    block_hashes: Mutex<BTreeMap<u64, H256>>,
+}
+
+impl Default for StarknetCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_CAPACITY)
+    }
+}
+
+impl StarknetCache {
+    /// Creates the caches, each holding at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            class_hashes: BoundedCache::new(capacity),
+            evm_addresses: BoundedCache::new(capacity),
+            starknet_addresses: BoundedCache::new(capacity),
+            blocks: BoundedCache::new(capacity),
+            receipts: BoundedCache::new(capacity),
+            block_receipts: BoundedCache::new(capacity),
+            bytecodes: BoundedCache::new(capacity),
+            capacity,
+            block_hashes: Mutex::default(),
+        }
+    }
+
+    /// Create a new `StarknetCache` from `KAKAROT_CACHE_CAPACITY`, falling back to the default
+    /// capacity.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        match std::env::var("KAKAROT_CACHE_CAPACITY") {
+            Ok(capacity) => Ok(Self::new(capacity.parse().map_err(|_| {
+                ConfigError::EnvironmentVariableSetWrong(format!(
+                    "KAKAROT_CACHE_CAPACITY should be a number of entries, got {capacity}"
+                ))
+            })?)),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn class_hash(&self, starknet_address: &FieldElement) -> Option<FieldElement> {
+        self.class_hashes.get(starknet_address)
+    }
+
+    pub fn insert_class_hash(&self, starknet_address: FieldElement, class_hash: FieldElement) {
+        self.class_hashes.insert(starknet_address, class_hash);
+    }
+
+    pub fn evm_address(&self, starknet_address: &FieldElement) -> Option<Address> {
+        self.evm_addresses.get(starknet_address)
+    }
+
+    pub fn starknet_address(&self, evm_address: &Address) -> Option<FieldElement> {
+        self.starknet_addresses.get(evm_address)
+    }
+
+    /// Records the Starknet address of the Kakarot account of the EVM address, in both directions.
+    pub fn insert_address_pair(&self, evm_address: Address, starknet_address: FieldElement) {
+        self.evm_addresses.insert(starknet_address, evm_address);
+        self.starknet_addresses.insert(evm_address, starknet_address);
+    }
+
+    pub fn block(&self, number: u64, hydrated_tx: bool) -> Option<RichBlock> {
+        self.blocks.get(&(number, hydrated_tx))
+    }
+
+    /// Caches the block, unless it is pending.
+    pub fn insert_block(&self, block: &RichBlock, hydrated_tx: bool) {
+        let (Some(number), Some(hash)) = (block.header.number, block.header.hash) else { return };
+        let Ok(number) = u64::try_from(number) else { return };
+        self.insert_at(number, hash, || self.blocks.insert((number, hydrated_tx), block.clone()));
+    }
+
+    pub fn receipt(&self, ethereum_hash: &H256) -> Option<KakarotTransactionReceipt> {
+        self.receipts.get(ethereum_hash)
+    }
+
+    /// Caches the receipt, if its transaction is accepted on L1.
+    pub fn insert_receipt(&self, ethereum_hash: H256, receipt: &KakarotTransactionReceipt) {
+        if receipt.starknet_status != Some(TransactionStatus::AcceptedOnL1) {
+            return;
+        }
+        let (Some(number), Some(hash)) = (receipt.inner.block_number, receipt.inner.block_hash) else { return };
+        let Ok(number) = u64::try_from(number) else { return };
+        self.insert_at(number, hash, || self.receipts.insert(ethereum_hash, receipt.clone()));
+    }
+
+    pub fn block_receipts(&self, block_hash: &H256) -> Option<Arc<Vec<ExecutedTransaction>>> {
+        self.block_receipts.get(block_hash).map(|(transactions, _)| transactions)
+    }
+
+    /// Caches the Kakarot transactions of the block with the given number and hash along with
+    /// their receipts.
+    pub fn insert_block_receipts(&self, number: u64, hash: H256, transactions: Arc<Vec<ExecutedTransaction>>) {
+        self.insert_at(number, hash, || self.block_receipts.insert(hash, (transactions, number)));
+    }
+
+    pub fn bytecode(&self, evm_address: &Address) -> Option<Bytes> {
+        self.bytecodes.get(evm_address).map(|(bytecode, _)| bytecode)
+    }
+
+    /// Caches the bytecode read at the block with the given number.
+    pub fn insert_bytecode(&self, evm_address: Address, bytecode: Bytes, block_number: u64) {
+        self.bytecodes.insert(evm_address, (bytecode, block_number));
+    }
+
+    pub fn stats(&self) -> StarknetCacheStats {
+        StarknetCacheStats {
+            class_hashes: self.class_hashes.stats(),
+            evm_addresses: self.evm_addresses.stats(),
+            starknet_addresses: self.starknet_addresses.stats(),
+            blocks: self.blocks.stats(),
+            receipts: self.receipts.stats(),
+            block_receipts: self.block_receipts.stats(),
+            bytecodes: self.bytecodes.stats(),
+        }
+    }
+
+    /// Records the hash of the block at the height and caches an entry of that block with
+    /// `insert`, after invalidating the cache from that height if it held another block there. The
+    /// check and the insertion happen under the same lock, so that an entry of another block at
+    /// that height inserted concurrently is invalidated as well.
+    fn insert_at(&self, number: u64, hash: H256, insert: impl FnOnce()) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut block_hashes = self.block_hashes.lock().expect("cache lock poisoned");
+        if block_hashes.get(&number).map_or(false, |recorded| *recorded != hash) {
+            self.invalidate_entries_from(&mut block_hashes, number);
+        }
+        block_hashes.insert(number, hash);
+        while block_hashes.len() > self.capacity {
+            block_hashes.pop_first();
+        }
+        insert();
+    }
+
+    /// Drops the blocks and receipts from the height, as well as the bytecodes read from there.
+    pub fn invalidate_from(&self, number: u64) {
+        let mut block_hashes = self.block_hashes.lock().expect("cache lock poisoned");
+        self.invalidate_entries_from(&mut block_hashes, number);
+    }
+
+    fn invalidate_entries_from(&self, block_hashes: &mut BTreeMap<u64, H256>, number: u64) {
+        block_hashes.retain(|height, _| *height < number);
+        self.blocks.retain(|(height, _), _| *height < number);
+        self.receipts
+            .retain(|_, receipt| receipt.inner.block_number.map_or(false, |height| height < U256::from(number)));
+        self.block_receipts.retain(|_, (_, height)| *height < number);
+        self.bytecodes.retain(|_, (_, height)| *height < number);
+    }
+
+    /// Spawns the invalidation of the cache from the height of the reorgs reported by the head
+    /// tracker on the Tokio runtime. Missed events might hide a reorg, so the whole cache is
+    /// invalidated when the subscription lags behind.
+    pub fn spawn(cache: Arc<Self>, head_tracker: HeadTrackerHandle) -> tokio::task::JoinHandle<()> {
+        let mut events = head_tracker.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(ChainEvent::Reorg { dropped, .. }) => {
+                        if let Some(number) = dropped.iter().map(|block| block.number).min() {
+                            log::warn!("Invalidating the cache from block {number} after a reorg");
+                            cache.invalidate_from(number);
+                        }
+                    }
+                    Ok(_) => (),
+                    Err(RecvError::Lagged(_)) => {
+                        log::warn!("Invalidating the cache after missing chain events");
+                        cache.invalidate_from(0);
+                    }
+                    Err(RecvError::Closed) => return,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::helpers::create_default_transaction_receipt;
+
+    fn receipt(block_number: u64, block_hash: H256) -> KakarotTransactionReceipt {
+        let mut inner = create_default_transaction_receipt();
+        inner.block_number = Some(U256::from(block_number));
+        inner.block_hash = Some(block_hash);
+        KakarotTransactionReceipt { inner, starknet_status: Some(TransactionStatus::AcceptedOnL1), revert_data: None }
+    }
+
+    #[test]
+    fn test_bounded_cache_evicts_least_recently_used_entries() {
+        // Given
+        let cache = BoundedCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+
+        // When
+        cache.get(&1);
+        cache.insert(3, "three");
+
+        // Then
+        assert_eq!(Some("one"), cache.get(&1));
+        assert_eq!(None, cache.get(&2));
+        assert_eq!(Some("three"), cache.get(&3));
+        assert_eq!(CacheStats { hits: 3, misses: 1, entries: 2 }, cache.stats());
+    }
+
+    #[test]
+    fn test_receipts_are_invalidated_from_reorged_block() {
+        // Given
+        let cache = StarknetCache::new(10);
+        cache.insert_receipt(H256::from_low_u64_be(1), &receipt(1, H256::from_low_u64_be(0xb1)));
+        cache.insert_receipt(H256::from_low_u64_be(2), &receipt(2, H256::from_low_u64_be(0xb2)));
+        cache.insert_bytecode(Address::from_low_u64_be(1), Bytes::from(vec![0x60]), 1);
+        cache.insert_bytecode(Address::from_low_u64_be(2), Bytes::from(vec![0x60]), 2);
+
+        // When
+        cache.insert_receipt(H256::from_low_u64_be(3), &receipt(2, H256::from_low_u64_be(0xc2)));
+
+        // Then
+        assert!(cache.receipt(&H256::from_low_u64_be(1)).is_some());
+        assert!(cache.receipt(&H256::from_low_u64_be(2)).is_none());
+        assert!(cache.receipt(&H256::from_low_u64_be(3)).is_some());
+        assert!(cache.bytecode(&Address::from_low_u64_be(1)).is_some());
+        assert!(cache.bytecode(&Address::from_low_u64_be(2)).is_none());
+    }
+
+    #[test]
+    fn test_receipts_not_accepted_on_l1_are_not_cached() {
+        // Given
+        let cache = StarknetCache::new(10);
+        let mut receipt = receipt(1, H256::from_low_u64_be(0xb1));
+        receipt.starknet_status = Some(TransactionStatus::AcceptedOnL2);
+
+        // When
+        cache.insert_receipt(H256::from_low_u64_be(1), &receipt);
+
+        // Then
+        assert!(cache.receipt(&H256::from_low_u64_be(1)).is_none());
+    }
+}
@@ -4,7 +4,8 @@ use starknet::providers::jsonrpc::{HttpTransport, JsonRpcTransport};
 use starknet::providers::{JsonRpcClient, SequencerGatewayProvider};
 use url::Url;
 
-use super::constants::{KATANA_RPC_URL, MADARA_RPC_URL};
+use super::constants::gas::{DEFAULT_EVM_GAS_PER_STARKNET_GAS, DEFAULT_MAX_FEE_MULTIPLIER_PERCENT};
+use super::constants::{DEFAULT_BLOCK_GAS_LIMIT, KATANA_RPC_URL, MADARA_RPC_URL};
 use super::errors::ConfigError;
 
 fn get_env_var(name: &str) -> Result<String, ConfigError> {
@@ -48,7 +49,7 @@ impl Network {
     }
 }
 
-#[derive(Default, Clone)]
+#[derive(Clone)]
 /// Configuration for the Starknet RPC client.
 pub struct StarknetConfig {
     /// Starknet network.
@@ -57,11 +58,84 @@ pub struct StarknetConfig {
     pub kakarot_address: FieldElement,
     /// Proxy account class hash.
     pub proxy_account_class_hash: FieldElement,
+    /// Gas limit reported in the headers of the Kakarot blocks.
+    pub block_gas_limit: u64,
+    /// Account whose fee estimates give the gas price on the JSON-RPC networks.
+    pub gas_price_account: Option<FieldElement>,
+    /// EVM gas units one Starknet gas unit is worth in gas estimates.
+    pub evm_gas_per_starknet_gas: u64,
+    /// Percentage of the Starknet fee estimate set as the max fee of Kakarot transactions.
+    pub max_fee_multiplier_percent: u64,
+    /// Whether raw transactions are simulated before their submission, to reject the transactions
+    /// which revert.
+    pub preflight_simulation: bool,
+    /// Starknet block in which Kakarot was deployed, discovered from the chain when unset.
+    pub genesis_block: Option<u64>,
+}
+
+impl Default for StarknetConfig {
+    fn default() -> Self {
+        Self::new(Network::default(), FieldElement::ZERO, FieldElement::ZERO)
+    }
 }
 
 impl StarknetConfig {
     pub fn new(network: Network, kakarot_address: FieldElement, proxy_account_class_hash: FieldElement) -> Self {
-        StarknetConfig { network, kakarot_address, proxy_account_class_hash }
+        StarknetConfig {
+            network,
+            kakarot_address,
+            proxy_account_class_hash,
+            block_gas_limit: DEFAULT_BLOCK_GAS_LIMIT,
+            gas_price_account: None,
+            evm_gas_per_starknet_gas: DEFAULT_EVM_GAS_PER_STARKNET_GAS,
+            max_fee_multiplier_percent: DEFAULT_MAX_FEE_MULTIPLIER_PERCENT,
+            preflight_simulation: false,
+            genesis_block: None,
+        }
+    }
+
+    /// Sets the gas limit reported in the headers of the Kakarot blocks.
+    #[must_use]
+    pub fn with_block_gas_limit(mut self, block_gas_limit: u64) -> Self {
+        self.block_gas_limit = block_gas_limit;
+        self
+    }
+
+    /// Sets the EVM gas units one Starknet gas unit is worth in gas estimates.
+    #[must_use]
+    pub fn with_evm_gas_per_starknet_gas(mut self, evm_gas_per_starknet_gas: u64) -> Self {
+        self.evm_gas_per_starknet_gas = evm_gas_per_starknet_gas;
+        self
+    }
+
+    /// Sets the percentage of the Starknet fee estimate set as the max fee of Kakarot transactions.
+    #[must_use]
+    pub fn with_max_fee_multiplier_percent(mut self, max_fee_multiplier_percent: u64) -> Self {
+        self.max_fee_multiplier_percent = max_fee_multiplier_percent;
+        self
+    }
+
+    /// Simulates the raw transactions before their submission, to reject the transactions which
+    /// revert.
+    #[must_use]
+    pub fn with_preflight_simulation(mut self, preflight_simulation: bool) -> Self {
+        self.preflight_simulation = preflight_simulation;
+        self
+    }
+
+    /// Sets the Starknet block in which Kakarot was deployed, reported as the `earliest` block.
+    #[must_use]
+    pub fn with_genesis_block(mut self, genesis_block: u64) -> Self {
+        self.genesis_block = Some(genesis_block);
+        self
+    }
+
+    /// Sets the account whose fee estimates give the gas price on the JSON-RPC networks. The
+    /// account must accept an empty multicall.
+    #[must_use]
+    pub fn with_gas_price_account(mut self, gas_price_account: FieldElement) -> Self {
+        self.gas_price_account = Some(gas_price_account);
+        self
     }
 
     /// Create a new `StarknetConfig` from environment variables.
@@ -95,7 +169,72 @@ impl StarknetConfig {
             ))
         })?;
 
-        Ok(StarknetConfig::new(network, kakarot_address, proxy_account_class_hash))
+        let block_gas_limit = match std::env::var("KAKAROT_BLOCK_GAS_LIMIT") {
+            Ok(block_gas_limit) => block_gas_limit.parse().map_err(|_| {
+                ConfigError::EnvironmentVariableSetWrong(format!(
+                    "KAKAROT_BLOCK_GAS_LIMIT should be a gas amount, got {block_gas_limit}"
+                ))
+            })?,
+            Err(_) => DEFAULT_BLOCK_GAS_LIMIT,
+        };
+
+        let evm_gas_per_starknet_gas = match std::env::var("KAKAROT_EVM_GAS_PER_STARKNET_GAS") {
+            Ok(evm_gas_per_starknet_gas) => {
+                evm_gas_per_starknet_gas.parse().ok().filter(|gas: &u64| *gas > 0).ok_or_else(|| {
+                    ConfigError::EnvironmentVariableSetWrong(format!(
+                        "KAKAROT_EVM_GAS_PER_STARKNET_GAS should be a positive gas amount, got \
+                         {evm_gas_per_starknet_gas}"
+                    ))
+                })?
+            }
+            Err(_) => DEFAULT_EVM_GAS_PER_STARKNET_GAS,
+        };
+
+        let max_fee_multiplier_percent = match std::env::var("KAKAROT_MAX_FEE_MULTIPLIER_PERCENT") {
+            Ok(max_fee_multiplier_percent) => max_fee_multiplier_percent.parse().map_err(|_| {
+                ConfigError::EnvironmentVariableSetWrong(format!(
+                    "KAKAROT_MAX_FEE_MULTIPLIER_PERCENT should be a percentage, got {max_fee_multiplier_percent}"
+                ))
+            })?,
+            Err(_) => DEFAULT_MAX_FEE_MULTIPLIER_PERCENT,
+        };
+
+        let config = StarknetConfig::new(network, kakarot_address, proxy_account_class_hash)
+            .with_block_gas_limit(block_gas_limit)
+            .with_evm_gas_per_starknet_gas(evm_gas_per_starknet_gas)
+            .with_max_fee_multiplier_percent(max_fee_multiplier_percent);
+
+        let config = match std::env::var("KAKAROT_PREFLIGHT_SIMULATION") {
+            Ok(preflight_simulation) => {
+                config.with_preflight_simulation(preflight_simulation.parse().map_err(|_| {
+                    ConfigError::EnvironmentVariableSetWrong(format!(
+                        "KAKAROT_PREFLIGHT_SIMULATION should be true or false, got {preflight_simulation}"
+                    ))
+                })?)
+            }
+            Err(_) => config,
+        };
+
+        let config = match std::env::var("KAKAROT_GENESIS_BLOCK") {
+            Ok(genesis_block) => config.with_genesis_block(genesis_block.parse().map_err(|_| {
+                ConfigError::EnvironmentVariableSetWrong(format!(
+                    "KAKAROT_GENESIS_BLOCK should be a block number, got {genesis_block}"
+                ))
+            })?),
+            Err(_) => config,
+        };
+
+        match std::env::var("KAKAROT_GAS_PRICE_ACCOUNT_ADDRESS") {
+            Ok(gas_price_account) => {
+                let gas_price_account = FieldElement::from_hex_be(&gas_price_account).map_err(|_| {
+                    ConfigError::EnvironmentVariableSetWrong(format!(
+                        "KAKAROT_GAS_PRICE_ACCOUNT_ADDRESS should be provided as a hex string, got {gas_price_account}"
+                    ))
+                })?;
+                Ok(config.with_gas_price_account(gas_price_account))
+            }
+            Err(_) => Ok(config),
+        }
     }
 }
 
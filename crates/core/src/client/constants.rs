@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use lazy_static::lazy_static;
 use reth_primitives::{H256, H64, U128, U256, U8};
 use starknet::accounts::Call as StarknetCall;
@@ -17,6 +19,58 @@ pub const MADARA_RPC_URL: &str = "http://127.0.0.1:9944";
 
 pub const KATANA_RPC_URL: &str = "http://0.0.0.0:5050";
 
+/// The gas limit reported in the headers of the Kakarot blocks, unless configured otherwise.
+pub const DEFAULT_BLOCK_GAS_LIMIT: u64 = 30_000_000;
+
+/// The number of events requested per `starknet_getEvents` page.
+pub const EVENTS_CHUNK_SIZE: u64 = 1000;
+
+/// The maximum number of blocks of the range of an `eth_getLogs` query.
+pub const MAX_LOGS_BLOCK_RANGE: u64 = 10_000;
+
+/// The maximum number of logs returned by an `eth_getLogs` query.
+pub const MAX_LOGS: usize = 10_000;
+
+/// The interval between two polls of the chain head by the head tracker.
+pub const HEAD_TRACKER_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The number of recent blocks kept by the head tracker to detect reorgs.
+pub const HEAD_TRACKER_WINDOW_SIZE: usize = 64;
+
+/// The number of chain events buffered for each receiver of the head tracker.
+pub const CHAIN_EVENTS_CHANNEL_SIZE: usize = 1024;
+
+/// The interval between two synchronizations of the indexer with the chain.
+pub const INDEXER_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The interval between two promotions of the queued transactions of the transaction pool.
+pub const TXPOOL_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The interval between two polls of the receipt of a transaction sent with
+/// `eth_sendRawTransactionSync`.
+pub const RECEIPT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The interval between two polls of the Starknet status of the tracked submissions.
+pub const SUBMISSION_TRACKER_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The maximum number of rebroadcasts of a submission which Starknet doesn't know about.
+pub const MAX_REBROADCASTS: u32 = 3;
+
+/// The duration after which a submission which Starknet doesn't know about is rebroadcast.
+pub const REBROADCAST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The duration during which the lifecycle of a submission is tracked.
+pub const SUBMISSION_RETENTION: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// The interval between two resolutions of the `safe` and `finalized` block tags.
+pub const BLOCK_TAGS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The number of blocks on top of the `safe` block by default, making it the latest block.
+pub const DEFAULT_SAFE_BLOCK_CONFIRMATIONS: u64 = 0;
+
+/// The number of entries of each Starknet lookup cache, unless configured otherwise.
+pub const DEFAULT_CACHE_CAPACITY: usize = 10_000;
+
 pub mod selectors {
     use starknet::core::types::FieldElement;
     use starknet::macros::selector;
@@ -33,6 +87,7 @@ pub mod selectors {
     pub const BALANCE_OF: FieldElement = selector!("balanceOf");
 
     pub const EVM_CONTRACT_DEPLOYED: FieldElement = selector!("evm_contract_deployed");
+    pub const TRANSACTION_EXECUTED: FieldElement = selector!("transaction_executed");
 }
 
 /// This module contains constants related to EVM gas fees.
@@ -61,6 +116,16 @@ pub mod gas {
     /// is used if the returned fee estimate is lower, otherwise wallets such as Metamask will not
     /// allow the transaction to be sent.
     pub const MINIMUM_GAS_FEE: u64 = 21000;
+
+    /// The EVM gas units one Starknet gas unit is worth, unless configured otherwise.
+    ///
+    /// The gas price reported by `eth_gasPrice` is the Starknet gas price, so that with a one to
+    /// one conversion the fee shown by wallets, EVM gas times gas price, is the Starknet fee.
+    pub const DEFAULT_EVM_GAS_PER_STARKNET_GAS: u64 = 1;
+
+    /// The percentage of the Starknet fee estimate set as the max fee of the Starknet transactions
+    /// wrapping Kakarot transactions, unless configured otherwise.
+    pub const DEFAULT_MAX_FEE_MULTIPLIER_PERCENT: u64 = 150;
 }
 
 /// This module contains error messages related to Kakarot.
@@ -72,13 +137,9 @@ pub mod error_messages {
 // This module contains constants which are being used in place of real data that should be fetched
 // in production.
 lazy_static! {
-    pub static ref GAS_LIMIT: U256 = U256::from(1_000_000u64);
     pub static ref GAS_USED: U256 = U256::from(500_000u64);
     pub static ref CUMULATIVE_GAS_USED: U256 = U256::from(1_000_000u64);
     pub static ref EFFECTIVE_GAS_PRICE: U128 = U128::from(1_000_000u64);
-    pub static ref SIZE: Option<U256> = Some(U256::from(1_000_000u64));
-    pub static ref MAX_FEE: FieldElement = FieldElement::from(100_000_000_000_000_000u64);
-    pub static ref ESTIMATE_GAS: U256 = U256::from(100_000_000_000_000_000u64);
     pub static ref TRANSACTION_TYPE: U8 = U8::from(0);
     pub static ref NONCE: Option<H64> = Some(H64::zero());
     pub static ref MIX_HASH: H256 = H256::zero();
@@ -1,11 +1,15 @@
 use jsonrpsee::types::error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE, SERVER_IS_BUSY_CODE, UNKNOWN_ERROR_CODE};
 use jsonrpsee::types::ErrorObject;
+use reth_primitives::{Bytes, H256};
 use starknet::core::types::StarknetError;
 use starknet::providers::ProviderError;
 use thiserror::Error;
 
 use super::helpers::DataDecodingError;
+use super::validation::ValidationError;
+use crate::indexer::store::IndexerError;
 use crate::models::ConversionError;
+use crate::txpool::PoolError;
 
 /// List of JSON-RPC error codes from reth
 #[derive(Debug, Copy, PartialEq, Eq, Clone)]
@@ -20,6 +24,11 @@ pub enum EthRpcErrorCode {
     ResourceNotFound = -32001,
     /// Failed to send transaction, See also <https://github.com/MetaMask/eth-rpc-errors/blob/main/src/error-constants.ts>
     TransactionRejected = -32003,
+    /// Request exceeding a limit of the node, <https://eips.ethereum.org/EIPS/eip-1474>
+    LimitExceeded = -32005,
+    /// Transaction sent by `eth_sendRawTransactionSync` but not included within the timeout, the
+    /// hash of the transaction being the data of the error.
+    TransactionTimeout = 4,
 }
 
 // Error that can accure when preparing configuration.
@@ -57,12 +66,34 @@ pub enum EthApiError<E: std::error::Error> {
     /// Feeder gateway error.
     #[error("Feeder gateway error: {0}")]
     FeederGatewayError(String),
+    /// EVM execution reverted, with the revert data.
+    #[error("execution reverted")]
+    ExecutionReverted(Bytes),
+    /// Transaction rejected before its submission to Starknet.
+    #[error("transaction rejected: {0}")]
+    TransactionRejected(String),
+    /// Transaction submitted but not included within the timeout, with its hash.
+    #[error("transaction {0:?} was submitted but not included within the timeout")]
+    TransactionTimeout(H256),
+    /// Block number preceding the deployment of Kakarot, with the number of the Kakarot genesis
+    /// block.
+    #[error("block {0} precedes the Kakarot genesis block {1}")]
+    BlockBeforeGenesis(u64, u64),
+    /// No block qualifies for the `safe` or `finalized` tag yet.
+    #[error("{0} block not found")]
+    UnknownBlockTag(String),
+    /// Query exceeding a limit of the RPC, such as the block range of `eth_getLogs`.
+    #[error("{0}")]
+    LimitExceeded(String),
     /// Missing parameter error.
     #[error("Missing parameter: {0}")]
     MissingParameterError(String),
     /// Configuration error.
     #[error(transparent)]
     ConfigError(#[from] ConfigError),
+    /// Local index error.
+    #[error(transparent)]
+    IndexerError(#[from] IndexerError),
     /// Other error.
     #[error(transparent)]
     Other(#[from] anyhow::Error),
@@ -74,6 +105,18 @@ impl<T, E: std::error::Error> From<ConversionError<T>> for EthApiError<E> {
     }
 }
 
+impl<E: std::error::Error> From<ValidationError> for EthApiError<E> {
+    fn from(err: ValidationError) -> Self {
+        Self::TransactionRejected(err.to_string())
+    }
+}
+
+impl<E: std::error::Error> From<PoolError> for EthApiError<E> {
+    fn from(err: PoolError) -> Self {
+        Self::TransactionRejected(err.to_string())
+    }
+}
+
 impl<E: std::error::Error> From<EthApiError<E>> for ErrorObject<'static> {
     fn from(error: EthApiError<E>) -> Self {
         match error {
@@ -109,8 +152,22 @@ impl<E: std::error::Error> From<EthApiError<E>> for ErrorObject<'static> {
             EthApiError::DataDecodingError(err) => rpc_err(INTERNAL_ERROR_CODE, err.to_string()),
             EthApiError::KakarotDataFilteringError(err) => rpc_err(INTERNAL_ERROR_CODE, err),
             EthApiError::FeederGatewayError(err) => rpc_err(INTERNAL_ERROR_CODE, err),
+            EthApiError::ExecutionReverted(data) => {
+                ErrorObject::owned(EthRpcErrorCode::ExecutionError as i32, "execution reverted", Some(data))
+            }
+            EthApiError::TransactionRejected(err) => rpc_err(EthRpcErrorCode::TransactionRejected as i32, err),
+            EthApiError::TransactionTimeout(hash) => ErrorObject::owned(
+                EthRpcErrorCode::TransactionTimeout as i32,
+                "transaction was submitted but not included within the timeout",
+                Some(hash),
+            ),
+            EthApiError::BlockBeforeGenesis(..) | EthApiError::UnknownBlockTag(_) => {
+                rpc_err(EthRpcErrorCode::ResourceNotFound as i32, error.to_string())
+            }
+            EthApiError::LimitExceeded(err) => rpc_err(EthRpcErrorCode::LimitExceeded as i32, err),
             EthApiError::MissingParameterError(err) => rpc_err(INVALID_PARAMS_CODE, err),
             EthApiError::ConfigError(err) => rpc_err(INTERNAL_ERROR_CODE, err.to_string()),
+            EthApiError::IndexerError(err) => rpc_err(INTERNAL_ERROR_CODE, err.to_string()),
             EthApiError::Other(err) => rpc_err(INTERNAL_ERROR_CODE, err.to_string()),
         }
     }
@@ -0,0 +1,334 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+use reth_primitives::{BlockNumberOrTag, H256};
+use reth_rpc_types::{Filter, FilterBlockOption, Log, RichBlock};
+use starknet::core::types::{BlockId as StarknetBlockId, BlockTag, MaybePendingBlockWithTxHashes, Transaction};
+use starknet::providers::Provider;
+use tokio::sync::broadcast;
+
+use super::api::KakarotEthApi;
+use super::constants::{CHAIN_EVENTS_CHANNEL_SIZE, HEAD_TRACKER_POLL_INTERVAL, HEAD_TRACKER_WINDOW_SIZE};
+use super::errors::EthApiError;
+use crate::models::block::BlockWithTxs;
+use crate::models::felt::Felt252Wrapper;
+use crate::models::transaction::StarknetTransaction;
+
+/// Number and hash of a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockRef {
+    pub number: u64,
+    pub hash: H256,
+}
+
+/// Events published by the [`HeadTracker`].
+#[derive(Debug, Clone)]
+pub enum ChainEvent {
+    /// A block was appended to the canonical chain, along with the logs it contains.
+    NewBlock { block: Arc<RichBlock>, logs: Arc<Vec<Log>> },
+    /// Blocks were dropped from the canonical chain, from the highest to the lowest. The logs they
+    /// contained are re-emitted with `removed: true`.
+    Reorg { dropped: Vec<BlockRef>, removed_logs: Arc<Vec<Log>> },
+    /// Kakarot transactions entered the pending block.
+    PendingChanged { new_transactions: Arc<Vec<H256>> },
+}
+
+/// A block of the tracked window, with the logs it contains.
+#[derive(Debug, Clone)]
+struct TrackedBlock {
+    block: BlockRef,
+    parent_hash: H256,
+    logs: Arc<Vec<Log>>,
+}
+
+/// The most recent blocks of the canonical chain, as seen by the [`HeadTracker`].
+#[derive(Debug)]
+struct BlockWindow {
+    blocks: VecDeque<TrackedBlock>,
+    capacity: usize,
+}
+
+impl BlockWindow {
+    fn new(capacity: usize) -> Self {
+        Self { blocks: VecDeque::with_capacity(capacity), capacity }
+    }
+
+    fn tip(&self) -> Option<&TrackedBlock> {
+        self.blocks.back()
+    }
+
+    /// Returns true if a block with the given parent hash extends the tip of the window.
+    fn extends(&self, parent_hash: H256) -> bool {
+        self.tip().map_or(true, |tip| tip.block.hash == parent_hash)
+    }
+
+    /// Appends a block, evicting the oldest block if the window is full.
+    fn push(&mut self, block: TrackedBlock) {
+        if self.blocks.len() == self.capacity {
+            self.blocks.pop_front();
+        }
+        self.blocks.push_back(block);
+    }
+
+    fn pop(&mut self) -> Option<TrackedBlock> {
+        self.blocks.pop_back()
+    }
+
+    fn clear(&mut self) {
+        self.blocks.clear();
+    }
+}
+
+/// Handle on a running [`HeadTracker`], used to subscribe to its events.
+#[derive(Debug, Clone)]
+pub struct HeadTrackerHandle {
+    events: broadcast::Sender<ChainEvent>,
+}
+
+impl HeadTrackerHandle {
+    /// Returns a receiver of the chain events published from now on. A receiver lagging more
+    /// than `CHAIN_EVENTS_CHANNEL_SIZE` events behind misses the oldest ones.
+    pub fn subscribe(&self) -> broadcast::Receiver<ChainEvent> {
+        self.events.subscribe()
+    }
+}
+
+/// Follows the head of the Starknet chain and publishes [`ChainEvent`]s.
+///
+/// The tracker keeps a window of the most recent blocks. A reorg is detected when a new block
+/// doesn't extend the tip of the window, or when the block at the height of the tip changes. The
+/// window is then rewound until it matches the canonical chain again. Polling is skipped while
+/// nobody listens to the events. The cache and the filters listen for as long as the server runs,
+/// so a poll only reads the hashes of the pending transactions, and new blocks are converted once.
+pub struct HeadTracker<P: Provider + Send + Sync> {
+    kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    events: broadcast::Sender<ChainEvent>,
+    window: BlockWindow,
+    pending_transactions: Option<HashSet<H256>>,
+}
+
+impl<P: Provider + Send + Sync + 'static> HeadTracker<P> {
+    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>) -> Self {
+        let (events, _) = broadcast::channel(CHAIN_EVENTS_CHANNEL_SIZE);
+        Self { kakarot_client, events, window: BlockWindow::new(HEAD_TRACKER_WINDOW_SIZE), pending_transactions: None }
+    }
+
+    pub fn handle(&self) -> HeadTrackerHandle {
+        HeadTrackerHandle { events: self.events.clone() }
+    }
+
+    /// Spawns the tracker on the Tokio runtime. Subscriptions go through the handles taken
+    /// beforehand.
+    pub fn spawn(mut self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(HEAD_TRACKER_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                if self.events.receiver_count() == 0 {
+                    self.window.clear();
+                    self.pending_transactions = None;
+                    continue;
+                }
+                if let Err(err) = self.poll().await {
+                    log::error!("Failed to poll the chain head: {err}");
+                }
+            }
+        })
+    }
+
+    /// Polls the chain head and the pending block, publishing the resulting events.
+    pub async fn poll(&mut self) -> Result<(), EthApiError<P::Error>> {
+        self.poll_blocks().await?;
+        self.poll_pending_transactions().await
+    }
+
+    async fn poll_blocks(&mut self) -> Result<(), EthApiError<P::Error>> {
+        let latest = self.kakarot_client.block_number().await?.as_u64();
+
+        loop {
+            let Some(tip) = self.window.tip().map(|tip| tip.block) else {
+                // Nothing is tracked yet: the current head is the starting point.
+                let (block, _) = self.fetch_block(latest).await?;
+                self.window.push(block);
+                return Ok(());
+            };
+
+            if tip.number >= latest {
+                // No new block: make sure the tip is still canonical.
+                if tip.number == latest && self.fetch_block_hash(latest).await? == tip.hash {
+                    return Ok(());
+                }
+                if !self.rewind(latest).await? {
+                    return Ok(());
+                }
+                continue;
+            }
+
+            let (block, rich_block) = self.fetch_block(tip.number + 1).await?;
+            if !self.window.extends(block.parent_hash) {
+                if !self.rewind(latest).await? {
+                    return Ok(());
+                }
+                continue;
+            }
+            let logs = block.logs.clone();
+            self.window.push(block);
+            // Sending only fails when there are no receivers left, in which case the event is dropped.
+            let _ = self.events.send(ChainEvent::NewBlock { block: Arc::new(rich_block), logs });
+        }
+    }
+
+    /// Drops the blocks of the window which are no longer canonical and publishes a reorg event.
+    /// Returns false if the window already matched the canonical chain.
+    async fn rewind(&mut self, latest: u64) -> Result<bool, EthApiError<P::Error>> {
+        let mut dropped = vec![];
+
+        while let Some(tip) = self.window.tip().map(|tip| tip.block) {
+            if tip.number <= latest && self.fetch_block_hash(tip.number).await? == tip.hash {
+                break;
+            }
+            dropped.extend(self.window.pop());
+        }
+
+        if dropped.is_empty() {
+            return Ok(false);
+        }
+
+        log::warn!("Reorg detected, dropped {} blocks", dropped.len());
+        let removed_logs = removed_logs(&dropped);
+        let dropped = dropped.into_iter().map(|block| block.block).collect();
+        let _ = self.events.send(ChainEvent::Reorg { dropped, removed_logs: Arc::new(removed_logs) });
+        Ok(true)
+    }
+
+    async fn poll_pending_transactions(&mut self) -> Result<(), EthApiError<P::Error>> {
+        let pending = pending_transaction_hashes(&self.kakarot_client).await?;
+        // On the first poll, the transactions already in the pending block are considered as seen.
+        let Some(previous) = self.pending_transactions.replace(pending.clone()) else { return Ok(()) };
+
+        let new_transactions: Vec<H256> = pending.into_iter().filter(|hash| !previous.contains(hash)).collect();
+        if !new_transactions.is_empty() {
+            let _ = self.events.send(ChainEvent::PendingChanged { new_transactions: Arc::new(new_transactions) });
+        }
+        Ok(())
+    }
+
+    /// Fetches the hash of the canonical block at the height from Starknet, bypassing the caches
+    /// which the reorg events invalidate.
+    async fn fetch_block_hash(&self, number: u64) -> Result<H256, EthApiError<P::Error>> {
+        let block =
+            self.kakarot_client.starknet_provider().get_block_with_tx_hashes(StarknetBlockId::Number(number)).await?;
+        match block {
+            MaybePendingBlockWithTxHashes::Block(block) => Ok(Felt252Wrapper::from(block.block_hash).into()),
+            MaybePendingBlockWithTxHashes::PendingBlock(_) => Ok(H256::zero()),
+        }
+    }
+
+    /// Fetches a block along with its logs. The header of the block, published to the `newHeads`
+    /// subscribers, carries the gas used and the logs bloom derived from the receipts of the block.
+    async fn fetch_block(&self, number: u64) -> Result<(TrackedBlock, RichBlock), EthApiError<P::Error>> {
+        let block =
+            self.kakarot_client.get_eth_block_from_starknet_block(StarknetBlockId::Number(number), false).await?;
+        let hash = block.inner.header.hash.unwrap_or_default();
+        let parent_hash = block.inner.header.parent_hash;
+
+        let filter = Filter {
+            block_option: FilterBlockOption::Range {
+                from_block: Some(BlockNumberOrTag::Number(number)),
+                to_block: Some(BlockNumberOrTag::Number(number)),
+            },
+            ..Default::default()
+        };
+        let logs = self.kakarot_client.get_logs(filter).await?;
+
+        let tracked = TrackedBlock { block: BlockRef { number, hash }, parent_hash, logs: Arc::new(logs) };
+        Ok((tracked, block))
+    }
+}
+
+/// Returns the logs of the dropped blocks, flagged as removed.
+fn removed_logs(dropped: &[TrackedBlock]) -> Vec<Log> {
+    dropped
+        .iter()
+        .flat_map(|block| block.logs.iter().cloned())
+        .map(|mut log| {
+            log.removed = true;
+            log
+        })
+        .collect()
+}
+
+/// Returns the hashes of the Kakarot transactions in the pending block. The hashes are decoded from
+/// the Starknet transactions of the block, without converting the block, which would compute the
+/// receipts of its transactions.
+pub async fn pending_transaction_hashes<P: Provider + Send + Sync>(
+    kakarot_client: &Arc<dyn KakarotEthApi<P>>,
+) -> Result<HashSet<H256>, EthApiError<P::Error>> {
+    let block = kakarot_client.starknet_provider().get_block_with_txs(StarknetBlockId::Tag(BlockTag::Pending)).await?;
+    let transactions: Vec<Transaction> =
+        kakarot_client.filter_kakarot_transactions(BlockWithTxs::new(block).transactions().into()).await?.into();
+
+    let mut hashes = HashSet::new();
+    for transaction in transactions {
+        hashes.extend(StarknetTransaction::from(transaction).ethereum_transaction_hashes(kakarot_client.as_ref())?);
+    }
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracked_block(number: u64, parent_hash: H256, logs: Vec<Log>) -> TrackedBlock {
+        TrackedBlock {
+            block: BlockRef { number, hash: H256::from_low_u64_be(number) },
+            parent_hash,
+            logs: Arc::new(logs),
+        }
+    }
+
+    #[test]
+    fn test_window_evicts_oldest_block() {
+        // Given
+        let mut window = BlockWindow::new(2);
+
+        // When
+        for number in 1..=3 {
+            window.push(tracked_block(number, H256::from_low_u64_be(number - 1), vec![]));
+        }
+
+        // Then
+        assert_eq!(2, window.blocks.len());
+        assert_eq!(2, window.blocks.front().unwrap().block.number);
+        assert_eq!(3, window.tip().unwrap().block.number);
+    }
+
+    #[test]
+    fn test_window_extends() {
+        // Given
+        let mut window = BlockWindow::new(2);
+        assert!(window.extends(H256::zero()));
+
+        // When
+        window.push(tracked_block(1, H256::zero(), vec![]));
+
+        // Then
+        assert!(window.extends(H256::from_low_u64_be(1)));
+        assert!(!window.extends(H256::from_low_u64_be(2)));
+    }
+
+    #[test]
+    fn test_removed_logs() {
+        // Given
+        let log: Log =
+            serde_json::from_str(include_str!("../models/test_data/conversion/eth/event_log3.json")).unwrap();
+        let dropped = vec![tracked_block(2, H256::from_low_u64_be(1), vec![log.clone(), log.clone()])];
+
+        // When
+        let removed = removed_logs(&dropped);
+
+        // Then
+        assert_eq!(2, removed.len());
+        assert!(removed.iter().all(|removed_log| removed_log.removed && removed_log.address == log.address));
+    }
+}
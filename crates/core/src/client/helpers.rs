@@ -1,7 +1,10 @@
+use std::future::Future;
+use std::time::Duration;
+
 use eyre::Result;
-use reth_primitives::{Bloom, Bytes, H160};
+use reth_primitives::{keccak256, Bloom, Bytes, Transaction, TxEip1559, TxEip2930, TxLegacy, H160, U256};
 use reth_rlp::DecodeError;
-use reth_rpc_types::TransactionReceipt;
+use reth_rpc_types::{Log, TransactionReceipt};
 use starknet::core::types::{
     FieldElement, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs, ValueOutOfRangeError,
 };
@@ -104,21 +107,175 @@ pub fn create_default_transaction_receipt() -> TransactionReceipt {
     }
 }
 
+/// Returns the 2048-bit bloom filter of the logs, accruing the address and the topics of each log.
+#[must_use]
+pub fn logs_bloom(logs: &[Log]) -> Bloom {
+    let mut bloom = Bloom::default();
+    for log in logs {
+        accrue_bloom(&mut bloom, log.address.as_bytes());
+        for topic in &log.topics {
+            accrue_bloom(&mut bloom, topic.as_bytes());
+        }
+    }
+    bloom
+}
+
+/// Sets the three bits of the bloom selected by the keccak256 hash of the input, as specified in
+/// the Ethereum yellow paper.
+fn accrue_bloom(bloom: &mut Bloom, input: &[u8]) {
+    let hash = keccak256(input);
+    for i in [0, 2, 4] {
+        let bit = ((usize::from(hash[i]) << 8) | usize::from(hash[i + 1])) & 2047;
+        bloom.0[255 - bit / 8] |= 1 << (bit % 8);
+    }
+}
+
+/// Returns the EVM gas used by a Kakarot transaction, derived from the actual fee paid on Starknet
+/// and the Starknet gas price of its block, one Starknet gas unit being worth
+/// `evm_gas_per_starknet_gas` EVM gas units. A zero gas price returns no gas.
+#[must_use]
+pub fn gas_used(actual_fee: U256, starknet_gas_price: U256, evm_gas_per_starknet_gas: u64) -> U256 {
+    if starknet_gas_price == U256::ZERO {
+        return U256::ZERO;
+    }
+    actual_fee / starknet_gas_price * U256::from(evm_gas_per_starknet_gas)
+}
+
+/// Splits the gas used by a Kakarot multicall evenly between its `calls_len` calls, the last call
+/// being charged the remainder.
+#[must_use]
+pub fn split_gas_used(gas_used: U256, calls_len: usize) -> Vec<U256> {
+    let Some(last) = calls_len.checked_sub(1) else { return vec![] };
+    let share = gas_used / U256::from(calls_len);
+    let mut shares = vec![share; last];
+    shares.push(gas_used - share * U256::from(last));
+    shares
+}
+
+/// Returns the EVM gas worth the Starknet gas consumed by a Starknet transaction, one Starknet gas
+/// unit being worth `evm_gas_per_starknet_gas` EVM gas units.
+#[must_use]
+pub fn starknet_gas_to_evm_gas(starknet_gas: u64, evm_gas_per_starknet_gas: u64) -> U256 {
+    U256::from(starknet_gas) * U256::from(evm_gas_per_starknet_gas)
+}
+
+/// Returns the price of an EVM gas unit worth the Starknet gas price, one Starknet gas unit being
+/// worth `evm_gas_per_starknet_gas` EVM gas units.
+#[must_use]
+pub fn starknet_gas_price_to_evm_gas_price(starknet_gas_price: U256, evm_gas_per_starknet_gas: u64) -> U256 {
+    starknet_gas_price / U256::from(evm_gas_per_starknet_gas.max(1))
+}
+
+/// Returns the lowest value in `(lower, upper]` for which `holds` holds, assuming that it holds for
+/// `upper` and that it never fails for a value above one for which it holds, e.g. the lowest
+/// successful gas limit.
+pub async fn binary_search<F, Fut, E>(mut lower: u64, mut upper: u64, mut holds: F) -> Result<u64, E>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<bool, E>>,
+{
+    while upper - lower > 1 {
+        let middle = lower + (upper - lower) / 2;
+        if holds(middle).await? {
+            upper = middle;
+        } else {
+            lower = middle;
+        }
+    }
+    Ok(upper)
+}
+
+/// Calls `poll` every `interval` until it returns a value, e.g. the receipt of a transaction, or
+/// returns `None` once `timeout` has elapsed.
+pub async fn poll_until<F, Fut, T, E>(interval: Duration, timeout: Duration, mut poll: F) -> Result<Option<T>, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<T>, E>>,
+{
+    let polling = async {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            if let Some(result) = poll().await.transpose() {
+                return result;
+            }
+        }
+    };
+
+    match tokio::time::timeout(timeout, polling).await {
+        Ok(result) => result.map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Returns the fee the Ethereum transaction allows, its gas limit times its max fee per gas.
+#[must_use]
+pub fn evm_fee_cap(transaction: &Transaction) -> U256 {
+    let (gas_limit, max_fee_per_gas) = match transaction {
+        Transaction::Legacy(TxLegacy { gas_limit, gas_price, .. })
+        | Transaction::Eip2930(TxEip2930 { gas_limit, gas_price, .. }) => (gas_limit, gas_price),
+        Transaction::Eip1559(TxEip1559 { gas_limit, max_fee_per_gas, .. }) => (gas_limit, max_fee_per_gas),
+    };
+    U256::from(*gas_limit) * U256::from(*max_fee_per_gas)
+}
+
+/// Returns the Starknet fee the Ethereum transaction allows: the Starknet gas its gas limit is
+/// worth, times the Starknet gas price its max fee per gas is worth, one Starknet gas unit being
+/// worth `evm_gas_per_starknet_gas` EVM gas units.
+#[must_use]
+pub fn starknet_fee_cap(transaction: &Transaction, evm_gas_per_starknet_gas: u64) -> U256 {
+    let evm_gas_per_starknet_gas = evm_gas_per_starknet_gas.max(1);
+    let (gas_limit, max_fee_per_gas) = match transaction {
+        Transaction::Legacy(TxLegacy { gas_limit, gas_price, .. })
+        | Transaction::Eip2930(TxEip2930 { gas_limit, gas_price, .. }) => (gas_limit, gas_price),
+        Transaction::Eip1559(TxEip1559 { gas_limit, max_fee_per_gas, .. }) => (gas_limit, max_fee_per_gas),
+    };
+    U256::from(gas_limit / evm_gas_per_starknet_gas)
+        * (U256::from(*max_fee_per_gas) * U256::from(evm_gas_per_starknet_gas))
+}
+
+/// Returns the max fee of the Starknet transaction wrapping Kakarot transactions: the Starknet
+/// fee estimate increased by `multiplier_percent`, capped by the Starknet fee the Ethereum
+/// transactions allow. Returns `None` when the cap doesn't cover the estimate.
+#[must_use]
+pub fn max_fee(fee_estimate: U256, multiplier_percent: u64, starknet_fee_cap: U256) -> Option<U256> {
+    if starknet_fee_cap < fee_estimate {
+        return None;
+    }
+    let max_fee = fee_estimate * U256::from(multiplier_percent) / U256::from(100);
+    Some(max_fee.min(starknet_fee_cap).max(fee_estimate))
+}
+
 pub fn bytes_to_felt_vec(bytes: &Bytes) -> Vec<FieldElement> {
     bytes.to_vec().into_iter().map(FieldElement::from).collect()
 }
 
 /// Constructs the calldata for a raw Starknet invoke transaction call
-pub fn raw_kakarot_calldata(kakarot_address: FieldElement, mut calldata: Vec<FieldElement>) -> Vec<FieldElement> {
-    let mut execute_calldata: Vec<FieldElement> = vec![
-        FieldElement::ONE,                  // call array length
-        kakarot_address,                    // contract address
-        ETH_SEND_TRANSACTION,               // selector
-        FieldElement::ZERO,                 // data offset
-        FieldElement::from(calldata.len()), // data length
-        FieldElement::from(calldata.len()), // calldata length
-    ];
-    execute_calldata.append(&mut calldata);
+pub fn raw_kakarot_calldata(kakarot_address: FieldElement, calldata: Vec<FieldElement>) -> Vec<FieldElement> {
+    raw_kakarot_multicall_calldata(kakarot_address, vec![calldata])
+}
+
+/// Constructs the calldata for a raw Starknet invoke transaction calling Kakarot once per
+/// calldata, in order.
+pub fn raw_kakarot_multicall_calldata(
+    kakarot_address: FieldElement,
+    calldatas: Vec<Vec<FieldElement>>,
+) -> Vec<FieldElement> {
+    let mut execute_calldata: Vec<FieldElement> = vec![FieldElement::from(calldatas.len())]; // call array length
+
+    let mut offset = 0;
+    for calldata in &calldatas {
+        execute_calldata.extend([
+            kakarot_address,                    // contract address
+            ETH_SEND_TRANSACTION,               // selector
+            FieldElement::from(offset),         // data offset
+            FieldElement::from(calldata.len()), // data length
+        ]);
+        offset += calldata.len();
+    }
+
+    execute_calldata.push(FieldElement::from(offset)); // calldata length
+    execute_calldata.extend(calldatas.into_iter().flatten());
 
     execute_calldata
 }
@@ -164,4 +321,170 @@ mod tests {
             serde_json::from_str(include_str!("../models/test_data/bytecode/eth/counter.json")).unwrap();
         assert_eq!(expected, bytes);
     }
+
+    #[test]
+    fn test_logs_bloom() {
+        // Given
+        let log: Log = serde_json::from_value(serde_json::json!({
+            "address": "0x2b61c43a85bd35987c5311215e8288b823a6873e",
+            "topics": ["0x0000000000000000000000000000000000000000000000000000000000000001"],
+            "data": "0x",
+            "blockHash": null,
+            "blockNumber": null,
+            "transactionHash": null,
+            "transactionIndex": null,
+            "logIndex": null,
+            "removed": false
+        }))
+        .unwrap();
+
+        // When
+        let bloom = logs_bloom(&[log]);
+
+        // Then
+        let set_bytes: Vec<(usize, u8)> = bloom.0.iter().copied().enumerate().filter(|(_, byte)| *byte != 0).collect();
+        assert_eq!(vec![(61, 4), (85, 4), (103, 2), (138, 2), (222, 64), (251, 4)], set_bytes);
+        assert_eq!(Bloom::default(), logs_bloom(&[]));
+    }
+
+    #[test]
+    fn test_gas_used() {
+        assert_eq!(U256::from(21_000), gas_used(U256::from(21_000_000_000_000_u64), U256::from(1_000_000_000), 1));
+        assert_eq!(U256::from(63_000), gas_used(U256::from(21_000_000_000_000_u64), U256::from(1_000_000_000), 3));
+        assert_eq!(U256::ZERO, gas_used(U256::from(42), U256::ZERO, 1));
+    }
+
+    #[test]
+    fn test_split_gas_used() {
+        assert_eq!(vec![U256::from(33), U256::from(33), U256::from(34)], split_gas_used(U256::from(100), 3));
+        assert_eq!(vec![U256::from(21_000)], split_gas_used(U256::from(21_000), 1));
+        assert!(split_gas_used(U256::from(21_000), 0).is_empty());
+    }
+
+    #[test]
+    fn test_starknet_gas_to_evm_gas() {
+        assert_eq!(U256::from(21_000), starknet_gas_to_evm_gas(21_000, 1));
+        assert_eq!(U256::from(63_000), starknet_gas_to_evm_gas(21_000, 3));
+    }
+
+    #[test]
+    fn test_starknet_gas_price_to_evm_gas_price() {
+        assert_eq!(U256::from(1_000_000_000), starknet_gas_price_to_evm_gas_price(U256::from(1_000_000_000), 1));
+        assert_eq!(U256::from(333_333_333), starknet_gas_price_to_evm_gas_price(U256::from(1_000_000_000), 3));
+    }
+
+    #[tokio::test]
+    async fn test_binary_search() {
+        // Given
+        let mut probes = 0;
+        let succeeds = |gas_limit: u64| {
+            probes += 1;
+            async move { Ok::<_, ()>(gas_limit >= 53_123) }
+        };
+
+        // When
+        let gas_limit = binary_search(21_000, 30_000_000, succeeds).await.unwrap();
+
+        // Then
+        assert_eq!(53_123, gas_limit);
+        assert!(probes <= 25);
+    }
+
+    #[tokio::test]
+    async fn test_poll_until_returns_the_first_value() {
+        // Given
+        let mut polls = 0;
+        let poll = || {
+            polls += 1;
+            let value = if polls == 3 { Some(polls) } else { None };
+            async move { Ok::<_, ()>(value) }
+        };
+
+        // When
+        let value = poll_until(Duration::from_millis(1), Duration::from_secs(10), poll).await.unwrap();
+
+        // Then
+        assert_eq!(Some(3), value);
+    }
+
+    #[tokio::test]
+    async fn test_poll_until_returns_none_after_the_timeout() {
+        // Given
+        let poll = || async { Ok::<Option<()>, ()>(None) };
+
+        // When
+        let value = poll_until(Duration::from_millis(1), Duration::from_millis(20), poll).await.unwrap();
+
+        // Then
+        assert_eq!(None, value);
+    }
+
+    #[tokio::test]
+    async fn test_poll_until_fails_on_the_first_error() {
+        // Given
+        let mut polls = 0;
+        let poll = || {
+            polls += 1;
+            async { Err::<Option<()>, _>("rejected") }
+        };
+
+        // When
+        let result = poll_until(Duration::from_millis(1), Duration::from_secs(10), poll).await;
+
+        // Then
+        assert_eq!(Err("rejected"), result);
+        assert_eq!(1, polls);
+    }
+
+    #[test]
+    fn test_max_fee() {
+        // The Starknet fee allowed by a batch of two transactions, one Starknet gas unit being worth
+        // two EVM gas units.
+        let transaction = Transaction::Eip1559(TxEip1559 { gas_limit: 500, max_fee_per_gas: 10, ..Default::default() });
+        let starknet_fee_cap = starknet_fee_cap(&transaction, 2) + starknet_fee_cap(&transaction, 2);
+
+        assert_eq!(U256::from(10_000), starknet_fee_cap);
+        assert_eq!(Some(U256::from(7_500)), max_fee(U256::from(5_000), 150, starknet_fee_cap));
+        assert_eq!(Some(U256::from(10_000)), max_fee(U256::from(8_000), 150, starknet_fee_cap));
+        assert_eq!(Some(U256::from(10_000)), max_fee(U256::from(10_000), 150, starknet_fee_cap));
+        assert_eq!(None, max_fee(U256::from(12_000), 150, starknet_fee_cap));
+    }
+
+    #[test]
+    fn test_starknet_fee_cap() {
+        let transaction =
+            Transaction::Eip1559(TxEip1559 { gas_limit: 1_001, max_fee_per_gas: 10, ..Default::default() });
+
+        assert_eq!(U256::from(10_010), starknet_fee_cap(&transaction, 1));
+        // 333 Starknet gas at a Starknet gas price of 30.
+        assert_eq!(U256::from(9_990), starknet_fee_cap(&transaction, 3));
+    }
+
+    #[test]
+    fn test_raw_kakarot_multicall_calldata() {
+        // Given
+        let kakarot_address = FieldElement::from(0xabcu16);
+        let calldatas = vec![vec![FieldElement::ONE, FieldElement::from(2u8)], vec![FieldElement::from(3u8)]];
+
+        // When
+        let calldata = raw_kakarot_multicall_calldata(kakarot_address, calldatas);
+
+        // Then
+        let expected: Vec<FieldElement> = vec![
+            FieldElement::from(2u8),
+            kakarot_address,
+            ETH_SEND_TRANSACTION,
+            FieldElement::ZERO,
+            FieldElement::from(2u8),
+            kakarot_address,
+            ETH_SEND_TRANSACTION,
+            FieldElement::from(2u8),
+            FieldElement::ONE,
+            FieldElement::from(3u8),
+            FieldElement::ONE,
+            FieldElement::from(2u8),
+            FieldElement::from(3u8),
+        ];
+        assert_eq!(expected, calldata);
+    }
 }
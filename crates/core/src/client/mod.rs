@@ -1,10 +1,20 @@
 pub mod api;
+pub mod block_tags;
+pub mod cache;
 pub mod config;
 pub mod constants;
 pub mod errors;
+pub mod head_tracker;
 pub mod helpers;
+pub mod single_flight;
+pub mod submission_tracker;
 #[cfg(test)]
 pub mod tests;
+pub mod validation;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use eyre::Result;
@@ -12,57 +22,812 @@ use futures::future::join_all;
 use helpers::vec_felt_to_bytes;
 use reqwest::Client;
 use reth_primitives::{
-    keccak256, AccessList, Address, BlockId, BlockNumberOrTag, Bloom, Bytes, Signature, Transaction, TransactionKind,
+    keccak256, AccessList, Address, BlockId, BlockNumberOrTag, Bytes, Signature, Transaction, TransactionKind,
     TransactionSigned, TxEip1559, H256, U128, U256, U64, U8,
 };
 use reth_rlp::Decodable;
 use reth_rpc_types::{
-    BlockTransactions, CallRequest, FeeHistory, Index, RichBlock, SyncInfo, SyncStatus,
+    BlockTransactions, CallRequest, FeeHistory, Filter, FilterBlockOption, Index, Log, RichBlock, SyncInfo, SyncStatus,
     Transaction as EtherTransaction, TransactionReceipt,
 };
 use starknet::core::types::{
-    BlockId as StarknetBlockId, BlockTag, BroadcastedInvokeTransaction, BroadcastedInvokeTransactionV1, FieldElement,
-    FunctionCall, InvokeTransactionReceipt, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
-    MaybePendingTransactionReceipt, StarknetError, SyncStatusType, Transaction as TransactionType,
-    TransactionReceipt as StarknetTransactionReceipt, TransactionStatus as StarknetTransactionStatus,
+    BlockId as StarknetBlockId, BlockTag, BroadcastedInvokeTransaction, BroadcastedInvokeTransactionV1,
+    BroadcastedTransaction, Event, EventFilter, FeeEstimate, FieldElement, FunctionCall, MaybePendingBlockWithTxHashes,
+    MaybePendingBlockWithTxs, MaybePendingTransactionReceipt, PendingTransactionReceipt, StarknetError, SyncStatusType,
+    Transaction as TransactionType, TransactionReceipt as StarknetTransactionReceipt, TransactionStatus,
 };
-use starknet::providers::sequencer::models::{FeeEstimate, FeeUnit, TransactionSimulationInfo, TransactionTrace};
+use starknet::providers::sequencer::models::TransactionSimulationInfo;
 use starknet::providers::{Provider, ProviderError};
 
 use self::api::{KakarotEthApi, KakarotStarknetApi};
+use self::block_tags::BlockTagResolver;
+use self::cache::{StarknetCache, StarknetCacheStats};
 use self::config::{Network, StarknetConfig};
 use self::constants::gas::{BASE_FEE_PER_GAS, MAX_PRIORITY_FEE_PER_GAS, MINIMUM_GAS_FEE};
 use self::constants::selectors::{BALANCE_OF, EVM_CONTRACT_DEPLOYED, GET_EVM_ADDRESS};
 use self::constants::{
-    ACCOUNT_ADDRESS, CHAIN_ID, COUNTER_CALL_MAINNET, COUNTER_CALL_TESTNET1, COUNTER_CALL_TESTNET2, ESTIMATE_GAS,
-    MAX_FEE, STARKNET_NATIVE_TOKEN,
+    ACCOUNT_ADDRESS, CHAIN_ID, COUNTER_CALL_MAINNET, COUNTER_CALL_TESTNET1, COUNTER_CALL_TESTNET2,
+    EARLIEST_BLOCK_NUMBER, EVENTS_CHUNK_SIZE, MAX_LOGS, MAX_LOGS_BLOCK_RANGE, RECEIPT_POLL_INTERVAL,
+    STARKNET_NATIVE_TOKEN,
 };
 use self::errors::EthApiError;
-use self::helpers::{bytes_to_felt_vec, raw_kakarot_calldata, DataDecodingError};
+use self::helpers::{bytes_to_felt_vec, raw_kakarot_calldata, raw_kakarot_multicall_calldata, DataDecodingError};
+use self::single_flight::SingleFlight;
+use self::submission_tracker::{
+    GatewayTransactionStatus, StarknetTransactionStatus, SubmissionStatus, SubmissionTracker, TransactionLifecycle,
+};
+use self::validation::{SenderState, ValidationError};
 use crate::contracts::contract_account::ContractAccount;
 use crate::contracts::kakarot::KakarotContract;
+use crate::indexer::hashes::TransactionHashStore;
+use crate::indexer::store::IndexStore;
 use crate::models::balance::{TokenBalance, TokenBalances};
-use crate::models::block::{BlockWithTxHashes, BlockWithTxs, EthBlockId};
+use crate::models::block::{BlockExecution, BlockWithTxHashes, BlockWithTxs, EthBlockId, ExecutedTransaction};
 use crate::models::convertible::{ConvertibleStarknetBlock, ConvertibleStarknetEvent, ConvertibleStarknetTransaction};
 use crate::models::event::StarknetEvent;
 use crate::models::felt::Felt252Wrapper;
+use crate::models::filter::EthFilter;
+use crate::models::receipt::{EvmExecutionOutcome, KakarotTransactionReceipt};
 use crate::models::transaction::{StarknetTransaction, StarknetTransactions};
 use crate::models::ConversionError;
+use crate::txpool::{PooledTransaction, TransactionPool};
 
 pub struct KakarotClient<P: Provider + Send + Sync> {
     starknet_provider: P,
     kakarot_contract: KakarotContract<P>,
     network: Network,
+    index: Option<Arc<IndexStore>>,
+    transaction_hashes: Option<Arc<TransactionHashStore>>,
+    block_gas_limit: u64,
+    gas_price_account: Option<FieldElement>,
+    evm_gas_per_starknet_gas: u64,
+    max_fee_multiplier_percent: u64,
+    preflight_simulation: bool,
+    genesis_block: u64,
+    transaction_pool: Option<Arc<TransactionPool>>,
+    submission_tracker: Option<Arc<SubmissionTracker>>,
+    block_tag_resolver: Option<Arc<BlockTagResolver>>,
+    cache: Arc<StarknetCache>,
+    blocks_with_txs: SingleFlight<MaybePendingBlockWithTxs>,
+    blocks_with_tx_hashes: SingleFlight<MaybePendingBlockWithTxHashes>,
+    starknet_calls: SingleFlight<Vec<FieldElement>>,
+    eth_calls: SingleFlight<Bytes>,
 }
 
 impl<P: Provider + Send + Sync> KakarotClient<P> {
     /// Create a new `KakarotClient`.
     pub fn new(starknet_config: StarknetConfig, starknet_provider: P) -> Self {
-        let StarknetConfig { kakarot_address, proxy_account_class_hash, network } = starknet_config;
+        let StarknetConfig {
+            kakarot_address,
+            proxy_account_class_hash,
+            network,
+            block_gas_limit,
+            gas_price_account,
+            evm_gas_per_starknet_gas,
+            max_fee_multiplier_percent,
+            preflight_simulation,
+            genesis_block,
+        } = starknet_config;
 
         let kakarot_contract = KakarotContract::new(kakarot_address, proxy_account_class_hash);
 
-        Self { starknet_provider, network, kakarot_contract }
+        Self {
+            starknet_provider,
+            network,
+            kakarot_contract,
+            index: None,
+            transaction_hashes: None,
+            block_gas_limit,
+            gas_price_account,
+            evm_gas_per_starknet_gas,
+            max_fee_multiplier_percent,
+            preflight_simulation,
+            genesis_block: genesis_block.unwrap_or(EARLIEST_BLOCK_NUMBER),
+            transaction_pool: None,
+            submission_tracker: None,
+            block_tag_resolver: None,
+            cache: Arc::default(),
+            blocks_with_txs: SingleFlight::default(),
+            blocks_with_tx_hashes: SingleFlight::default(),
+            starknet_calls: SingleFlight::default(),
+            eth_calls: SingleFlight::default(),
+        }
+    }
+
+    /// Serves the reads of indexed blocks, transactions, receipts and logs from the local index.
+    /// Data which isn't indexed yet is still translated from Starknet.
+    #[must_use]
+    pub fn with_index(mut self, index: Arc<IndexStore>) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    /// Records the mapping between the Ethereum and the Starknet hashes of the transactions, so
+    /// that transactions can be looked up by either hash.
+    #[must_use]
+    pub fn with_transaction_hashes(mut self, transaction_hashes: Arc<TransactionHashStore>) -> Self {
+        self.transaction_hashes = Some(transaction_hashes);
+        self
+    }
+
+    /// Holds the raw transactions ahead of the nonce of their sender in the pool, instead of
+    /// rejecting them, until their predecessors are accepted.
+    #[must_use]
+    pub fn with_transaction_pool(mut self, transaction_pool: Arc<TransactionPool>) -> Self {
+        self.transaction_pool = Some(transaction_pool);
+        self
+    }
+
+    /// Tracks the Starknet status of the submitted transactions, rebroadcasting the ones which
+    /// Starknet drops.
+    #[must_use]
+    pub fn with_submission_tracker(mut self, submission_tracker: Arc<SubmissionTracker>) -> Self {
+        self.submission_tracker = Some(submission_tracker);
+        self
+    }
+
+    /// Replaces the default cache of the Starknet lookups, e.g. to size it or to share it.
+    #[must_use]
+    pub fn with_cache(mut self, cache: Arc<StarknetCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Derives the Starknet addresses of the EVM addresses locally, instead of calling Kakarot,
+    /// once the derivation is verified against the `compute_starknet_address` entrypoint. The
+    /// addresses are still computed by Kakarot when the verification fails.
+    pub async fn with_verified_address_derivation(mut self) -> Self {
+        match self.kakarot_contract.verify_address_derivation(&self.starknet_provider).await {
+            Ok(true) => (),
+            Ok(false) => {
+                log::warn!("The local Starknet address derivation doesn't match Kakarot, falling back to Kakarot calls")
+            }
+            Err(err) => {
+                log::warn!(
+                    "Failed to verify the local Starknet address derivation, falling back to Kakarot calls: {err}"
+                )
+            }
+        }
+        self
+    }
+
+    /// Resolves the `safe` and `finalized` tags to the blocks tracked by the resolver, instead of
+    /// the latest block.
+    #[must_use]
+    pub fn with_block_tag_resolver(mut self, block_tag_resolver: Arc<BlockTagResolver>) -> Self {
+        self.block_tag_resolver = Some(block_tag_resolver);
+        self
+    }
+
+    /// Replaces the `earliest` tag by the genesis block number, and the `safe` and `finalized` tags
+    /// by the block number tracked by the resolver. Until the resolver's first resolution, `safe`
+    /// is the latest block and `finalized` the genesis block. Blocks preceding the genesis block
+    /// are rejected.
+    fn resolve_block_tag(&self, block: BlockNumberOrTag) -> Result<BlockNumberOrTag, EthApiError<P::Error>> {
+        let tags = self.block_tag_resolver.as_ref().map(|resolver| resolver.tags());
+        match (block, tags) {
+            (BlockNumberOrTag::Earliest, _) | (BlockNumberOrTag::Finalized, Some(None)) => {
+                Ok(BlockNumberOrTag::Number(self.genesis_block))
+            }
+            (BlockNumberOrTag::Number(number), _) if number < self.genesis_block => {
+                Err(EthApiError::BlockBeforeGenesis(number, self.genesis_block))
+            }
+            (BlockNumberOrTag::Safe, Some(None)) => Ok(BlockNumberOrTag::Latest),
+            (BlockNumberOrTag::Safe | BlockNumberOrTag::Finalized, Some(Some(tags))) => {
+                tags.resolve(block).ok_or_else(|| {
+                    let tag = if block == BlockNumberOrTag::Safe { "safe" } else { "finalized" };
+                    EthApiError::UnknownBlockTag(tag.into())
+                })
+            }
+            (block, _) => Ok(block),
+        }
+    }
+
+    /// Returns the Starknet block with its transactions, sharing the request with the identical
+    /// requests in flight.
+    async fn block_with_txs(
+        &self,
+        block_id: &StarknetBlockId,
+    ) -> Result<MaybePendingBlockWithTxs, EthApiError<P::Error>> {
+        self.blocks_with_txs
+            .run(format!("{block_id:?}"), || async {
+                Ok::<_, EthApiError<P::Error>>(self.starknet_provider.get_block_with_txs(block_id).await?)
+            })
+            .await
+    }
+
+    /// Returns the Starknet block with its transaction hashes, sharing the request with the
+    /// identical requests in flight.
+    async fn block_with_tx_hashes(
+        &self,
+        block_id: &StarknetBlockId,
+    ) -> Result<MaybePendingBlockWithTxHashes, EthApiError<P::Error>> {
+        self.blocks_with_tx_hashes
+            .run(format!("{block_id:?}"), || async {
+                Ok::<_, EthApiError<P::Error>>(self.starknet_provider.get_block_with_tx_hashes(block_id).await?)
+            })
+            .await
+    }
+
+    /// Calls the Starknet contract, sharing the call with the identical calls in flight.
+    async fn starknet_call(
+        &self,
+        request: FunctionCall,
+        block_id: &StarknetBlockId,
+    ) -> Result<Vec<FieldElement>, EthApiError<P::Error>> {
+        self.starknet_calls
+            .run(format!("{request:?}@{block_id:?}"), || async {
+                Ok::<_, EthApiError<P::Error>>(self.starknet_provider.call(request, block_id).await?)
+            })
+            .await
+    }
+
+    /// Returns the number of the block in the local index, if it is indexed.
+    fn indexed_block_number(&self, block_id: &StarknetBlockId) -> Result<Option<u64>, EthApiError<P::Error>> {
+        let Some(index) = &self.index else { return Ok(None) };
+        let number = match block_id {
+            StarknetBlockId::Number(number) => index.contains_block(*number)?.then_some(*number),
+            StarknetBlockId::Hash(hash) => {
+                let hash: Felt252Wrapper = (*hash).into();
+                index.block_number(hash.into())?
+            }
+            StarknetBlockId::Tag(_) => None,
+        };
+        Ok(number)
+    }
+
+    /// Returns the position and the Ethereum hash of the given Kakarot transactions in the Starknet
+    /// blocks with the given hashes, indexed by Starknet transaction hash.
+    ///
+    /// The position is the one of the Ethereum transaction among the Ethereum transactions of the
+    /// block. The logs of a multicall are attributed to its last call, as in its receipts.
+    async fn kakarot_transactions(
+        &self,
+        block_hashes: Vec<FieldElement>,
+        transaction_hashes: &HashSet<FieldElement>,
+    ) -> Result<HashMap<FieldElement, (u64, H256)>, EthApiError<P::Error>> {
+        let handles = block_hashes
+            .into_iter()
+            .map(|block_hash| async move { self.block_with_txs(&StarknetBlockId::Hash(block_hash)).await });
+
+        let mut transactions = HashMap::new();
+        for block in join_all(handles).await {
+            let block = BlockWithTxs::new(block?);
+            let kakarot_transactions: Vec<TransactionType> =
+                self.filter_kakarot_transactions(block.transactions().into()).await?.into();
+
+            let mut index = 0u64;
+            for transaction in kakarot_transactions {
+                let transaction: StarknetTransaction = transaction.into();
+                let ethereum_transactions = transaction.ethereum_transactions()?;
+                index += ethereum_transactions.len() as u64;
+
+                let starknet_hash: FieldElement = transaction.transaction_hash()?.into();
+                if !transaction_hashes.contains(&starknet_hash) {
+                    continue;
+                }
+                if let Some(last) = ethereum_transactions.last() {
+                    transactions.insert(starknet_hash, (index - 1, last.hash()));
+                }
+            }
+        }
+        Ok(transactions)
+    }
+
+    /// Returns the balance of the Starknet account in the Starknet native token.
+    async fn native_token_balance(
+        &self,
+        starknet_address: FieldElement,
+        block_id: &StarknetBlockId,
+    ) -> Result<U256, EthApiError<P::Error>> {
+        let request = FunctionCall {
+            // This FieldElement::from_hex_be cannot fail as the value is a constant
+            contract_address: FieldElement::from_hex_be(STARKNET_NATIVE_TOKEN).unwrap(),
+            entry_point_selector: BALANCE_OF,
+            calldata: vec![starknet_address],
+        };
+
+        let balance = self.starknet_call(request, block_id).await?;
+
+        let balance: Felt252Wrapper = (*balance.first().ok_or_else(|| {
+            DataDecodingError::InvalidReturnArrayLength { entrypoint: "balance".into(), expected: 1, actual: 0 }
+        })?)
+        .into();
+
+        Ok(balance.into())
+    }
+
+    /// Returns the Starknet transaction of the Kakarot account calling Kakarot once per raw
+    /// Ethereum transaction, with the nonce of the first transaction and no max fee.
+    fn invoke_request(
+        &self,
+        transactions: &[(TransactionSigned, Bytes)],
+        starknet_address: FieldElement,
+    ) -> BroadcastedInvokeTransactionV1 {
+        let nonce = transactions.first().map_or(FieldElement::ZERO, |(first, _)| FieldElement::from(first.nonce()));
+
+        let calldata = raw_kakarot_multicall_calldata(
+            self.kakarot_address(),
+            transactions.iter().map(|(_, bytes)| bytes_to_felt_vec(bytes)).collect(),
+        );
+
+        BroadcastedInvokeTransactionV1 {
+            max_fee: FieldElement::ZERO,
+            signature: vec![],
+            nonce,
+            sender_address: starknet_address,
+            calldata,
+        }
+    }
+
+    /// Returns the fee estimate of the Starknet transaction of a Kakarot account. A transaction
+    /// whose execution fails is an execution which reverted without data.
+    async fn estimate_invoke_fee(
+        &self,
+        request: BroadcastedInvokeTransactionV1,
+        block_id: StarknetBlockId,
+    ) -> Result<FeeEstimate, EthApiError<P::Error>> {
+        match self.estimate_fee(request, block_id).await {
+            Err(EthApiError::RequestError(ProviderError::StarknetError(StarknetError::ContractError))) => {
+                Err(EthApiError::ExecutionReverted(Bytes::default()))
+            }
+            fee_estimate => fee_estimate,
+        }
+    }
+
+    /// Returns the data returned by the EVM execution of a reverted transaction, replayed through
+    /// the `eth_call` entrypoint of Kakarot at the given block. The replay has no origin: no data
+    /// is returned when it doesn't revert.
+    async fn revert_data(
+        &self,
+        to: Option<FieldElement>,
+        gas_limit: u64,
+        value: FieldElement,
+        data: Vec<FieldElement>,
+        block_id: &StarknetBlockId,
+    ) -> Result<Bytes, EthApiError<P::Error>> {
+        match self.kakarot_contract.simulate(&self.starknet_provider, to, gas_limit, value, data, block_id).await {
+            Ok(outcome) => Ok(outcome.revert_data().cloned().unwrap_or_default()),
+            Err(EthApiError::RequestError(ProviderError::StarknetError(StarknetError::ContractError))) => {
+                Ok(Bytes::default())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Rejects the transaction before its submission when its sender is not a deployed Kakarot
+    /// account, when it doesn't pass the validation against the state of its sender or, with the
+    /// pre-flight simulation, when the execution of its Starknet transaction fails.
+    ///
+    /// Returns the state of the sender the transaction was validated against. A transaction ahead
+    /// of the nonce of its sender passes the validation when it can be queued in the transaction
+    /// pool.
+    async fn validate_transaction(
+        &self,
+        (transaction, bytes): &(TransactionSigned, Bytes),
+        starknet_address: FieldElement,
+        block_id: &StarknetBlockId,
+    ) -> Result<SenderState, EthApiError<P::Error>> {
+        match self.is_kakarot_account(starknet_address).await {
+            Ok(true) => (),
+            Ok(false)
+            | Err(EthApiError::RequestError(ProviderError::StarknetError(StarknetError::ContractNotFound))) => {
+                return Err(ValidationError::SenderNotDeployed.into());
+            }
+            Err(err) => return Err(err),
+        }
+
+        let nonce = self.starknet_provider.get_nonce(block_id, starknet_address).await?;
+        let nonce: u64 = Felt252Wrapper::from(nonce).try_into()?;
+        let balance = self.native_token_balance(starknet_address, block_id).await?;
+        let sender = SenderState { nonce, balance };
+        match validation::validate_transaction(transaction, &sender) {
+            Ok(()) => (),
+            Err(ValidationError::NonceTooHigh) if self.transaction_pool.is_some() => return Ok(sender),
+            Err(err) => return Err(err.into()),
+        }
+
+        if self.preflight_simulation {
+            let request = self.invoke_request(&[(transaction.clone(), bytes.clone())], starknet_address);
+            self.estimate_invoke_fee(request, *block_id).await?;
+        }
+
+        Ok(sender)
+    }
+
+    /// Validates a batch of transactions of the Kakarot account, against its nonce and balance at
+    /// the given block, and returns the state of the account. The Starknet transaction of the whole
+    /// batch is simulated when the preflight simulation is enabled.
+    async fn validate_transaction_batch(
+        &self,
+        transactions: &[(TransactionSigned, Bytes)],
+        starknet_address: FieldElement,
+        block_id: &StarknetBlockId,
+    ) -> Result<SenderState, EthApiError<P::Error>> {
+        match self.is_kakarot_account(starknet_address).await {
+            Ok(true) => (),
+            Ok(false)
+            | Err(EthApiError::RequestError(ProviderError::StarknetError(StarknetError::ContractNotFound))) => {
+                return Err(ValidationError::SenderNotDeployed.into());
+            }
+            Err(err) => return Err(err),
+        }
+
+        let nonce = self.starknet_provider.get_nonce(block_id, starknet_address).await?;
+        let nonce: u64 = Felt252Wrapper::from(nonce).try_into()?;
+        let balance = self.native_token_balance(starknet_address, block_id).await?;
+        let signed: Vec<TransactionSigned> = transactions.iter().map(|(transaction, _)| transaction.clone()).collect();
+        let sender = SenderState { nonce, balance };
+        validation::validate_batch(&signed, &sender)?;
+
+        if self.preflight_simulation {
+            self.estimate_invoke_fee(self.invoke_request(transactions, starknet_address), *block_id).await?;
+        }
+
+        Ok(sender)
+    }
+
+    /// Submits the validated transactions to Starknet in a single invoke transaction calling
+    /// Kakarot once per transaction, with a max fee derived from the Starknet fee estimate of the
+    /// exact invoke transaction. The transactions are rejected when the fee they allow doesn't
+    /// cover the estimate or when the balance of the sender, as validated, doesn't cover the max
+    /// fee.
+    async fn submit_ethereum_transactions(
+        &self,
+        transactions: &[(TransactionSigned, Bytes)],
+        starknet_address: FieldElement,
+        sender: &SenderState,
+        starknet_block_id: &StarknetBlockId,
+    ) -> Result<(), EthApiError<P::Error>> {
+        if transactions.is_empty() {
+            return Ok(());
+        }
+
+        let mut request = self.invoke_request(transactions, starknet_address);
+
+        let fee_estimate = self.estimate_invoke_fee(request.clone(), *starknet_block_id).await?;
+        let max_fee = helpers::max_fee(
+            U256::from(fee_estimate.overall_fee),
+            self.max_fee_multiplier_percent,
+            transactions
+                .iter()
+                .map(|(transaction, _)| {
+                    helpers::starknet_fee_cap(&transaction.transaction, self.evm_gas_per_starknet_gas)
+                })
+                .sum(),
+        )
+        .ok_or(ValidationError::InsufficientMaxFee)?;
+
+        let value: U256 = transactions.iter().map(|(transaction, _)| U256::from(transaction.value())).sum();
+        if sender.balance < max_fee + value {
+            return Err(ValidationError::InsufficientFunds.into());
+        }
+
+        request.max_fee = Felt252Wrapper::try_from(max_fee)?.into();
+
+        let starknet_transaction_hash = self.submit_starknet_transaction(request.clone()).await?;
+        let hashes: Vec<H256> = transactions.iter().map(|(transaction, _)| transaction.hash()).collect();
+        for hash in &hashes {
+            self.save_transaction_hash(*hash, starknet_transaction_hash)?;
+        }
+        if let Some(submission_tracker) = &self.submission_tracker {
+            submission_tracker.track(hashes, starknet_transaction_hash, request);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the status of the transaction and the reason of its rejection from the feeder
+    /// gateway.
+    async fn gateway_transaction_status(
+        &self,
+        starknet_hash: FieldElement,
+    ) -> Result<Option<StarknetTransactionStatus>, EthApiError<P::Error>> {
+        let mut url = self
+            .network
+            .gateway_url()?
+            .join("get_transaction_status")
+            .map_err(|e| EthApiError::FeederGatewayError(format!("gateway url parsing error: {:?}", e)))?;
+        url.query_pairs_mut().append_pair("transactionHash", &format!("{starknet_hash:#x}"));
+
+        let response: GatewayTransactionStatus = Client::new()
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| EthApiError::FeederGatewayError(format!("gateway get error: {:?}", e)))?
+            .error_for_status()
+            .map_err(|e| EthApiError::FeederGatewayError(format!("http error: {:?}", e)))?
+            .json()
+            .await
+            .map_err(|e| {
+                EthApiError::FeederGatewayError(format!(
+                    "error while decoding response body to GatewayTransactionStatus: {:?}",
+                    e
+                ))
+            })?;
+
+        Ok(response.into_status())
+    }
+
+    /// Returns the Starknet gas price at the given block, from the fee estimate of a transaction
+    /// of an account of the network, the configured gas price account on JSON-RPC networks.
+    /// Returns `None` on a JSON-RPC network without a gas price account.
+    async fn starknet_gas_price(&self, block_id: &StarknetBlockId) -> Result<Option<U256>, EthApiError<P::Error>> {
+        let (sender_address, calldata) = match self.network {
+            Network::MainnetGateway => (*ACCOUNT_ADDRESS, COUNTER_CALL_MAINNET.clone().into()),
+            Network::Goerli1Gateway => (*ACCOUNT_ADDRESS, COUNTER_CALL_TESTNET1.clone().into()),
+            Network::Goerli2Gateway => (*ACCOUNT_ADDRESS, COUNTER_CALL_TESTNET2.clone().into()),
+            // An empty multicall: no call and no calldata.
+            _ => match self.gas_price_account {
+                Some(account) => (account, vec![FieldElement::ZERO, FieldElement::ZERO]),
+                None => return Ok(None),
+            },
+        };
+
+        let nonce = self.starknet_provider.get_nonce(block_id, sender_address).await?;
+
+        let tx = BroadcastedInvokeTransactionV1 {
+            max_fee: FieldElement::ZERO,
+            signature: vec![],
+            sender_address,
+            nonce,
+            calldata,
+        };
+
+        let fee_estimate = self.estimate_fee(tx, *block_id).await?;
+
+        Ok(Some(U256::from(fee_estimate.gas_price)))
+    }
+
+    /// Returns the Ethereum transactions wrapped in the Kakarot transaction of the block with the
+    /// given hash and number, or of the pending block, along with their receipts. The transactions
+    /// are positioned in their block by `executed_transactions`.
+    async fn kakarot_receipts(
+        &self,
+        transaction: StarknetTransaction,
+        block: Option<(FieldElement, u64)>,
+        starknet_gas_price: Option<U256>,
+    ) -> Result<Vec<ExecutedTransaction>, EthApiError<P::Error>> {
+        let starknet_hash: FieldElement = transaction.transaction_hash()?.into();
+        let (actual_fee, status, events) = match self.starknet_provider.get_transaction_receipt(starknet_hash).await? {
+            MaybePendingTransactionReceipt::Receipt(StarknetTransactionReceipt::Invoke(receipt)) => {
+                (receipt.actual_fee, receipt.status, receipt.events)
+            }
+            MaybePendingTransactionReceipt::PendingReceipt(PendingTransactionReceipt::Invoke(receipt)) => {
+                (receipt.actual_fee, TransactionStatus::Pending, receipt.events)
+            }
+            _ => {
+                return Err(EthApiError::ConversionError(format!(
+                    "no invoke receipt for Kakarot transaction {starknet_hash:#x}"
+                )));
+            }
+        };
+
+        let block_hash: Option<H256> = block.map(|(block_hash, _)| Felt252Wrapper::from(block_hash).into());
+        let block_number: Option<U256> = block.map(|(_, block_number)| U256::from(block_number));
+
+        let signed_transactions = transaction.ethereum_transactions()?;
+        let eth_txs = transaction.to_eth_transactions(self, block_hash, block_number, None).await?;
+
+        // The account which sent the multicall reports the EVM outcome of each of its calls.
+        let sender_address: FieldElement = transaction.sender_address()?.into();
+        let outcomes = EvmExecutionOutcome::from_events(&events, sender_address);
+        let reverted = |i: usize| {
+            matches!(status, TransactionStatus::Rejected) || outcomes.get(i).is_some_and(|outcome| !outcome.success)
+        };
+
+        // The gas paid for by the fee of a multicall is shared by its calls. Without the Starknet
+        // gas price of the block, the fee isn't converted to gas.
+        let actual_fee: Felt252Wrapper = actual_fee.into();
+        let gas_used = match starknet_gas_price {
+            Some(starknet_gas_price) => {
+                helpers::gas_used(actual_fee.into(), starknet_gas_price, self.evm_gas_per_starknet_gas)
+            }
+            None => actual_fee.into(),
+        };
+        let gas_used = helpers::split_gas_used(gas_used, eth_txs.len());
+
+        // The contracts deployed by a multicall are deployed in the order of its calls.
+        let mut deployments = events.iter().filter(|event| event.keys.contains(&EVM_CONTRACT_DEPLOYED));
+        let contract_addresses = eth_txs
+            .iter()
+            .enumerate()
+            .map(|(i, eth_tx)| -> Result<Option<Address>, EthApiError<P::Error>> {
+                match eth_tx.to {
+                    // If to is Some, means contract_address should be None as it is a normal transaction
+                    Some(_) => Ok(None),
+                    // A failed contract creation transaction doesn't deploy any contract
+                    None if reverted(i) => Ok(None),
+                    // If to is None, is a contract creation transaction so contract_address should be Some
+                    None => {
+                        let event = deployments.next().ok_or(EthApiError::Other(anyhow::anyhow!(
+                            "Kakarot Core: No contract deployment event found in Kakarot transaction receipt"
+                        )))?;
+
+                        let evm_address = event.data.first().ok_or(DataDecodingError::InvalidReturnArrayLength {
+                            entrypoint: "deployment".into(),
+                            expected: 1,
+                            actual: 0,
+                        })?;
+
+                        let evm_address = Felt252Wrapper::from(*evm_address);
+                        Ok(Some(evm_address.try_into()?))
+                    }
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The events of a multicall can't be told apart by call, they are attributed to its last
+        // call.
+        let mut logs = events
+            .into_iter()
+            .map(StarknetEvent::new)
+            .filter(|event| event.is_evm_log(self.kakarot_address()))
+            .map(|event| event.to_eth_log(self, block_hash, block_number, None, None, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        let last = eth_txs.len().saturating_sub(1);
+
+        let mut executed = Vec::with_capacity(eth_txs.len());
+        let calls = eth_txs.into_iter().zip(signed_transactions).zip(gas_used).zip(contract_addresses);
+        for (i, (((eth_tx, transaction), gas_used), contract_address)) in calls.enumerate() {
+            let logs: Vec<Log> = if i == last { std::mem::take(&mut logs) } else { vec![] };
+            let logs: Vec<Log> =
+                logs.into_iter().map(|log| Log { transaction_hash: Some(eth_tx.hash), ..log }).collect();
+
+            let receipt = TransactionReceipt {
+                transaction_hash: Some(eth_tx.hash),
+                transaction_index: None,
+                block_hash,
+                block_number,
+                from: eth_tx.from,
+                to: eth_tx.to,
+                cumulative_gas_used: gas_used,
+                gas_used: Some(gas_used),
+                contract_address,
+                logs_bloom: helpers::logs_bloom(&logs),
+                logs,
+                state_root: None,
+                status_code: None,
+                effective_gas_price: eth_tx.gas_price.unwrap_or_default(),
+                transaction_type: eth_tx.transaction_type.map(U8::from).unwrap_or_default(),
+            };
+            executed.push(ExecutedTransaction {
+                starknet_hash,
+                transaction,
+                receipt: KakarotTransactionReceipt::new(receipt, status, outcomes.get(i)),
+            });
+        }
+
+        Ok(executed)
+    }
+
+    /// Returns the Kakarot transactions among the given transactions of the block with the given
+    /// hash and number, or of the pending block, along with their receipts. The receipts of a block
+    /// are computed together, from the Starknet gas price of the block, and cached by block hash.
+    async fn executed_transactions(
+        &self,
+        transactions: StarknetTransactions,
+        block: Option<(FieldElement, u64)>,
+    ) -> Result<Arc<Vec<ExecutedTransaction>>, EthApiError<P::Error>> {
+        let block_hash: Option<H256> = block.map(|(block_hash, _)| Felt252Wrapper::from(block_hash).into());
+        if let Some(executed) = block_hash.and_then(|block_hash| self.cache.block_receipts(&block_hash)) {
+            return Ok(executed);
+        }
+
+        let transactions: Vec<TransactionType> = self.filter_kakarot_transactions(transactions).await?.into();
+        if transactions.is_empty() {
+            return Ok(Arc::default());
+        }
+
+        let block_id =
+            block.map_or(StarknetBlockId::Tag(BlockTag::Pending), |(block_hash, _)| StarknetBlockId::Hash(block_hash));
+        let starknet_gas_price = self.starknet_gas_price(&block_id).await?;
+
+        let handles = transactions.into_iter().map(|tx| self.kakarot_receipts(tx.into(), block, starknet_gas_price));
+        let mut executed: Vec<ExecutedTransaction> =
+            join_all(handles).await.into_iter().collect::<Result<Vec<_>, _>>()?.into_iter().flatten().collect();
+
+        // The indices of the transactions and of their logs, as well as the cumulative gas used,
+        // follow the order of the Kakarot transactions in the block.
+        let mut cumulative_gas_used = U256::ZERO;
+        let mut log_index = 0_u64;
+        for (index, executed) in executed.iter_mut().enumerate() {
+            let transaction_index = Some(U256::from(index));
+            cumulative_gas_used += executed.receipt.gas_used.unwrap_or_default();
+            executed.receipt.transaction_index = transaction_index;
+            executed.receipt.cumulative_gas_used = cumulative_gas_used;
+            for log in &mut executed.receipt.logs {
+                log.transaction_index = transaction_index;
+                log.log_index = Some(U256::from(log_index));
+                log_index += 1;
+            }
+        }
+
+        let executed = Arc::new(executed);
+        if let (Some((_, block_number)), Some(block_hash)) = (block, block_hash) {
+            self.cache.insert_block_receipts(block_number, block_hash, executed.clone());
+        }
+        Ok(executed)
+    }
+
+    /// Returns the receipt of a transaction by transaction hash, including the receipts of pending
+    /// transactions when `include_pending` is set.
+    async fn receipt(
+        &self,
+        hash: H256,
+        include_pending: bool,
+    ) -> Result<Option<KakarotTransactionReceipt>, EthApiError<P::Error>> {
+        let ethereum_hash = self.ethereum_transaction_hash(hash)?.unwrap_or(hash);
+        if let Some(receipt) = self.index.as_ref().map(|index| index.receipt(ethereum_hash)).transpose()?.flatten() {
+            return Ok(Some(receipt));
+        }
+        if let Some(receipt) = self.cache.receipt(&ethereum_hash) {
+            return Ok(Some(receipt));
+        }
+
+        let Some(transaction_hash) = self.starknet_transaction_hash(hash)? else { return Ok(None) };
+        let starknet_tx_receipt =
+            match self.starknet_provider.get_transaction_receipt::<FieldElement>(transaction_hash).await {
+                Ok(receipt) => receipt,
+                Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+
+        let (block, status) = match starknet_tx_receipt {
+            MaybePendingTransactionReceipt::Receipt(StarknetTransactionReceipt::Invoke(receipt)) => {
+                (Some((receipt.block_hash, receipt.block_number)), receipt.status)
+            }
+            MaybePendingTransactionReceipt::PendingReceipt(PendingTransactionReceipt::Invoke(_)) if include_pending => {
+                (None, TransactionStatus::Pending)
+            }
+            // L1Handler, Declare, Deploy and DeployAccount transactions unsupported for now in
+            // Kakarot
+            _ => return Ok(None),
+        };
+
+        let block_id =
+            block.map_or(StarknetBlockId::Tag(BlockTag::Pending), |(block_hash, _)| StarknetBlockId::Hash(block_hash));
+        let executed = match status {
+            // A rejected transaction is not part of the transactions of its block.
+            TransactionStatus::Rejected => {
+                let transaction = self.starknet_provider.get_transaction_by_hash(transaction_hash).await?;
+                let starknet_gas_price = self.starknet_gas_price(&block_id).await?;
+                let executed = self.kakarot_receipts(transaction.into(), block, starknet_gas_price).await?;
+                Arc::new(executed)
+            }
+            _ => {
+                let transactions = BlockWithTxs::new(self.block_with_txs(&block_id).await?).transactions();
+                // The pending block can lag behind the pending receipt of the transaction.
+                let in_block = transactions.iter().any(|transaction| {
+                    let hash = StarknetTransaction::from(transaction.clone()).transaction_hash();
+                    hash.map_or(false, |hash| FieldElement::from(hash) == transaction_hash)
+                });
+                if !in_block {
+                    return Err(EthApiError::ConversionError(format!(
+                        "transaction {transaction_hash:#x} not found in its block"
+                    )));
+                }
+                self.executed_transactions(transactions.into(), block).await?
+            }
+        };
+
+        let calls: Vec<&ExecutedTransaction> =
+            executed.iter().filter(|executed| executed.starknet_hash == transaction_hash).collect();
+        let executed = match calls.as_slice() {
+            [executed] => executed,
+            calls => match calls.iter().find(|executed| executed.transaction.hash() == ethereum_hash) {
+                Some(executed) => executed,
+                None => return Ok(None),
+            },
+        };
+
+        // The status of the transaction might have changed since the receipts of its block were
+        // computed.
+        let mut receipt = executed.receipt.clone();
+        receipt.set_starknet_status(status);
+
+        self.cache.insert_receipt(executed.transaction.hash(), &receipt);
+        Ok(Some(receipt))
     }
 }
 
@@ -74,21 +839,31 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         Ok(block_number.into())
     }
 
-    /// Returns the bytecode of a contract given its address and a block id.
+    /// Returns the bytecode of a contract given its address and a block id. The bytecode of the
+    /// current state is cached once deployed, as a contract may not be deployed at a past block.
+    /// The latest state is read at the number of the latest block, so that the bytecode is
+    /// invalidated by the reorgs of that block.
     async fn get_code(&self, ethereum_address: Address, block_id: BlockId) -> Result<Bytes, EthApiError<P::Error>> {
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
-
-        // Convert the hex-encoded string to a FieldElement
-        let ethereum_address: Felt252Wrapper = ethereum_address.into();
-        let ethereum_address = ethereum_address.into();
+        let mut starknet_block_id = self.starknet_block_id(block_id)?;
+        let current_state = matches!(starknet_block_id, StarknetBlockId::Tag(_));
+        if let Some(bytecode) = current_state.then(|| self.cache.bytecode(&ethereum_address)).flatten() {
+            return Ok(bytecode);
+        }
+        let latest_block = match starknet_block_id {
+            StarknetBlockId::Tag(BlockTag::Latest) => Some(self.block_number().await?.as_u64()),
+            _ => None,
+        };
+        if let Some(latest_block) = latest_block {
+            starknet_block_id = StarknetBlockId::Number(latest_block);
+        }
 
-        let starknet_contract_address = self
-            .kakarot_contract
-            .compute_starknet_address(&self.starknet_provider, &ethereum_address, &starknet_block_id)
-            .await?;
+        let starknet_contract_address = self.compute_starknet_address(ethereum_address, &starknet_block_id).await?;
 
         let contract_account = ContractAccount::new(starknet_contract_address);
         let bytecode = contract_account.bytecode(&self.starknet_provider, &starknet_block_id).await?;
+        if let Some(latest_block) = latest_block.filter(|_| !bytecode.is_empty()) {
+            self.cache.insert_bytecode(ethereum_address, bytecode.clone(), latest_block);
+        }
 
         // Convert the result of the function call to a vector of bytes
         Ok(bytecode)
@@ -97,16 +872,22 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
     /// Returns the result of executing a call on a ethereum address for a given calldata and block
     /// without creating a transaction.
     async fn call(&self, to: Address, calldata: Bytes, block_id: BlockId) -> Result<Bytes, EthApiError<P::Error>> {
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
+        let starknet_block_id = self.starknet_block_id(block_id)?;
 
         let to: Felt252Wrapper = to.into();
         let to = to.into();
 
         let calldata = calldata.clone().into_iter().map(FieldElement::from).collect::<Vec<_>>();
 
-        let result = self.kakarot_contract.eth_call(&self.starknet_provider, &to, calldata, &starknet_block_id).await?;
-
-        Ok(result)
+        // Identical calls in flight against the same block share the Kakarot call.
+        let key = format!("{to:?}:{calldata:?}@{starknet_block_id:?}");
+        self.eth_calls
+            .run(key, || async {
+                Ok::<_, EthApiError<P::Error>>(
+                    self.kakarot_contract.eth_call(&self.starknet_provider, &to, calldata, &starknet_block_id).await?,
+                )
+            })
+            .await
     }
 
     /// Get the syncing status of the light client
@@ -117,7 +898,7 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
             SyncStatusType::NotSyncing => Ok(SyncStatus::None),
 
             SyncStatusType::Syncing(data) => {
-                let starting_block: U256 = U256::from(data.starting_block_num);
+                let starting_block: U256 = U256::from(data.starting_block_num.max(self.genesis_block));
                 let current_block: U256 = U256::from(data.current_block_num);
                 let highest_block: U256 = U256::from(data.highest_block_num);
                 let warp_chunks_amount: Option<U256> = None;
@@ -150,60 +931,77 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
 
     /// Returns the number of transactions in a block given a block id.
     async fn get_transaction_count_by_block(&self, block_id: BlockId) -> Result<U64, EthApiError<P::Error>> {
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
-        let starknet_block = self.starknet_provider.get_block_with_txs(starknet_block_id).await?;
+        let starknet_block_id = self.starknet_block_id(block_id)?;
+        let starknet_block = self.block_with_txs(&starknet_block_id).await?;
 
-        let block_transactions = match starknet_block {
-            MaybePendingBlockWithTxs::PendingBlock(pending_block_with_txs) => {
-                self.filter_starknet_into_eth_txs(pending_block_with_txs.transactions.into(), None, None).await
-            }
-            MaybePendingBlockWithTxs::Block(block_with_txs) => {
-                let block_hash: Felt252Wrapper = block_with_txs.block_hash.into();
-                let block_hash = Some(block_hash.into());
-                let block_number: Felt252Wrapper = block_with_txs.block_number.into();
-                let block_number = Some(block_number.into());
-                self.filter_starknet_into_eth_txs(block_with_txs.transactions.into(), block_hash, block_number).await
-            }
-        };
-        let len = match block_transactions {
-            BlockTransactions::Full(transactions) => transactions.len(),
-            BlockTransactions::Hashes(_) => 0,
-            BlockTransactions::Uncle => 0,
+        let transactions = match starknet_block {
+            MaybePendingBlockWithTxs::PendingBlock(block) => block.transactions,
+            MaybePendingBlockWithTxs::Block(block) => block.transactions,
         };
+        let transactions: Vec<TransactionType> = self.filter_kakarot_transactions(transactions.into()).await?.into();
+        let len: usize = transactions
+            .into_iter()
+            .map(|tx| {
+                StarknetTransaction::from(tx).ethereum_transactions().map_or(1, |transactions| transactions.len())
+            })
+            .sum();
         Ok(U64::from(len))
     }
 
-    /// Returns the transaction for a given block id and transaction index.
+    /// Returns the transaction for a given block id and transaction index. The index counts the
+    /// Ethereum transactions wrapped in the Kakarot transactions of the block.
     async fn transaction_by_block_id_and_index(
         &self,
         block_id: BlockId,
         tx_index: Index,
     ) -> Result<EtherTransaction, EthApiError<P::Error>> {
-        let index: u64 = usize::from(tx_index) as u64;
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
-
-        let starknet_tx: StarknetTransaction =
-            self.starknet_provider.get_transaction_by_block_id_and_index(starknet_block_id, index).await?.into();
+        let index = usize::from(tx_index);
+        let starknet_block_id = self.starknet_block_id(block_id)?;
+        let block = BlockWithTxs::new(self.block_with_txs(&starknet_block_id).await?);
+        let transactions: Vec<TransactionType> =
+            self.filter_kakarot_transactions(block.transactions().into()).await?.into();
+
+        let mut first_index = 0;
+        for transaction in transactions {
+            let starknet_tx = StarknetTransaction::from(transaction);
+            let batch_len = starknet_tx.ethereum_transactions().map_or(1, |transactions| transactions.len());
+            if index >= first_index + batch_len {
+                first_index += batch_len;
+                continue;
+            }
 
-        let tx_hash: FieldElement = starknet_tx.transaction_hash()?.into();
+            let tx_hash: FieldElement = starknet_tx.transaction_hash()?.into();
+            let tx_receipt = self.starknet_provider.get_transaction_receipt(tx_hash).await?;
+            let (block_hash, block_num) = match tx_receipt {
+                MaybePendingTransactionReceipt::Receipt(StarknetTransactionReceipt::Invoke(tr)) => {
+                    let block_hash: Felt252Wrapper = tr.block_hash.into();
+                    (Some(block_hash.into()), Some(U256::from(tr.block_number)))
+                }
+                _ => (None, None), // skip all transactions other than Invoke, covers the pending case
+            };
 
-        let tx_receipt = self.starknet_provider.get_transaction_receipt(tx_hash).await?;
-        let (block_hash, block_num) = match tx_receipt {
-            MaybePendingTransactionReceipt::Receipt(StarknetTransactionReceipt::Invoke(tr)) => {
-                let block_hash: Felt252Wrapper = tr.block_hash.into();
-                (Some(block_hash.into()), Some(U256::from(tr.block_number)))
-            }
-            _ => (None, None), // skip all transactions other than Invoke, covers the pending case
-        };
+            let eth_txs =
+                starknet_tx.to_eth_transactions(self, block_hash, block_num, Some(U256::from(first_index))).await?;
+            return eth_txs
+                .into_iter()
+                .nth(index - first_index)
+                .ok_or_else(|| EthApiError::KakarotDataFilteringError("Transaction".into()));
+        }
 
-        let eth_tx = starknet_tx.to_eth_transaction(self, block_hash, block_num, Some(U256::from(index))).await?;
-        Ok(eth_tx)
+        Err(EthApiError::KakarotDataFilteringError("Transaction".into()))
     }
 
     /// Returns the transaction for a given transaction hash.
+    /// The hash can either be the Ethereum or the Starknet hash of the transaction.
     async fn transaction_by_hash(&self, hash: H256) -> Result<Option<EtherTransaction>, EthApiError<P::Error>> {
-        let hash: Felt252Wrapper = hash.try_into()?;
-        let hash: FieldElement = hash.into();
+        let ethereum_hash = self.ethereum_transaction_hash(hash)?.unwrap_or(hash);
+        if let Some(transaction) =
+            self.index.as_ref().map(|index| index.transaction(ethereum_hash)).transpose()?.flatten()
+        {
+            return Ok(Some(transaction));
+        }
+
+        let Some(hash) = self.starknet_transaction_hash(hash)? else { return Ok(None) };
 
         let transaction: StarknetTransaction = match self.starknet_provider.get_transaction_by_hash(hash).await {
             Err(_) => return Ok(None),
@@ -222,124 +1020,149 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
             }
             _ => (None, None), // skip all transactions other than Invoke, covers the pending case
         };
-        let eth_transaction = transaction.to_eth_transaction(self, block_hash, block_num, None).await?;
-        Ok(Some(eth_transaction))
+        let eth_transactions = transaction.to_eth_transactions(self, block_hash, block_num, None).await?;
+
+        // The Starknet hash of a multicall doesn't designate any of its transactions.
+        match eth_transactions.len() {
+            1 => Ok(eth_transactions.into_iter().next()),
+            _ => Ok(eth_transactions.into_iter().find(|tx| tx.hash == ethereum_hash)),
+        }
     }
 
     /// Returns the receipt of a transaction by transaction hash.
-    async fn transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>, EthApiError<P::Error>> {
-        // TODO: Error when trying to transform 32 bytes hash to FieldElement
-        let transaction_hash: Felt252Wrapper = hash.try_into()?;
-        let starknet_tx_receipt =
-            match self.starknet_provider.get_transaction_receipt::<FieldElement>(transaction_hash.into()).await {
-                Err(_) => return Ok(None),
-                Ok(receipt) => receipt,
+    /// The hash can either be the Ethereum or the Starknet hash of the transaction.
+    async fn transaction_receipt(
+        &self,
+        hash: H256,
+    ) -> Result<Option<KakarotTransactionReceipt>, EthApiError<P::Error>> {
+        self.receipt(hash, false).await
+    }
+
+    /// Returns the logs emitted by Kakarot transactions matching the given filter.
+    ///
+    /// All the events emitted by the Kakarot contract in the requested range are fetched, following
+    /// the continuation tokens, and translated into Ethereum logs. Address and topics are matched
+    /// on the translated logs, so that every Kakarot log of a block is counted in its `log_index`.
+    async fn get_logs(&self, mut filter: Filter) -> Result<Vec<Log>, EthApiError<P::Error>> {
+        if let FilterBlockOption::Range { from_block, to_block } = &mut filter.block_option {
+            // Kakarot has no logs before its genesis block.
+            if matches!(*to_block, Some(BlockNumberOrTag::Number(to)) if to < self.genesis_block) {
+                return Ok(Vec::new());
+            }
+            *from_block = match *from_block {
+                Some(BlockNumberOrTag::Number(from)) => Some(BlockNumberOrTag::Number(from.max(self.genesis_block))),
+                from_block => from_block.map(|block| self.resolve_block_tag(block)).transpose()?,
             };
+            *to_block = to_block.map(|block| self.resolve_block_tag(block)).transpose()?;
+        }
+        let filter = EthFilter::new(filter);
+        let (from_block, to_block) = filter.starknet_block_range()?;
+
+        // A block hash filter targets a single block.
+        if !matches!(from_block, StarknetBlockId::Hash(_)) {
+            let from = self.map_block_id_to_block_number(&from_block).await?;
+            let to = self.map_block_id_to_block_number(&to_block).await?;
+            if to.saturating_sub(from) >= MAX_LOGS_BLOCK_RANGE {
+                return Err(EthApiError::LimitExceeded(format!(
+                    "block range greater than {MAX_LOGS_BLOCK_RANGE} blocks"
+                )));
+            }
+        }
 
-        let res_receipt = match starknet_tx_receipt {
-            MaybePendingTransactionReceipt::Receipt(receipt) => match receipt {
-                StarknetTransactionReceipt::Invoke(InvokeTransactionReceipt {
-                    transaction_hash,
-                    status,
-                    block_hash,
-                    block_number,
-                    events,
-                    ..
-                }) => {
-                    let starknet_tx: StarknetTransaction =
-                        self.starknet_provider.get_transaction_by_hash(transaction_hash).await?.into();
-
-                    let transaction_hash: Felt252Wrapper = transaction_hash.into();
-                    let transaction_hash: Option<H256> = Some(transaction_hash.into());
-
-                    let block_hash: Felt252Wrapper = block_hash.into();
-                    let block_hash: Option<H256> = Some(block_hash.into());
-
-                    let block_number: Felt252Wrapper = block_number.into();
-                    let block_number: Option<U256> = Some(block_number.into());
-
-                    let eth_tx = starknet_tx.to_eth_transaction(self, None, None, None).await?;
-                    let from = eth_tx.from;
-                    let to = eth_tx.to;
-                    let contract_address = match to {
-                        // If to is Some, means contract_address should be None as it is a normal transaction
-                        Some(_) => None,
-                        // If to is None, is a contract creation transaction so contract_address should be Some
-                        None => {
-                            let event = events
-                                .iter()
-                                .find(|event| event.keys.iter().any(|key| *key == EVM_CONTRACT_DEPLOYED))
-                                .ok_or(EthApiError::Other(anyhow::anyhow!(
-                                    "Kakarot Core: No contract deployment event found in Kakarot transaction receipt"
-                                )))?;
-
-                            let evm_address =
-                                event.data.first().ok_or(DataDecodingError::InvalidReturnArrayLength {
-                                    entrypoint: "deployment".into(),
-                                    expected: 1,
-                                    actual: 0,
-                                })?;
-
-                            let evm_address = Felt252Wrapper::from(*evm_address);
-                            Some(evm_address.try_into()?)
-                        }
-                    };
-
-                    let status_code = match status {
-                        StarknetTransactionStatus::Rejected | StarknetTransactionStatus::Pending => Some(U64::from(0)),
-                        StarknetTransactionStatus::AcceptedOnL1 | StarknetTransactionStatus::AcceptedOnL2 => {
-                            Some(U64::from(1))
-                        }
-                    };
-
-                    let logs = events
-                        .into_iter()
-                        .map(StarknetEvent::new)
-                        .filter_map(|event| {
-                            event.to_eth_log(self, block_hash, block_number, transaction_hash, None, None).ok()
-                        })
-                        .collect();
-
-                    TransactionReceipt {
-                        transaction_hash,
-                        // TODO: transition this hardcoded default out of nearing-demo-day hack and seeing how to
-                        // properly source/translate this value
-                        transaction_index: Some(U256::ZERO),
-                        block_hash,
-                        block_number,
-                        from,
-                        to,
-                        cumulative_gas_used: U256::from(1_000_000), // TODO: Fetch real data
-                        gas_used: Some(U256::from(500_000)),
-                        contract_address,
-                        logs,
-                        state_root: None,             // TODO: Fetch real data
-                        logs_bloom: Bloom::default(), // TODO: Fetch real data
-                        status_code,
-                        effective_gas_price: U128::from(1_000_000), // TODO: Fetch real data
-                        transaction_type: U8::from(0),              // TODO: Fetch real data
-                    }
+        if let (Some(index), Some(from), Some(to)) =
+            (&self.index, self.indexed_block_number(&from_block)?, self.indexed_block_number(&to_block)?)
+        {
+            if let Some(logs) = index.logs(from, to)? {
+                let logs: Vec<Log> = logs.into_iter().filter(|log| filter.matches(log)).collect();
+                if logs.len() > MAX_LOGS {
+                    return Err(EthApiError::LimitExceeded(format!("query returned more than {MAX_LOGS} results")));
                 }
-                // L1Handler, Declare, Deploy and DeployAccount transactions unsupported for now in
-                // Kakarot
-                _ => return Ok(None),
-            },
-            MaybePendingTransactionReceipt::PendingReceipt(_) => {
-                return Ok(None);
+                return Ok(logs);
             }
+        }
+
+        let event_filter = EventFilter {
+            from_block: Some(from_block),
+            to_block: Some(to_block),
+            address: Some(self.kakarot_address()),
+            keys: None,
         };
 
-        Ok(Some(res_receipt))
+        let mut events = vec![];
+        let mut continuation_token = None;
+        loop {
+            let page =
+                self.starknet_provider.get_events(event_filter.clone(), continuation_token, EVENTS_CHUNK_SIZE).await?;
+            events.extend(page.events);
+
+            continuation_token = page.continuation_token;
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        let mut block_hashes = events.iter().map(|event| event.block_hash).collect::<Vec<_>>();
+        block_hashes.dedup();
+        let transaction_hashes = events.iter().map(|event| event.transaction_hash).collect::<HashSet<_>>();
+        let transactions = self.kakarot_transactions(block_hashes, &transaction_hashes).await?;
+
+        let mut logs = vec![];
+        let mut current_block_hash = None;
+        let mut log_index = 0u64;
+        for event in events {
+            // Events are returned in order, the log index is reset at each new block.
+            if current_block_hash != Some(event.block_hash) {
+                current_block_hash = Some(event.block_hash);
+                log_index = 0;
+            }
+
+            let block_hash: Felt252Wrapper = event.block_hash.into();
+            let block_hash: H256 = block_hash.into();
+            let block_number = U256::from(event.block_number);
+            let (transaction_index, transaction_hash) = match transactions.get(&event.transaction_hash) {
+                Some((index, hash)) => (Some(U256::from(*index)), *hash),
+                None => {
+                    let transaction_hash: Felt252Wrapper = event.transaction_hash.into();
+                    (None, transaction_hash.into())
+                }
+            };
+
+            let event =
+                StarknetEvent::new(Event { from_address: event.from_address, keys: event.keys, data: event.data });
+            if !event.is_evm_log(self.kakarot_address()) {
+                continue;
+            }
+            let log = event.to_eth_log(
+                self,
+                Some(block_hash),
+                Some(block_number),
+                Some(transaction_hash),
+                Some(U256::from(log_index)),
+                transaction_index,
+            )?;
+            log_index += 1;
+
+            if filter.matches(&log) {
+                logs.push(log);
+            }
+            if logs.len() > MAX_LOGS {
+                return Err(EthApiError::LimitExceeded(format!("query returned more than {MAX_LOGS} results")));
+            }
+        }
+
+        Ok(logs)
     }
 
     /// Returns the nonce for a given ethereum address
     /// if ethereum -> stark mapping doesn't exist in the starknet provider, we translate
     /// ContractNotFound errors into zeros
+    /// The pending nonce also counts the transactions of the pool which follow the account nonce.
     async fn nonce(&self, ethereum_address: Address, block_id: BlockId) -> Result<U256, EthApiError<P::Error>> {
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
+        let starknet_block_id = self.starknet_block_id(block_id)?;
         let starknet_address = self.compute_starknet_address(ethereum_address, &starknet_block_id).await?;
 
-        self.starknet_provider
+        let nonce: U256 = self
+            .starknet_provider
             .get_nonce(starknet_block_id, starknet_address)
             .await
             .map(|nonce| {
@@ -351,29 +1174,23 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
                 ProviderError::StarknetError(StarknetError::ContractNotFound)
                 | ProviderError::StarknetError(StarknetError::ContractError) => Ok(U256::from(0)),
                 _ => Err(EthApiError::from(err)),
-            })
+            })?;
+
+        match (&self.transaction_pool, block_id) {
+            (Some(transaction_pool), BlockId::Number(BlockNumberOrTag::Pending)) => {
+                let nonce = u64::try_from(nonce)
+                    .map_err(|_| EthApiError::ConversionError(format!("nonce {nonce} overflows")))?;
+                Ok(U256::from(transaction_pool.pending_nonce(ethereum_address, nonce)))
+            }
+            _ => Ok(nonce),
+        }
     }
 
     /// Returns the balance in Starknet's native token of a specific EVM address.
     async fn balance(&self, ethereum_address: Address, block_id: BlockId) -> Result<U256, EthApiError<P::Error>> {
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
+        let starknet_block_id = self.starknet_block_id(block_id)?;
         let starknet_address = self.compute_starknet_address(ethereum_address, &starknet_block_id).await?;
-
-        let request = FunctionCall {
-            // This FieldElement::from_hex_be cannot fail as the value is a constant
-            contract_address: FieldElement::from_hex_be(STARKNET_NATIVE_TOKEN).unwrap(),
-            entry_point_selector: BALANCE_OF,
-            calldata: vec![starknet_address],
-        };
-
-        let balance = self.starknet_provider.call(request, starknet_block_id).await?;
-
-        let balance: Felt252Wrapper = (*balance.first().ok_or_else(|| {
-            DataDecodingError::InvalidReturnArrayLength { entrypoint: "balance".into(), expected: 1, actual: 0 }
-        })?)
-        .into();
-
-        Ok(balance.into())
+        self.native_token_balance(starknet_address, &starknet_block_id).await
     }
 
     /// Returns the storage value at a specific index of a contract given its address and a block
@@ -384,15 +1201,9 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         index: U256,
         block_id: BlockId,
     ) -> Result<U256, EthApiError<P::Error>> {
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
+        let starknet_block_id = self.starknet_block_id(block_id)?;
 
-        let address: Felt252Wrapper = address.into();
-        let address = address.into();
-
-        let starknet_contract_address = self
-            .kakarot_contract
-            .compute_starknet_address(&self.starknet_provider, &address, &starknet_block_id)
-            .await?;
+        let starknet_contract_address = self.compute_starknet_address(address, &starknet_block_id).await?;
 
         let key_low = index & U256::from(u128::MAX);
         let key_low: Felt252Wrapper = key_low.try_into()?;
@@ -450,7 +1261,8 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         Ok(TokenBalances { address, token_balances })
     }
 
-    /// Sends raw Ethereum transaction bytes to Kakarot
+    /// Sends raw Ethereum transaction bytes to Kakarot and returns the Ethereum hash of the
+    /// transaction.
     async fn send_transaction(&self, bytes: Bytes) -> Result<H256, EthApiError<P::Error>> {
         let mut data = bytes.as_ref();
 
@@ -464,21 +1276,104 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
 
         let starknet_address = self.compute_starknet_address(evm_address, &starknet_block_id).await?;
 
-        let nonce = FieldElement::from(transaction.nonce());
+        let hash = transaction.hash();
+        let transactions = [(transaction, bytes)];
+        let sender = self.validate_transaction(&transactions[0], starknet_address, &starknet_block_id).await?;
+
+        let Some(transaction_pool) = &self.transaction_pool else {
+            self.submit_ethereum_transactions(&transactions, starknet_address, &sender, &starknet_block_id).await?;
+            return Ok(hash);
+        };
+
+        // Queued transactions are submitted by the pool maintainer once their predecessors are
+        // accepted. Submitted transactions stay in the pool until their nonce is used.
+        let [(transaction, bytes)] = &transactions;
+        let queued = transaction.nonce() > sender.nonce;
+        transaction_pool.add(PooledTransaction::new(transaction.clone(), evm_address, bytes.clone(), !queued))?;
+        if !queued {
+            if let Err(err) =
+                self.submit_ethereum_transactions(&transactions, starknet_address, &sender, &starknet_block_id).await
+            {
+                transaction_pool.remove(evm_address, transaction.nonce());
+                return Err(err);
+            }
+        }
+
+        Ok(hash)
+    }
+
+    /// Sends a batch of raw Ethereum transaction bytes of the same sender to Kakarot in a single
+    /// Starknet multicall, executing the transactions atomically and in order. The transactions
+    /// must all have the nonce of the sender, the Kakarot account incrementing its nonce once per
+    /// Starknet transaction.
+    async fn send_transaction_batch(&self, batch: Vec<Bytes>) -> Result<Vec<H256>, EthApiError<P::Error>> {
+        let mut transactions = Vec::with_capacity(batch.len());
+        let mut sender = None;
+        for bytes in batch {
+            let transaction =
+                TransactionSigned::decode(&mut bytes.as_ref()).map_err(DataDecodingError::TransactionDecodingError)?;
+            let evm_address = transaction.recover_signer().ok_or_else(|| {
+                EthApiError::Other(anyhow::anyhow!("Kakarot send_transaction_batch: signature ecrecover failed"))
+            })?;
+            if *sender.get_or_insert(evm_address) != evm_address {
+                return Err(ValidationError::BatchSenderMismatch.into());
+            }
+            transactions.push((transaction, bytes));
+        }
+        let evm_address = sender.ok_or(ValidationError::EmptyBatch)?;
 
-        let calldata = raw_kakarot_calldata(self.kakarot_address(), bytes_to_felt_vec(&bytes));
+        let starknet_block_id = StarknetBlockId::Tag(BlockTag::Latest);
 
-        // Get estimated_fee from Starknet
-        let max_fee = *MAX_FEE;
+        let starknet_address = self.compute_starknet_address(evm_address, &starknet_block_id).await?;
 
-        let signature = vec![];
+        let sender = self.validate_transaction_batch(&transactions, starknet_address, &starknet_block_id).await?;
+        let hashes = transactions.iter().map(|(transaction, _)| transaction.hash()).collect();
 
-        let request =
-            BroadcastedInvokeTransactionV1 { max_fee, signature, nonce, sender_address: starknet_address, calldata };
+        let Some(transaction_pool) = &self.transaction_pool else {
+            self.submit_ethereum_transactions(&transactions, starknet_address, &sender, &starknet_block_id).await?;
+            return Ok(hashes);
+        };
 
-        let starknet_transaction_hash = self.submit_starknet_transaction(request).await?;
+        // The batch is tracked by the pool as a submitted transaction of its nonce, the only nonce
+        // it uses, so that the pending nonce of the sender accounts for it.
+        let Some((first, bytes)) = transactions.first() else { return Ok(hashes) };
+        transaction_pool.add(PooledTransaction::new(first.clone(), evm_address, bytes.clone(), true))?;
+        if let Err(err) =
+            self.submit_ethereum_transactions(&transactions, starknet_address, &sender, &starknet_block_id).await
+        {
+            transaction_pool.remove(evm_address, first.nonce());
+            return Err(err);
+        }
 
-        Ok(starknet_transaction_hash)
+        Ok(hashes)
+    }
+
+    /// Sends raw Ethereum transaction bytes to Kakarot and polls the receipt of the transaction
+    /// until it is included, failing with the hash of the transaction after the timeout. A
+    /// transaction which the submission tracker reports as rejected or dropped fails early.
+    async fn send_transaction_sync(
+        &self,
+        bytes: Bytes,
+        timeout: Duration,
+        include_pending: bool,
+    ) -> Result<KakarotTransactionReceipt, EthApiError<P::Error>> {
+        let hash = self.send_transaction(bytes).await?;
+
+        let poll_receipt = || async move {
+            if let Some(receipt) = self.receipt(hash, include_pending).await? {
+                return Ok(Some(receipt));
+            }
+            let lifecycle = self.submission_tracker.as_ref().and_then(|tracker| tracker.lifecycle(hash));
+            if let Some(lifecycle) = lifecycle.filter(|lifecycle| lifecycle.status.is_excluded()) {
+                let reason = lifecycle.rejection_reason.unwrap_or_else(|| "dropped by Starknet".into());
+                return Err(EthApiError::TransactionRejected(reason));
+            }
+            Ok::<_, EthApiError<P::Error>>(None)
+        };
+
+        helpers::poll_until(RECEIPT_POLL_INTERVAL, timeout, poll_receipt)
+            .await?
+            .ok_or(EthApiError::TransactionTimeout(hash))
     }
 
     /// Returns the fixed base_fee_per_gas of Kakarot
@@ -489,6 +1384,11 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         U256::from(BASE_FEE_PER_GAS)
     }
 
+    /// Returns the gas limit reported in the headers of the Kakarot blocks.
+    fn block_gas_limit(&self) -> U256 {
+        U256::from(self.block_gas_limit)
+    }
+
     /// Returns the max_priority_fee_per_gas of Kakarot
     fn max_priority_fee_per_gas(&self) -> U128 {
         MAX_PRIORITY_FEE_PER_GAS
@@ -501,18 +1401,18 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         newest_block: BlockNumberOrTag,
         _reward_percentiles: Option<Vec<f64>>,
     ) -> Result<FeeHistory, EthApiError<P::Error>> {
+        let newest_block = match self.resolve_block_tag(newest_block)? {
+            BlockNumberOrTag::Number(n) => n,
+            _ => self.block_number().await?.as_u64(),
+        };
+        // The history doesn't go back before the genesis block.
+        let block_count = block_count.min(U256::from(newest_block.saturating_sub(self.genesis_block)));
         let block_count_usize =
             usize::try_from(block_count).map_err(|e| ConversionError::<()>::ValueOutOfRange(e.to_string()))?;
 
         let base_fee = self.base_fee_per_gas();
 
         let base_fee_per_gas: Vec<U256> = vec![base_fee; block_count_usize + 1];
-        let newest_block = match newest_block {
-            BlockNumberOrTag::Number(n) => n,
-            // TODO: Add Genesis block number
-            BlockNumberOrTag::Earliest => 1_u64,
-            _ => self.block_number().await?.as_u64(),
-        };
 
         let gas_used_ratio: Vec<f64> = vec![0.9; block_count_usize];
         let newest_block = U256::from(newest_block);
@@ -523,21 +1423,20 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
         Ok(FeeHistory { base_fee_per_gas, gas_used_ratio, oldest_block, reward: Some(vec![vec![]]) })
     }
 
-    /// Returns the estimated gas for a transaction
+    /// Returns the estimated gas for a transaction.
+    ///
+    /// When the sender is a deployed Kakarot account, the Starknet transaction it would send is
+    /// simulated and the estimate is the lowest gas limit for which its execution succeeds, failing
+    /// when it reverts with the gas cap. Otherwise, the estimate is the Starknet gas of the
+    /// transaction translated to EVM gas.
     async fn estimate_gas(&self, request: CallRequest, block_id: BlockId) -> Result<U256, EthApiError<P::Error>> {
-        match self.network {
-            Network::MainnetGateway | Network::Goerli1Gateway | Network::Goerli2Gateway => (),
-            _ => {
-                return Ok(*ESTIMATE_GAS);
-            }
-        };
-
         let chain_id = request.chain_id.unwrap_or(CHAIN_ID.into());
 
         let from = request.from.ok_or_else(|| EthApiError::MissingParameterError("from for estimate_gas".into()))?;
         let nonce = self.nonce(from, block_id).await?.try_into().map_err(ConversionError::<u64>::from)?;
 
-        let gas_limit = request.gas.unwrap_or(U256::ZERO).try_into().map_err(ConversionError::<u64>::from)?;
+        let gas_cap =
+            request.gas.unwrap_or_else(|| self.block_gas_limit()).try_into().map_err(ConversionError::<u64>::from)?;
         let max_fee_per_gas = request
             .max_fee_per_gas
             .unwrap_or_else(|| U256::from(BASE_FEE_PER_GAS))
@@ -555,69 +1454,116 @@ impl<P: Provider + Send + Sync> KakarotEthApi<P> for KakarotClient<P> {
 
         let data = request.data.unwrap_or_default();
 
-        let tx = Transaction::Eip1559(TxEip1559 {
-            chain_id: chain_id.low_u64(),
-            nonce,
-            gas_limit,
-            max_fee_per_gas,
-            max_priority_fee_per_gas,
-            to,
-            value,
-            access_list: AccessList(vec![]),
-            input: data,
-        });
-
-        let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into()?;
-        let block_number = self.map_block_id_to_block_number(&starknet_block_id).await?;
+        let starknet_block_id = self.starknet_block_id(block_id)?;
 
         let sender_address = self.compute_starknet_address(from, &starknet_block_id).await?;
 
-        let mut data = vec![];
-        tx.encode_with_signature(&Signature::default(), &mut data, false);
-        let data = data.into_iter().map(FieldElement::from).collect();
-        let calldata = raw_kakarot_calldata(self.kakarot_address(), data);
+        // The Starknet transaction of the sender wrapping the Ethereum transaction with the given
+        // gas limit.
+        let invoke_request = |gas_limit: u64| {
+            let tx = Transaction::Eip1559(TxEip1559 {
+                chain_id: chain_id.low_u64(),
+                nonce,
+                gas_limit,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                to,
+                value,
+                access_list: AccessList(vec![]),
+                input: data.clone(),
+            });
 
-        let tx = BroadcastedInvokeTransactionV1 {
-            max_fee: FieldElement::ZERO,
-            signature: vec![],
-            sender_address,
-            nonce: nonce.into(),
-            calldata,
+            let mut data = vec![];
+            tx.encode_with_signature(&Signature::default(), &mut data, false);
+            let data = data.into_iter().map(FieldElement::from).collect();
+            let calldata = raw_kakarot_calldata(self.kakarot_address(), data);
+
+            BroadcastedInvokeTransactionV1 {
+                max_fee: FieldElement::ZERO,
+                signature: vec![],
+                sender_address,
+                nonce: nonce.into(),
+                calldata,
+            }
         };
+        let invoke_request = &invoke_request;
 
-        let fee_estimate = self.simulate_transaction(tx, block_number, true).await?.fee_estimation;
-        if fee_estimate.gas_usage < MINIMUM_GAS_FEE {
-            return Ok(U256::from(MINIMUM_GAS_FEE));
+        let sender_deployed = match self.is_kakarot_account(sender_address).await {
+            Ok(is_kakarot_account) => is_kakarot_account,
+            Err(EthApiError::RequestError(ProviderError::StarknetError(StarknetError::ContractNotFound))) => false,
+            Err(err) => return Err(err),
+        };
+        if !sender_deployed {
+            let fee_estimate = self.estimate_fee(invoke_request(gas_cap), starknet_block_id).await?;
+            let starknet_gas =
+                helpers::starknet_gas_to_evm_gas(fee_estimate.gas_consumed, self.evm_gas_per_starknet_gas);
+            return Ok(starknet_gas.max(U256::from(MINIMUM_GAS_FEE)));
         }
-        Ok(U256::from(fee_estimate.gas_usage))
-    }
 
-    /// Returns the gas price on the network
-    async fn gas_price(&self) -> Result<U256, EthApiError<P::Error>> {
-        let call = match self.network {
-            Network::MainnetGateway => COUNTER_CALL_MAINNET.clone(),
-            Network::Goerli1Gateway => COUNTER_CALL_TESTNET1.clone(),
-            Network::Goerli2Gateway => COUNTER_CALL_TESTNET2.clone(),
-            _ => return Ok(self.base_fee_per_gas()),
+        // The EVM gas is the lowest gas limit for which the execution succeeds, and at least the
+        // Starknet gas of the transaction translated to EVM gas. A transaction which reverts with
+        // the gas cap is replayed through Kakarot to report the data its execution returned.
+        let fee_estimate = match self.estimate_invoke_fee(invoke_request(gas_cap), starknet_block_id).await {
+            Err(EthApiError::ExecutionReverted(_)) => {
+                let to = match to {
+                    TransactionKind::Call(to) => Some(Felt252Wrapper::from(to).into()),
+                    TransactionKind::Create => None,
+                };
+                let data = data.iter().copied().map(FieldElement::from).collect();
+                let revert_data = self.revert_data(to, gas_cap, value.into(), data, &starknet_block_id).await?;
+                return Err(EthApiError::ExecutionReverted(revert_data));
+            }
+            fee_estimate => fee_estimate?,
         };
+        let starknet_gas = helpers::starknet_gas_to_evm_gas(fee_estimate.gas_consumed, self.evm_gas_per_starknet_gas);
+
+        let gas_limit =
+            helpers::binary_search(MINIMUM_GAS_FEE.saturating_sub(1).min(gas_cap), gas_cap, |gas_limit| async move {
+                match self.estimate_invoke_fee(invoke_request(gas_limit), starknet_block_id).await {
+                    Ok(_) => Ok(true),
+                    Err(EthApiError::ExecutionReverted(_)) => Ok(false),
+                    Err(err) => Err(err),
+                }
+            })
+            .await?;
 
-        let raw_calldata: Vec<FieldElement> = call.into();
+        Ok(U256::from(gas_limit).max(starknet_gas))
+    }
 
-        let block_id = StarknetBlockId::Tag(BlockTag::Latest);
-        let nonce = self.starknet_provider.get_nonce(block_id, *ACCOUNT_ADDRESS).await?;
+    /// Returns the gas price on the network, the Starknet gas price translated to the price of an
+    /// EVM gas unit, or the base fee per gas without a Starknet gas price.
+    async fn gas_price(&self) -> Result<U256, EthApiError<P::Error>> {
+        match self.starknet_gas_price(&StarknetBlockId::Tag(BlockTag::Latest)).await? {
+            Some(starknet_gas_price) => {
+                Ok(helpers::starknet_gas_price_to_evm_gas_price(starknet_gas_price, self.evm_gas_per_starknet_gas))
+            }
+            None => Ok(self.base_fee_per_gas()),
+        }
+    }
 
-        let tx = BroadcastedInvokeTransactionV1 {
-            max_fee: FieldElement::ZERO,
-            signature: vec![],
-            sender_address: *ACCOUNT_ADDRESS,
-            nonce,
-            calldata: raw_calldata,
-        };
+    /// Returns the lifecycle of the submission of the transaction when it is tracked, or else its
+    /// current Starknet status.
+    async fn transaction_status(&self, hash: H256) -> Result<Option<TransactionLifecycle>, EthApiError<P::Error>> {
+        if let Some(lifecycle) = self.submission_tracker.as_ref().and_then(|tracker| tracker.lifecycle(hash)) {
+            return Ok(Some(lifecycle));
+        }
 
-        let block_number = self.block_number().await?.as_u64();
-        let fee_estimate = self.simulate_transaction(tx, block_number, true).await?.fee_estimation;
+        let Some(starknet_hash) = self.starknet_transaction_hash(hash)? else { return Ok(None) };
+        let Some(status) = self.starknet_transaction_status(starknet_hash).await? else { return Ok(None) };
+        let starknet_hash: Felt252Wrapper = starknet_hash.into();
+        Ok(Some(TransactionLifecycle::new(hash, starknet_hash.into(), status)))
+    }
+
+    /// Returns the hit and miss counts of the caches of the Starknet lookups.
+    fn cache_stats(&self) -> StarknetCacheStats {
+        self.cache.stats()
+    }
 
-        Ok(U256::from(fee_estimate.gas_price))
+    /// Returns the hashes of the indexed transactions sent from or to the address, from the oldest
+    /// to the most recent, or `None` when the local index is disabled.
+    fn transactions_by_address(&self, address: Address) -> Result<Option<Vec<H256>>, EthApiError<P::Error>> {
+        let Some(index) = &self.index else { return Ok(None) };
+        Ok(Some(index.transactions_by_address(address)?))
     }
 }
 
@@ -638,13 +1584,28 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
         &self.starknet_provider
     }
 
+    /// Returns the number of the Starknet block in which Kakarot was deployed.
+    fn genesis_block_number(&self) -> u64 {
+        self.genesis_block
+    }
+
+    /// Returns the Starknet block id of the Ethereum block id. Without a block tag resolver, the
+    /// `safe` and `finalized` tags are the latest block.
+    fn starknet_block_id(&self, block_id: BlockId) -> Result<StarknetBlockId, EthApiError<P::Error>> {
+        let block_id = match block_id {
+            BlockId::Number(number) => BlockId::Number(self.resolve_block_tag(number)?),
+            block_id => block_id,
+        };
+        Ok(EthBlockId::new(block_id).try_into()?)
+    }
+
     /// Returns the Starknet block number for a given block id.
     async fn map_block_id_to_block_number(&self, block_id: &StarknetBlockId) -> Result<u64, EthApiError<P::Error>> {
         match block_id {
             StarknetBlockId::Number(n) => Ok(*n),
             StarknetBlockId::Tag(_) => Ok(self.block_number().await?.as_u64()),
             StarknetBlockId::Hash(_) => {
-                let block = self.starknet_provider.get_block_with_tx_hashes(block_id).await?;
+                let block = self.block_with_tx_hashes(block_id).await?;
                 match block {
                     MaybePendingBlockWithTxHashes::Block(block_with_tx_hashes) => Ok(block_with_tx_hashes.block_number),
                     _ => Err(ProviderError::StarknetError(StarknetError::BlockNotFound).into()),
@@ -660,19 +1621,25 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
         starknet_address: &FieldElement,
         starknet_block_id: &StarknetBlockId,
     ) -> Result<Address, EthApiError<P::Error>> {
+        if let Some(evm_address) = self.cache.evm_address(starknet_address) {
+            return Ok(evm_address);
+        }
+
         let request = FunctionCall {
             contract_address: *starknet_address,
             entry_point_selector: GET_EVM_ADDRESS,
             calldata: vec![],
         };
 
-        let evm_address = self.starknet_provider.call(request, starknet_block_id).await?;
+        let evm_address = self.starknet_call(request, starknet_block_id).await?;
         let evm_address: Felt252Wrapper = (*evm_address.first().ok_or_else(|| {
             DataDecodingError::InvalidReturnArrayLength { entrypoint: "get_evm_address".into(), expected: 1, actual: 0 }
         })?)
         .into();
 
-        Ok(evm_address.troncate_to_ethereum_address())
+        let evm_address = evm_address.troncate_to_ethereum_address();
+        self.cache.insert_address_pair(evm_address, *starknet_address);
+        Ok(evm_address)
     }
 
     /// Submits a Kakarot transaction to the Starknet provider.
@@ -693,13 +1660,71 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
         ethereum_address: Address,
         starknet_block_id: &StarknetBlockId,
     ) -> Result<FieldElement, EthApiError<P::Error>> {
-        let ethereum_address: Felt252Wrapper = ethereum_address.into();
-        let ethereum_address = ethereum_address.into();
+        if let Some(starknet_address) = self.cache.starknet_address(&ethereum_address) {
+            return Ok(starknet_address);
+        }
 
-        Ok(self
+        let evm_address: Felt252Wrapper = ethereum_address.into();
+        let starknet_address = self
             .kakarot_contract
-            .compute_starknet_address(&self.starknet_provider, &ethereum_address, starknet_block_id)
-            .await?)
+            .compute_starknet_address(&self.starknet_provider, &evm_address.into(), starknet_block_id)
+            .await?;
+        self.cache.insert_address_pair(ethereum_address, starknet_address);
+        Ok(starknet_address)
+    }
+
+    /// Returns true if the Starknet account is a Kakarot account. The class of an account is
+    /// looked up once and cached; failed lookups are not cached.
+    async fn is_kakarot_account(&self, starknet_address: FieldElement) -> Result<bool, EthApiError<P::Error>> {
+        let class_hash = match self.cache.class_hash(&starknet_address) {
+            Some(class_hash) => class_hash,
+            None => {
+                let class_hash = self
+                    .starknet_provider
+                    .get_class_hash_at(StarknetBlockId::Tag(BlockTag::Latest), starknet_address)
+                    .await?;
+                self.cache.insert_class_hash(starknet_address, class_hash);
+                class_hash
+            }
+        };
+        Ok(class_hash == self.proxy_account_class_hash())
+    }
+
+    /// Returns the Kakarot transactions among the given transactions, in the same order. The
+    /// senders of the transactions are deduplicated and looked up concurrently; the filtering
+    /// fails when a sender can't be looked up.
+    async fn filter_kakarot_transactions(
+        &self,
+        transactions: StarknetTransactions,
+    ) -> Result<StarknetTransactions, EthApiError<P::Error>> {
+        let transactions: Vec<StarknetTransaction> =
+            Into::<Vec<TransactionType>>::into(transactions).into_iter().map(StarknetTransaction::from).collect();
+
+        // Transactions without a sender, such as L1 handler transactions, aren't Kakarot
+        // transactions.
+        let senders: HashSet<FieldElement> =
+            transactions.iter().filter_map(|tx| tx.sender_address().ok()).map(FieldElement::from).collect();
+        let handles = senders.into_iter().map(|sender| async move {
+            match self.is_kakarot_account(sender).await {
+                Ok(is_kakarot_account) => Ok((sender, is_kakarot_account)),
+                Err(EthApiError::RequestError(ProviderError::StarknetError(StarknetError::ContractNotFound))) => {
+                    Ok((sender, false))
+                }
+                Err(err) => Err(err),
+            }
+        });
+        let kakarot_accounts: HashMap<FieldElement, bool> =
+            join_all(handles).await.into_iter().collect::<Result<_, _>>()?;
+
+        let is_kakarot_tx = |tx: &StarknetTransaction| {
+            tx.sender_address()
+                .ok()
+                .and_then(|sender| kakarot_accounts.get(&FieldElement::from(sender)).copied())
+                .unwrap_or(false)
+        };
+        let transactions: Vec<TransactionType> =
+            transactions.into_iter().filter(is_kakarot_tx).map(TransactionType::from).collect();
+        Ok(transactions.into())
     }
 
     /// Returns the Ethereum transactions executed by the Kakarot contract by filtering the provided
@@ -709,13 +1734,26 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
         initial_transactions: StarknetTransactions,
         block_hash: Option<H256>,
         block_number: Option<U256>,
-    ) -> BlockTransactions {
-        let handles = Into::<Vec<TransactionType>>::into(initial_transactions).into_iter().map(|tx| async move {
+    ) -> Result<BlockTransactions, EthApiError<P::Error>> {
+        let transactions = self.filter_kakarot_transactions(initial_transactions).await?;
+        let handles = Into::<Vec<TransactionType>>::into(transactions).into_iter().map(|tx| async move {
             let tx = Into::<StarknetTransaction>::into(tx);
-            tx.to_eth_transaction(self, block_hash, block_number, None).await
+            tx.to_eth_transactions(self, block_hash, block_number, None).await
         });
-        let transactions_vec = join_all(handles).await.into_iter().filter_map(|transaction| transaction.ok()).collect();
-        BlockTransactions::Full(transactions_vec)
+        let transactions_vec =
+            join_all(handles).await.into_iter().collect::<Result<Vec<_>, _>>()?.into_iter().flatten().collect();
+        Ok(BlockTransactions::Full(transactions_vec))
+    }
+
+    /// Returns the header fields derived from the execution of the Kakarot transactions among the
+    /// given transactions of the block with the given hash and number, or of the pending block.
+    async fn block_execution(
+        &self,
+        transactions: StarknetTransactions,
+        block: Option<(FieldElement, u64)>,
+    ) -> Result<BlockExecution, EthApiError<P::Error>> {
+        let executed = self.executed_transactions(transactions, block).await?;
+        Ok(BlockExecution::new(&executed))
     }
 
     /// Get the Kakarot eth block provided a Starknet block id.
@@ -724,20 +1762,62 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
         block_id: StarknetBlockId,
         hydrated_tx: bool,
     ) -> Result<RichBlock, EthApiError<P::Error>> {
-        if hydrated_tx {
-            let block = self.starknet_provider.get_block_with_txs(block_id).await?;
-            let starknet_block = BlockWithTxs::new(block);
-            Ok(starknet_block.to_eth_block(self).await)
+        if let (Some(index), Some(number)) = (&self.index, self.indexed_block_number(&block_id)?) {
+            if let Some(block) = index.block_by_number(number, hydrated_tx)? {
+                return Ok(block);
+            }
+        }
+        if let Some(block) = match block_id {
+            StarknetBlockId::Number(number) => self.cache.block(number, hydrated_tx),
+            _ => None,
+        } {
+            return Ok(block);
+        }
+
+        let block = if hydrated_tx {
+            let block = self.block_with_txs(&block_id).await?;
+            BlockWithTxs::new(block).to_eth_block(self).await?
         } else {
-            let block = self.starknet_provider.get_block_with_tx_hashes(block_id).await?;
-            let starknet_block = BlockWithTxHashes::new(block);
-            Ok(starknet_block.to_eth_block(self).await)
+            let block = self.block_with_tx_hashes(&block_id).await?;
+            BlockWithTxHashes::new(block).to_eth_block(self).await?
+        };
+        self.cache.insert_block(&block, hydrated_tx);
+        Ok(block)
+    }
+
+    /// Returns the fee estimate of the transaction at the given block. The estimate comes from the
+    /// feeder gateway simulation, which skips the validation of the transaction, on the gateway
+    /// networks, and from `starknet_estimateFee` otherwise.
+    async fn estimate_fee(
+        &self,
+        request: BroadcastedInvokeTransactionV1,
+        block_id: StarknetBlockId,
+    ) -> Result<FeeEstimate, EthApiError<P::Error>> {
+        if self.network.gateway_url().is_ok() {
+            let block_number = self.map_block_id_to_block_number(&block_id).await?;
+            let fee_estimate = self.simulate_transaction(request, block_number, true).await?.fee_estimation;
+            return Ok(FeeEstimate {
+                gas_consumed: fee_estimate.gas_usage,
+                gas_price: fee_estimate.gas_price,
+                overall_fee: fee_estimate.overall_fee,
+            });
         }
+
+        let request = BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V1(request));
+        let fee_estimates = self.starknet_provider.estimate_fee(vec![request], block_id).await?;
+        let fee_estimates_len = fee_estimates.len();
+        fee_estimates.into_iter().next().ok_or_else(|| {
+            DataDecodingError::InvalidReturnArrayLength {
+                entrypoint: "starknet_estimateFee".into(),
+                expected: 1,
+                actual: fee_estimates_len,
+            }
+            .into()
+        })
     }
 
-    /// Get the simulation of the BroadcastedInvokeTransactionV1 result
-    /// FIXME 306: make simulate_transaction agnostic of the provider (rn only works for
-    /// a SequencerGatewayProvider on testnets and mainnet)
+    /// Simulates the transaction with the feeder gateway. Only available on the gateway networks,
+    /// see `estimate_fee` for the fee estimate on any network.
     async fn simulate_transaction(
         &self,
         request: BroadcastedInvokeTransactionV1,
@@ -747,32 +1827,9 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
         let client = Client::new();
 
         // build the url for simulate transaction
-        let url = self.network.gateway_url();
-
-        // if the url is invalid, return an empty simulation (allows to call simulate_transaction on Kakana,
-        // Madara, etc.)
-        if url.is_err() {
-            let gas_usage = (*ESTIMATE_GAS).try_into().map_err(ConversionError::UintConversionError)?;
-            let gas_price: Felt252Wrapper = (*MAX_FEE).into();
-            let overall_fee = Felt252Wrapper::from(gas_usage) * gas_price.clone();
-            return Ok(TransactionSimulationInfo {
-                trace: TransactionTrace {
-                    function_invocation: None,
-                    fee_transfer_invocation: None,
-                    validate_invocation: None,
-                    signature: vec![],
-                },
-                fee_estimation: FeeEstimate {
-                    gas_usage,
-                    gas_price: gas_price.try_into()?,
-                    overall_fee: overall_fee.try_into()?,
-                    unit: FeeUnit::Wei,
-                },
-            });
-        }
+        let url = self.network.gateway_url()?;
 
         let mut url = url
-            .unwrap() // safe unwrap because we checked for error above
             .join("simulate_transaction")
             .map_err(|e| EthApiError::FeederGatewayError(format!("gateway url parsing error: {:?}", e)))?;
 
@@ -811,4 +1868,58 @@ impl<P: Provider + Send + Sync> KakarotStarknetApi<P> for KakarotClient<P> {
 
         Ok(resp)
     }
+
+    /// Returns the status of the Starknet transaction. The rejection reason is only reported by the
+    /// feeder gateway.
+    async fn starknet_transaction_status(
+        &self,
+        starknet_hash: FieldElement,
+    ) -> Result<Option<StarknetTransactionStatus>, EthApiError<P::Error>> {
+        if self.network.gateway_url().is_ok() {
+            return self.gateway_transaction_status(starknet_hash).await;
+        }
+
+        let receipt = match self.starknet_provider.get_transaction_receipt(starknet_hash).await {
+            Ok(receipt) => receipt,
+            Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let status = match receipt {
+            MaybePendingTransactionReceipt::PendingReceipt(_) => SubmissionStatus::Pending,
+            MaybePendingTransactionReceipt::Receipt(receipt) => match receipt {
+                StarknetTransactionReceipt::Invoke(receipt) => receipt.status.into(),
+                StarknetTransactionReceipt::L1Handler(receipt) => receipt.status.into(),
+                StarknetTransactionReceipt::Declare(receipt) => receipt.status.into(),
+                StarknetTransactionReceipt::Deploy(receipt) => receipt.status.into(),
+                StarknetTransactionReceipt::DeployAccount(receipt) => receipt.status.into(),
+            },
+        };
+        Ok(Some(StarknetTransactionStatus { status, failure_reason: None }))
+    }
+
+    /// Records the Starknet hash of the transaction with the given Ethereum hash.
+    fn save_transaction_hash(&self, ethereum_hash: H256, starknet_hash: H256) -> Result<(), EthApiError<P::Error>> {
+        if let Some(transaction_hashes) = &self.transaction_hashes {
+            transaction_hashes.insert(ethereum_hash, starknet_hash)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the Ethereum hash of the transaction with the given Starknet hash, if it is known.
+    fn ethereum_transaction_hash(&self, starknet_hash: H256) -> Result<Option<H256>, EthApiError<P::Error>> {
+        let Some(transaction_hashes) = &self.transaction_hashes else { return Ok(None) };
+        Ok(transaction_hashes.ethereum_hash(starknet_hash)?)
+    }
+
+    /// Returns the Starknet hash of the transaction with the given Ethereum or Starknet hash.
+    /// Hashes which aren't mapped are considered to be Starknet hashes, `None` is returned if they
+    /// aren't valid field elements.
+    fn starknet_transaction_hash(&self, hash: H256) -> Result<Option<FieldElement>, EthApiError<P::Error>> {
+        let mapped = match &self.transaction_hashes {
+            Some(transaction_hashes) => transaction_hashes.starknet_hash(hash)?,
+            None => None,
+        };
+        let Ok(hash) = Felt252Wrapper::try_from(mapped.unwrap_or(hash)) else { return Ok(None) };
+        Ok(Some(hash.into()))
+    }
 }
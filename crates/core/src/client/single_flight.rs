@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use tokio::sync::broadcast;
+
+type InFlightCalls<V> = Mutex<HashMap<String, InFlightCall<V>>>;
+
+/// Call in flight, identified so that only the leading call which issued it removes it.
+#[derive(Debug)]
+struct InFlightCall<V> {
+    id: u64,
+    sender: broadcast::Sender<V>,
+}
+
+/// Deduplicates identical concurrent upstream calls: while a call is in flight, the calls with the
+/// same key wait for its result instead of being issued.
+///
+/// Errors can't be shared, so the waiting calls are issued on their own when the leading call
+/// fails or is cancelled.
+#[derive(Debug)]
+pub struct SingleFlight<V> {
+    in_flight: InFlightCalls<V>,
+    next_id: AtomicU64,
+}
+
+impl<V> Default for SingleFlight<V> {
+    fn default() -> Self {
+        Self { in_flight: Mutex::default(), next_id: AtomicU64::default() }
+    }
+}
+
+impl<V: Clone> SingleFlight<V> {
+    /// Returns the result of the call in flight with the same key, or else issues the call.
+    pub async fn run<F, Fut, E>(&self, key: String, call: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, E>>,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let receiver = {
+            let mut in_flight = self.in_flight.lock().expect("single flight lock poisoned");
+            match in_flight.get(&key) {
+                Some(call) => Some(call.sender.subscribe()),
+                None => {
+                    in_flight.insert(key.clone(), InFlightCall { id, sender: broadcast::channel(1).0 });
+                    None
+                }
+            }
+        };
+
+        if let Some(mut receiver) = receiver {
+            return match receiver.recv().await {
+                Ok(value) => Ok(value),
+                Err(_) => call().await,
+            };
+        }
+
+        let leading_call = LeadingCall { in_flight: &self.in_flight, key, id };
+        let result = call().await;
+        if let Ok(value) = &result {
+            leading_call.complete(value);
+        }
+        result
+    }
+}
+
+/// Call in flight, removed from the in flight calls when it completes or is dropped. The call is
+/// only removed while it is the one in flight for its key, so that a call issued with the same key
+/// after its completion is left in flight.
+struct LeadingCall<'a, V> {
+    in_flight: &'a InFlightCalls<V>,
+    key: String,
+    id: u64,
+}
+
+impl<V> LeadingCall<'_, V> {
+    /// Removes the call from the in flight calls, returning the sender of its result if it was
+    /// still in flight.
+    fn remove(&self, in_flight: &mut HashMap<String, InFlightCall<V>>) -> Option<broadcast::Sender<V>> {
+        if in_flight.get(&self.key).map_or(true, |call| call.id != self.id) {
+            return None;
+        }
+        in_flight.remove(&self.key).map(|call| call.sender)
+    }
+}
+
+impl<V: Clone> LeadingCall<'_, V> {
+    fn complete(self, value: &V) {
+        let sender = self.remove(&mut self.in_flight.lock().expect("single flight lock poisoned"));
+        if let Some(sender) = sender {
+            // No call waiting for the result is not an error.
+            let _ = sender.send(value.clone());
+        }
+    }
+}
+
+impl<V> Drop for LeadingCall<'_, V> {
+    fn drop(&mut self) {
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            self.remove(&mut in_flight);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_identical_concurrent_calls_are_issued_once() {
+        // Given
+        let single_flight = SingleFlight::default();
+        let calls = AtomicU64::default();
+        let calls = &calls;
+        let call = || async move {
+            tokio::task::yield_now().await;
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, ()>(42)
+        };
+
+        // When
+        let (first, second) =
+            tokio::join!(single_flight.run("key".into(), call), single_flight.run("key".into(), call));
+        let third = single_flight.run("key".into(), call).await;
+
+        // Then
+        assert_eq!((Ok(42), Ok(42), Ok(42)), (first, second, third));
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_waiting_calls_are_issued_when_leading_call_fails() {
+        // Given
+        let single_flight = SingleFlight::default();
+        let calls = AtomicU64::default();
+        let calls = &calls;
+        let call = || async move {
+            tokio::task::yield_now().await;
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err("unavailable"),
+                _ => Ok(42),
+            }
+        };
+
+        // When
+        let (first, second) =
+            tokio::join!(single_flight.run("key".into(), call), single_flight.run("key".into(), call));
+
+        // Then
+        assert_eq!((Err("unavailable"), Ok(42)), (first, second));
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_dropped_leading_call_leaves_the_next_call_in_flight() {
+        // Given
+        let in_flight: InFlightCalls<u64> = Mutex::default();
+        let key = String::from("key");
+        in_flight.lock().unwrap().insert(key.clone(), InFlightCall { id: 0, sender: broadcast::channel(1).0 });
+        let leading_call = LeadingCall { in_flight: &in_flight, key: key.clone(), id: 0 };
+        leading_call.complete(&42);
+        let next_call = LeadingCall { in_flight: &in_flight, key: key.clone(), id: 1 };
+        in_flight.lock().unwrap().insert(key.clone(), InFlightCall { id: 1, sender: broadcast::channel(1).0 });
+
+        // When
+        let stale_call = LeadingCall { in_flight: &in_flight, key: key.clone(), id: 0 };
+        drop(stale_call);
+
+        // Then
+        assert_eq!(Some(1), in_flight.lock().unwrap().get(&key).map(|call| call.id));
+        drop(next_call);
+        assert!(in_flight.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,499 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use reth_primitives::{H256, U64};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{BroadcastedInvokeTransactionV1, FieldElement, TransactionStatus};
+use starknet::providers::Provider;
+
+use super::api::KakarotEthApi;
+use super::constants::{MAX_REBROADCASTS, REBROADCAST_TIMEOUT, SUBMISSION_RETENTION, SUBMISSION_TRACKER_POLL_INTERVAL};
+use super::errors::EthApiError;
+use crate::models::felt::Felt252Wrapper;
+use crate::txpool::TransactionPool;
+
+/// Status of a transaction submitted to Starknet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SubmissionStatus {
+    /// Submitted, but not included in a block yet.
+    Received,
+    Pending,
+    AcceptedOnL2,
+    AcceptedOnL1,
+    /// Included in a block, but its execution failed.
+    Reverted,
+    Rejected,
+    /// Unknown to Starknet after all the rebroadcasts.
+    Dropped,
+}
+
+impl SubmissionStatus {
+    /// Returns true if the status can no longer change.
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::AcceptedOnL1 | Self::Reverted | Self::Rejected | Self::Dropped)
+    }
+
+    /// Returns true if the transaction will never be included, which frees its nonce.
+    pub fn is_excluded(&self) -> bool {
+        matches!(self, Self::Rejected | Self::Dropped)
+    }
+}
+
+impl From<TransactionStatus> for SubmissionStatus {
+    fn from(status: TransactionStatus) -> Self {
+        match status {
+            TransactionStatus::Pending => Self::Pending,
+            TransactionStatus::AcceptedOnL2 => Self::AcceptedOnL2,
+            TransactionStatus::AcceptedOnL1 => Self::AcceptedOnL1,
+            TransactionStatus::Rejected => Self::Rejected,
+        }
+    }
+}
+
+/// Status of a transaction reported by Starknet, along with the reason of its rejection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StarknetTransactionStatus {
+    pub status: SubmissionStatus,
+    pub failure_reason: Option<String>,
+}
+
+/// Response of the `get_transaction_status` endpoint of the feeder gateway.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GatewayTransactionStatus {
+    tx_status: GatewayStatus,
+    #[serde(default)]
+    tx_failure_reason: Option<GatewayFailureReason>,
+    #[serde(default)]
+    tx_revert_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum GatewayStatus {
+    NotReceived,
+    Received,
+    Pending,
+    AcceptedOnL2,
+    AcceptedOnL1,
+    Rejected,
+    Reverted,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GatewayFailureReason {
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+impl GatewayTransactionStatus {
+    /// Returns the status of the transaction, or `None` if the gateway doesn't know about it.
+    pub fn into_status(self) -> Option<StarknetTransactionStatus> {
+        let status = match self.tx_status {
+            GatewayStatus::NotReceived => return None,
+            GatewayStatus::Received => SubmissionStatus::Received,
+            GatewayStatus::Pending => SubmissionStatus::Pending,
+            GatewayStatus::AcceptedOnL2 => SubmissionStatus::AcceptedOnL2,
+            GatewayStatus::AcceptedOnL1 => SubmissionStatus::AcceptedOnL1,
+            GatewayStatus::Reverted => SubmissionStatus::Reverted,
+            GatewayStatus::Rejected => SubmissionStatus::Rejected,
+        };
+        let failure_reason = self.tx_failure_reason.and_then(|reason| reason.error_message).or(self.tx_revert_reason);
+        Some(StarknetTransactionStatus { status, failure_reason })
+    }
+}
+
+/// A status of a submission and the Unix timestamp, in seconds, at which it was observed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusUpdate {
+    pub status: SubmissionStatus,
+    pub timestamp: U64,
+}
+
+impl StatusUpdate {
+    fn now(status: SubmissionStatus) -> Self {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        Self { status, timestamp: U64::from(timestamp) }
+    }
+}
+
+/// Lifecycle of a transaction submitted to Starknet, as returned by `kakarot_getTransactionStatus`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionLifecycle {
+    pub hash: H256,
+    pub starknet_hash: H256,
+    pub status: SubmissionStatus,
+    /// The successive statuses of the transaction, from the oldest.
+    pub history: Vec<StatusUpdate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejection_reason: Option<String>,
+    pub rebroadcasts: u32,
+}
+
+impl TransactionLifecycle {
+    pub fn new(hash: H256, starknet_hash: H256, status: StarknetTransactionStatus) -> Self {
+        Self {
+            hash,
+            starknet_hash,
+            status: status.status,
+            history: vec![StatusUpdate::now(status.status)],
+            rejection_reason: status.failure_reason,
+            rebroadcasts: 0,
+        }
+    }
+
+    fn update(&mut self, status: StarknetTransactionStatus) {
+        if status.failure_reason.is_some() {
+            self.rejection_reason = status.failure_reason;
+        }
+        if status.status != self.status {
+            self.status = status.status;
+            self.history.push(StatusUpdate::now(status.status));
+        }
+    }
+}
+
+/// A tracked submission, with the request to rebroadcast if Starknet drops it.
+#[derive(Debug, Clone)]
+struct Submission {
+    /// The lifecycle of the Starknet transaction, under the hash of its first Ethereum transaction.
+    lifecycle: TransactionLifecycle,
+    /// The hashes of the Ethereum transactions wrapped in the Starknet transaction.
+    hashes: Vec<H256>,
+    request: BroadcastedInvokeTransactionV1,
+    submitted_at: Instant,
+    broadcast_at: Instant,
+}
+
+/// Follows the Starknet status of the submitted transactions.
+///
+/// A submission which remains unknown to Starknet for the rebroadcast timeout is rebroadcast, at
+/// most `max_rebroadcasts` times, after which it is dropped. Submissions are forgotten once the
+/// retention elapsed. The transactions of a submission which is rejected or dropped are evicted
+/// from the transaction pool.
+pub struct SubmissionTracker {
+    submissions: Mutex<Submissions>,
+    transaction_pool: Option<Arc<TransactionPool>>,
+    max_rebroadcasts: u32,
+    rebroadcast_timeout: Duration,
+    retention: Duration,
+}
+
+/// The tracked submissions, one per Starknet transaction.
+#[derive(Debug, Default)]
+struct Submissions {
+    /// The submissions by the hash of their first Ethereum transaction.
+    submissions: HashMap<H256, Submission>,
+    /// The hash of the first Ethereum transaction of the submission of each Ethereum transaction.
+    first_hashes: HashMap<H256, H256>,
+}
+
+impl Default for SubmissionTracker {
+    fn default() -> Self {
+        Self::new(MAX_REBROADCASTS, REBROADCAST_TIMEOUT, SUBMISSION_RETENTION)
+    }
+}
+
+impl SubmissionTracker {
+    pub fn new(max_rebroadcasts: u32, rebroadcast_timeout: Duration, retention: Duration) -> Self {
+        Self { submissions: Mutex::default(), transaction_pool: None, max_rebroadcasts, rebroadcast_timeout, retention }
+    }
+
+    /// Evicts the transactions of the rejected or dropped submissions from the transaction pool.
+    #[must_use]
+    pub fn with_transaction_pool(mut self, transaction_pool: Arc<TransactionPool>) -> Self {
+        self.transaction_pool = Some(transaction_pool);
+        self
+    }
+
+    /// Starts tracking the submission of the Starknet transaction wrapping the Ethereum
+    /// transactions `hashes`.
+    pub fn track(&self, hashes: Vec<H256>, starknet_hash: H256, request: BroadcastedInvokeTransactionV1) {
+        let Some(&hash) = hashes.first() else { return };
+        let received = StarknetTransactionStatus { status: SubmissionStatus::Received, failure_reason: None };
+
+        let mut submissions = self.submissions.lock().expect("submissions lock poisoned");
+        submissions.first_hashes.extend(hashes.iter().map(|inner_hash| (*inner_hash, hash)));
+        let submission = Submission {
+            lifecycle: TransactionLifecycle::new(hash, starknet_hash, received),
+            hashes,
+            request,
+            submitted_at: Instant::now(),
+            broadcast_at: Instant::now(),
+        };
+        submissions.submissions.insert(hash, submission);
+    }
+
+    /// Returns the lifecycle of the submission of the Ethereum transaction.
+    pub fn lifecycle(&self, hash: H256) -> Option<TransactionLifecycle> {
+        let submissions = self.submissions.lock().expect("submissions lock poisoned");
+        let first_hash = submissions.first_hashes.get(&hash)?;
+        let lifecycle = &submissions.submissions.get(first_hash)?.lifecycle;
+        Some(TransactionLifecycle { hash, ..lifecycle.clone() })
+    }
+
+    /// Returns the hashes of the first Ethereum transaction and of the Starknet transaction of the
+    /// submissions whose status can still change, after forgetting the submissions older than the
+    /// retention.
+    pub fn unsettled(&self) -> Vec<(H256, H256)> {
+        let mut submissions = self.submissions.lock().expect("submissions lock poisoned");
+        let Submissions { submissions, first_hashes } = &mut *submissions;
+        submissions.retain(|_, submission| submission.submitted_at.elapsed() < self.retention);
+        first_hashes.retain(|_, first_hash| submissions.contains_key(first_hash));
+        submissions
+            .values()
+            .filter(|submission| !submission.lifecycle.status.is_final())
+            .map(|submission| (submission.lifecycle.hash, submission.lifecycle.starknet_hash))
+            .collect()
+    }
+
+    /// Records the status reported by Starknet for the submission of the first Ethereum
+    /// transaction `hash`, `None` when Starknet doesn't know about the transaction. Returns the
+    /// request to rebroadcast when the transaction remained unknown for the rebroadcast timeout.
+    pub fn update(
+        &self,
+        hash: H256,
+        status: Option<StarknetTransactionStatus>,
+    ) -> Option<BroadcastedInvokeTransactionV1> {
+        let mut submissions = self.submissions.lock().expect("submissions lock poisoned");
+        let submission = submissions.submissions.get_mut(&hash)?;
+
+        if let Some(status) = status {
+            submission.lifecycle.update(status);
+            self.evict_excluded(submission);
+            return None;
+        }
+        if submission.broadcast_at.elapsed() < self.rebroadcast_timeout {
+            return None;
+        }
+        if submission.lifecycle.rebroadcasts >= self.max_rebroadcasts {
+            submission
+                .lifecycle
+                .update(StarknetTransactionStatus { status: SubmissionStatus::Dropped, failure_reason: None });
+            self.evict_excluded(submission);
+            return None;
+        }
+
+        submission.lifecycle.rebroadcasts += 1;
+        submission.broadcast_at = Instant::now();
+        Some(submission.request.clone())
+    }
+
+    /// Records the Starknet hash returned by the rebroadcast of the submission of the first
+    /// Ethereum transaction `hash`.
+    pub fn rebroadcast(&self, hash: H256, starknet_hash: H256) {
+        let mut submissions = self.submissions.lock().expect("submissions lock poisoned");
+        if let Some(submission) = submissions.submissions.get_mut(&hash) {
+            submission.lifecycle.starknet_hash = starknet_hash;
+        }
+    }
+
+    /// Evicts the transactions of the submission from the transaction pool once Starknet rejected
+    /// or dropped it, so that their nonce can be used again.
+    fn evict_excluded(&self, submission: &Submission) {
+        let Some(transaction_pool) = &self.transaction_pool else { return };
+        if submission.lifecycle.status.is_excluded() {
+            for hash in &submission.hashes {
+                transaction_pool.evict(*hash);
+            }
+        }
+    }
+
+    /// Spawns the polling of the tracked submissions on the Tokio runtime.
+    pub fn spawn<P: Provider + Send + Sync + 'static>(
+        tracker: Arc<Self>,
+        kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SUBMISSION_TRACKER_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                for (hash, starknet_hash) in tracker.unsettled() {
+                    if let Err(err) = tracker.poll(kakarot_client.as_ref(), hash, starknet_hash).await {
+                        log::error!("Failed to poll the status of the transaction {hash:?}: {err}");
+                    }
+                }
+            }
+        })
+    }
+
+    /// Polls the Starknet status of a submission, and rebroadcasts it if needed.
+    pub async fn poll<P: Provider + Send + Sync>(
+        &self,
+        kakarot_client: &dyn KakarotEthApi<P>,
+        hash: H256,
+        starknet_hash: H256,
+    ) -> Result<(), EthApiError<P::Error>> {
+        let starknet_hash: FieldElement = Felt252Wrapper::try_from(starknet_hash)?.into();
+        let status = kakarot_client.starknet_transaction_status(starknet_hash).await?;
+
+        let Some(request) = self.update(hash, status) else { return Ok(()) };
+        log::warn!("Rebroadcasting the transaction {hash:?}, unknown to Starknet");
+        let starknet_hash = kakarot_client.submit_starknet_transaction(request).await?;
+        self.rebroadcast(hash, starknet_hash);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reth_primitives::{Address, Bytes, Signature, Transaction, TransactionSigned, TxEip1559};
+
+    use super::*;
+    use crate::txpool::{PooledTransaction, TransactionPoolConfig};
+
+    fn tracker(max_rebroadcasts: u32) -> SubmissionTracker {
+        SubmissionTracker::new(max_rebroadcasts, Duration::ZERO, SUBMISSION_RETENTION)
+    }
+
+    fn request() -> BroadcastedInvokeTransactionV1 {
+        BroadcastedInvokeTransactionV1 {
+            max_fee: FieldElement::ZERO,
+            signature: vec![],
+            nonce: FieldElement::ONE,
+            sender_address: FieldElement::ONE,
+            calldata: vec![],
+        }
+    }
+
+    #[test]
+    fn test_lifecycle() {
+        // Given
+        let tracker = tracker(MAX_REBROADCASTS);
+        let hash = H256::from_low_u64_be(1);
+        tracker.track(vec![hash], H256::from_low_u64_be(2), request());
+
+        // When
+        tracker
+            .update(hash, Some(StarknetTransactionStatus { status: SubmissionStatus::Pending, failure_reason: None }));
+        tracker
+            .update(hash, Some(StarknetTransactionStatus { status: SubmissionStatus::Pending, failure_reason: None }));
+        tracker.update(
+            hash,
+            Some(StarknetTransactionStatus {
+                status: SubmissionStatus::Rejected,
+                failure_reason: Some("Invalid transaction nonce".into()),
+            }),
+        );
+
+        // Then
+        let lifecycle = tracker.lifecycle(hash).unwrap();
+        assert_eq!(SubmissionStatus::Rejected, lifecycle.status);
+        assert_eq!(
+            vec![SubmissionStatus::Received, SubmissionStatus::Pending, SubmissionStatus::Rejected],
+            lifecycle.history.iter().map(|update| update.status).collect::<Vec<_>>()
+        );
+        assert_eq!(Some("Invalid transaction nonce".to_string()), lifecycle.rejection_reason);
+        assert!(tracker.unsettled().is_empty());
+    }
+
+    #[test]
+    fn test_rebroadcast_is_bounded() {
+        // Given
+        let tracker = tracker(2);
+        let hash = H256::from_low_u64_be(1);
+        tracker.track(vec![hash], H256::from_low_u64_be(2), request());
+
+        // When
+        let rebroadcasts = (0..3).filter_map(|_| tracker.update(hash, None)).count();
+
+        // Then
+        let lifecycle = tracker.lifecycle(hash).unwrap();
+        assert_eq!(2, rebroadcasts);
+        assert_eq!(2, lifecycle.rebroadcasts);
+        assert_eq!(SubmissionStatus::Dropped, lifecycle.status);
+    }
+
+    #[test]
+    fn test_batch_is_tracked_once() {
+        // Given
+        let tracker = tracker(MAX_REBROADCASTS);
+        let (first, second) = (H256::from_low_u64_be(1), H256::from_low_u64_be(3));
+        tracker.track(vec![first, second], H256::from_low_u64_be(2), request());
+
+        // When
+        tracker.update(
+            first,
+            Some(StarknetTransactionStatus { status: SubmissionStatus::AcceptedOnL2, failure_reason: None }),
+        );
+
+        // Then
+        assert_eq!(vec![(first, H256::from_low_u64_be(2))], tracker.unsettled());
+        let lifecycle = tracker.lifecycle(second).unwrap();
+        assert_eq!(second, lifecycle.hash);
+        assert_eq!(SubmissionStatus::AcceptedOnL2, lifecycle.status);
+    }
+
+    #[test]
+    fn test_rejected_transaction_is_evicted_from_pool() {
+        // Given
+        let transaction = TransactionSigned::from_transaction_and_signature(
+            Transaction::Eip1559(TxEip1559 { nonce: 3, ..Default::default() }),
+            Signature::default(),
+        );
+        let hash = transaction.hash();
+        let pool = Arc::new(TransactionPool::new(TransactionPoolConfig::default()));
+        pool.add(PooledTransaction::new(transaction, Address::zero(), Bytes::default(), true)).unwrap();
+        let tracker = tracker(MAX_REBROADCASTS).with_transaction_pool(pool.clone());
+        tracker.track(vec![hash], H256::from_low_u64_be(2), request());
+
+        // When
+        tracker
+            .update(hash, Some(StarknetTransactionStatus { status: SubmissionStatus::Rejected, failure_reason: None }));
+
+        // Then
+        assert!(pool.senders().is_empty());
+    }
+
+    #[test]
+    fn test_gateway_transaction_status() {
+        // Given
+        let rejected = serde_json::json!({
+            "tx_status": "REJECTED",
+            "tx_failure_reason": { "code": "INVALID_TRANSACTION_NONCE", "error_message": "Invalid transaction nonce" }
+        });
+        let reverted =
+            serde_json::json!({ "tx_status": "REVERTED", "tx_revert_reason": "Error in the called contract" });
+        let not_received = serde_json::json!({ "tx_status": "NOT_RECEIVED" });
+
+        // When
+        let rejected: GatewayTransactionStatus = serde_json::from_value(rejected).unwrap();
+        let reverted: GatewayTransactionStatus = serde_json::from_value(reverted).unwrap();
+        let not_received: GatewayTransactionStatus = serde_json::from_value(not_received).unwrap();
+
+        // Then
+        assert_eq!(
+            Some(StarknetTransactionStatus {
+                status: SubmissionStatus::Rejected,
+                failure_reason: Some("Invalid transaction nonce".into())
+            }),
+            rejected.into_status()
+        );
+        assert_eq!(
+            Some(StarknetTransactionStatus {
+                status: SubmissionStatus::Reverted,
+                failure_reason: Some("Error in the called contract".into())
+            }),
+            reverted.into_status()
+        );
+        assert_eq!(None, not_received.into_status());
+    }
+
+    #[test]
+    fn test_lifecycle_serialization() {
+        // Given
+        let status = StarknetTransactionStatus { status: SubmissionStatus::AcceptedOnL2, failure_reason: None };
+
+        // When
+        let lifecycle = TransactionLifecycle::new(H256::zero(), H256::zero(), status);
+        let value = serde_json::to_value(&lifecycle).unwrap();
+
+        // Then
+        assert_eq!("ACCEPTED_ON_L2", value["status"]);
+        assert_eq!("ACCEPTED_ON_L2", value["history"][0]["status"]);
+        assert!(value.get("rejectionReason").is_none());
+        assert_eq!(lifecycle, serde_json::from_value(value).unwrap());
+    }
+}
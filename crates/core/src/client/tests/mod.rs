@@ -1,9 +1,13 @@
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
 use dojo_test_utils::rpc::MockJsonRpcTransport;
 use reth_primitives::{BlockId, BlockNumberOrTag, Bytes, H256, U256, U64};
-use reth_rpc_types::CallRequest;
-use starknet::core::types::{BlockId as StarknetBlockId, BlockTag, BroadcastedInvokeTransactionV1};
+use reth_rpc_types::{Block, BlockTransactions, CallRequest, Filter, FilterBlockOption, Rich};
+use starknet::core::types::{
+    BlockId as StarknetBlockId, BlockTag, BroadcastedInvokeTransactionV1, MaybePendingBlockWithTxs, Transaction,
+};
 use starknet::providers::jsonrpc::JsonRpcMethod;
 use starknet::providers::sequencer::models::BlockId as SequencerBlockId;
 use starknet::providers::{JsonRpcClient, SequencerGatewayProvider};
@@ -12,14 +16,19 @@ use starknet_crypto::FieldElement;
 use super::config::{Network, SequencerGatewayProviderBuilder};
 use crate::client::api::{KakarotEthApi, KakarotStarknetApi};
 use crate::client::config::StarknetConfig;
-use crate::client::constants::{CHAIN_ID, COUNTER_ADDRESS_TESTNET1, INC_SELECTOR};
+use crate::client::constants::gas::BASE_FEE_PER_GAS;
+use crate::client::constants::{CHAIN_ID, COUNTER_ADDRESS_TESTNET1, INC_SELECTOR, MAX_LOGS_BLOCK_RANGE};
+use crate::client::errors::EthApiError;
 use crate::client::KakarotClient;
+use crate::indexer::hashes::TransactionHashStore;
+use crate::indexer::store::{IndexStore, IndexedBlock};
 use crate::mock::constants::{
-    ABDEL_ETHEREUM_ADDRESS, ABDEL_STARKNET_ADDRESS, ABDEL_STARKNET_ADDRESS_HEX, ACCOUNT_ADDRESS, ACCOUNT_ADDRESS_EVM,
-    COUNTER_ADDRESS_EVM, INC_DATA, KAKAROT_ADDRESS, KAKAROT_TESTNET_ADDRESS, PROXY_ACCOUNT_CLASS_HASH,
-    PROXY_ACCOUNT_CLASS_HASH_HEX,
+    ABDEL_ETHEREUM_ADDRESS, ABDEL_STARKNET_ADDRESS, ABDEL_STARKNET_ADDRESS_HEX, ABDEL_TRANSACTION_HASH,
+    ACCOUNT_ADDRESS, ACCOUNT_ADDRESS_EVM, COUNTER_ADDRESS_EVM, INC_DATA, KAKAROT_ADDRESS, KAKAROT_TESTNET_ADDRESS,
+    OTHER_ADDRESS_HEX, OTHER_PROXY_ACCOUNT_CLASS_HASH_HEX, PROXY_ACCOUNT_CLASS_HASH, PROXY_ACCOUNT_CLASS_HASH_HEX,
 };
 use crate::mock::mock_starknet::{fixtures, mock_starknet_provider, AvailableFixtures, StarknetRpcFixture};
+use crate::models::transaction::StarknetTransaction;
 use crate::wrap_kakarot;
 
 pub fn init_testnet_client() -> KakarotClient<SequencerGatewayProvider> {
@@ -113,6 +122,97 @@ async fn test_fee_history_should_return_oldest_block_0() {
     assert_eq!(U256::from(0), fee_history.oldest_block);
 }
 
+#[tokio::test]
+async fn test_fee_history_should_start_at_genesis_block() {
+    // Given
+    let config =
+        StarknetConfig::new(Network::Katana, *KAKAROT_ADDRESS, *PROXY_ACCOUNT_CLASS_HASH).with_genesis_block(100);
+    let client = KakarotClient::new(config, mock_starknet_provider(Some(fixtures(vec![]))));
+
+    // When
+    let fee_history = client.fee_history(U256::from(10), BlockNumberOrTag::Number(104), None).await.unwrap();
+
+    // Then
+    assert_eq!(U256::from(100), fee_history.oldest_block);
+    assert_eq!(vec![0.9; 4], fee_history.gas_used_ratio);
+}
+
+#[test]
+fn test_starknet_block_id_should_start_at_genesis_block() {
+    // Given
+    let config =
+        StarknetConfig::new(Network::Katana, *KAKAROT_ADDRESS, *PROXY_ACCOUNT_CLASS_HASH).with_genesis_block(100);
+    let client = KakarotClient::new(config, mock_starknet_provider(None));
+
+    // When
+    let earliest = client.starknet_block_id(BlockId::Number(BlockNumberOrTag::Earliest)).unwrap();
+    let before_genesis = client.starknet_block_id(BlockId::Number(BlockNumberOrTag::Number(99)));
+
+    // Then
+    assert_eq!(StarknetBlockId::Number(100), earliest);
+    assert!(matches!(before_genesis, Err(EthApiError::BlockBeforeGenesis(99, 100))));
+}
+
+#[tokio::test]
+async fn test_get_logs_should_fail_on_block_range_above_limit() {
+    // Given
+    let client = init_mock_client(None);
+    let filter = Filter {
+        block_option: FilterBlockOption::Range {
+            from_block: Some(BlockNumberOrTag::Number(0)),
+            to_block: Some(BlockNumberOrTag::Number(MAX_LOGS_BLOCK_RANGE)),
+        },
+        ..Default::default()
+    };
+
+    // When
+    let logs = client.get_logs(filter).await;
+
+    // Then
+    assert!(matches!(logs, Err(EthApiError::LimitExceeded(_))));
+}
+
+#[test]
+fn test_transactions_by_address() {
+    // Given
+    let block: Block =
+        serde_json::from_str(include_str!("../../models/test_data/conversion/eth/block_with_txs.json")).unwrap();
+    let BlockTransactions::Full(transactions) = block.transactions.clone() else { panic!("Block should be hydrated") };
+    let index = Arc::new(IndexStore::temporary().unwrap());
+    index
+        .insert_block(&IndexedBlock {
+            block: Rich { inner: block, extra_info: Default::default() },
+            receipts: vec![],
+            logs: vec![],
+        })
+        .unwrap();
+    let client = init_mock_client(None).with_index(index);
+
+    // When
+    let transaction_hashes = client.transactions_by_address(transactions[0].from).unwrap();
+
+    // Then
+    assert_eq!(Some(transactions.iter().map(|tx| tx.hash).collect()), transaction_hashes);
+    assert_eq!(None, init_mock_client(None).transactions_by_address(transactions[0].from).unwrap());
+}
+
+#[tokio::test]
+async fn test_send_transaction_sync_should_fail_without_waiting_on_undecodable_transaction() {
+    // Given
+    let client = init_mock_client(None);
+
+    // When
+    let receipt = tokio::time::timeout(
+        Duration::from_secs(1),
+        client.send_transaction_sync(Bytes::from(vec![0x01]), Duration::from_secs(60), false),
+    )
+    .await
+    .expect("send_transaction_sync should not wait for a receipt");
+
+    // Then
+    assert!(matches!(receipt, Err(EthApiError::DataDecodingError(_))));
+}
+
 #[tokio::test]
 async fn test_transaction_by_hash() {
     // Given
@@ -141,6 +241,74 @@ async fn test_transaction_by_hash() {
     assert_eq!(U256::from(0), tx.nonce);
 }
 
+#[tokio::test]
+async fn test_transaction_by_ethereum_hash() {
+    // Given
+    let fixtures = fixtures(vec![
+        wrap_kakarot!(JsonRpcMethod::GetTransactionByHash),
+        wrap_kakarot!(JsonRpcMethod::GetTransactionReceipt),
+        AvailableFixtures::GetClassHashAt(ABDEL_STARKNET_ADDRESS_HEX.into(), PROXY_ACCOUNT_CLASS_HASH_HEX.into()),
+        AvailableFixtures::GetEvmAddress,
+    ]);
+    let starknet_hash = H256::from_str("0x03204b4c0e379c3a5ccb80d08661d5a538e95e2960581c9faf7ebcf8ff5a7d3c").unwrap();
+    let transaction_hashes = Arc::new(TransactionHashStore::temporary().unwrap());
+    transaction_hashes.insert(*ABDEL_TRANSACTION_HASH, starknet_hash).unwrap();
+    let client = init_mock_client(Some(fixtures)).with_transaction_hashes(transaction_hashes);
+
+    // When
+    let tx = client.transaction_by_hash(*ABDEL_TRANSACTION_HASH).await.unwrap().unwrap();
+
+    // Then
+    assert_eq!(*ABDEL_TRANSACTION_HASH, tx.hash);
+}
+
+#[tokio::test]
+async fn test_transaction_by_hash_should_not_record_hash_mapping() {
+    // Given
+    let fixtures = fixtures(vec![
+        wrap_kakarot!(JsonRpcMethod::GetTransactionByHash),
+        wrap_kakarot!(JsonRpcMethod::GetTransactionReceipt),
+        AvailableFixtures::GetClassHashAt(ABDEL_STARKNET_ADDRESS_HEX.into(), PROXY_ACCOUNT_CLASS_HASH_HEX.into()),
+        AvailableFixtures::GetEvmAddress,
+    ]);
+    let client =
+        init_mock_client(Some(fixtures)).with_transaction_hashes(Arc::new(TransactionHashStore::temporary().unwrap()));
+    let starknet_hash = H256::from_str("0x03204b4c0e379c3a5ccb80d08661d5a538e95e2960581c9faf7ebcf8ff5a7d3c").unwrap();
+
+    // When
+    let tx = client.transaction_by_hash(starknet_hash).await.unwrap().unwrap();
+
+    // Then
+    assert_eq!(*ABDEL_TRANSACTION_HASH, tx.hash);
+    assert_eq!(None, client.ethereum_transaction_hash(starknet_hash).unwrap());
+}
+
+#[tokio::test]
+async fn test_filter_kakarot_transactions() {
+    // Given
+    let block: MaybePendingBlockWithTxs =
+        serde_json::from_str(include_str!("../../models/test_data/conversion/starknet/block_with_txs.json")).unwrap();
+    let MaybePendingBlockWithTxs::Block(block) = block else { panic!("Block should not be pending") };
+    let fixtures = fixtures(vec![
+        AvailableFixtures::GetClassHashAt(ABDEL_STARKNET_ADDRESS_HEX.into(), PROXY_ACCOUNT_CLASS_HASH_HEX.into()),
+        AvailableFixtures::GetClassHashAt(OTHER_ADDRESS_HEX.into(), OTHER_PROXY_ACCOUNT_CLASS_HASH_HEX.into()),
+    ]);
+    let client = init_mock_client(Some(fixtures));
+
+    // When
+    let transactions: Vec<Transaction> =
+        client.filter_kakarot_transactions(block.transactions.into()).await.unwrap().into();
+
+    // Then
+    assert_eq!(7, transactions.len());
+    assert!(
+        transactions
+            .into_iter()
+            .map(StarknetTransaction::from)
+            .all(|tx| { FieldElement::from(tx.sender_address().unwrap()) == *ABDEL_STARKNET_ADDRESS })
+    );
+}
+
 #[tokio::test]
 #[allow(deprecated)]
 async fn test_simulate_transaction() {
@@ -212,3 +380,52 @@ async fn test_gas_price() {
     // Then
     assert!(gas_price > U256::from(0));
 }
+
+#[tokio::test]
+async fn test_estimate_fee() {
+    // Given
+    let fixtures = fixtures(vec![wrap_kakarot!(JsonRpcMethod::EstimateFee)]);
+    let client = init_mock_client(Some(fixtures));
+    let tx = BroadcastedInvokeTransactionV1 {
+        sender_address: *ABDEL_STARKNET_ADDRESS,
+        calldata: vec![FieldElement::ZERO, FieldElement::ZERO],
+        max_fee: FieldElement::ZERO,
+        nonce: FieldElement::ONE,
+        signature: vec![],
+    };
+
+    // When
+    let fee_estimate = client.estimate_fee(tx, StarknetBlockId::Tag(BlockTag::Latest)).await.unwrap();
+
+    // Then
+    assert_eq!(21_000, fee_estimate.gas_consumed);
+    assert_eq!(1_000_000_000, fee_estimate.gas_price);
+    assert_eq!(fee_estimate.gas_consumed * fee_estimate.gas_price, fee_estimate.overall_fee);
+}
+
+#[tokio::test]
+async fn test_gas_price_from_fee_estimate() {
+    // Given
+    let fixtures = fixtures(vec![wrap_kakarot!(JsonRpcMethod::GetNonce), wrap_kakarot!(JsonRpcMethod::EstimateFee)]);
+    let config = StarknetConfig::new(Network::Katana, *KAKAROT_ADDRESS, *PROXY_ACCOUNT_CLASS_HASH)
+        .with_gas_price_account(*ABDEL_STARKNET_ADDRESS);
+    let client = KakarotClient::new(config, mock_starknet_provider(Some(fixtures)));
+
+    // When
+    let gas_price = client.gas_price().await.unwrap();
+
+    // Then
+    assert_eq!(U256::from(1_000_000_000), gas_price);
+}
+
+#[tokio::test]
+async fn test_gas_price_without_gas_price_account() {
+    // Given
+    let client = init_mock_client(None);
+
+    // When
+    let gas_price = client.gas_price().await.unwrap();
+
+    // Then
+    assert_eq!(U256::from(BASE_FEE_PER_GAS), gas_price);
+}
@@ -0,0 +1,212 @@
+use reth_primitives::{AccessListItem, Transaction, TransactionKind, TransactionSigned, U256};
+use thiserror::Error;
+
+use super::constants::CHAIN_ID;
+use super::helpers::evm_fee_cap;
+
+/// Gas paid by every transaction.
+const TRANSACTION_GAS: u64 = 21_000;
+/// Additional gas paid by contract creations.
+const CREATE_GAS: u64 = 32_000;
+/// Gas paid per zero byte of the input.
+const ZERO_BYTE_GAS: u64 = 4;
+/// Gas paid per non zero byte of the input.
+const NON_ZERO_BYTE_GAS: u64 = 16;
+/// Gas paid per address of the access list.
+const ACCESS_LIST_ADDRESS_GAS: u64 = 2_400;
+/// Gas paid per storage key of the access list.
+const ACCESS_LIST_STORAGE_KEY_GAS: u64 = 1_900;
+
+/// Reasons for which a raw transaction is rejected before its submission to Starknet, with the
+/// error messages of geth.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ValidationError {
+    #[error("only replay-protected (EIP-155) transactions allowed over RPC")]
+    UnprotectedTransaction,
+    #[error("invalid chain id")]
+    InvalidChainId,
+    #[error("nonce too low")]
+    NonceTooLow,
+    #[error("nonce too high")]
+    NonceTooHigh,
+    #[error("intrinsic gas too low")]
+    IntrinsicGasTooLow,
+    #[error("insufficient funds for gas * price + value")]
+    InsufficientFunds,
+    #[error("max fee per gas less than block base fee")]
+    InsufficientMaxFee,
+    #[error("sender account is not deployed")]
+    SenderNotDeployed,
+    #[error("empty transaction batch")]
+    EmptyBatch,
+    #[error("batched transactions must have the same sender")]
+    BatchSenderMismatch,
+    #[error("batched transactions must have the same nonce")]
+    BatchNonceMismatch,
+}
+
+/// State of the sender of a transaction, against which the transaction is validated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SenderState {
+    pub nonce: u64,
+    pub balance: U256,
+}
+
+/// Returns the gas charged to a transaction before the execution of any instruction.
+#[must_use]
+pub fn intrinsic_gas(transaction: &Transaction) -> u64 {
+    let input_gas: u64 =
+        transaction.input().iter().map(|byte| if *byte == 0 { ZERO_BYTE_GAS } else { NON_ZERO_BYTE_GAS }).sum();
+    let create_gas = match transaction.kind() {
+        TransactionKind::Create => CREATE_GAS,
+        TransactionKind::Call(_) => 0,
+    };
+    let access_list_gas = match transaction {
+        Transaction::Legacy(_) => 0,
+        Transaction::Eip2930(tx) => access_list_gas(&tx.access_list.0),
+        Transaction::Eip1559(tx) => access_list_gas(&tx.access_list.0),
+    };
+    TRANSACTION_GAS + create_gas + input_gas + access_list_gas
+}
+
+fn access_list_gas(access_list: &[AccessListItem]) -> u64 {
+    access_list
+        .iter()
+        .map(|item| ACCESS_LIST_ADDRESS_GAS + ACCESS_LIST_STORAGE_KEY_GAS * item.storage_keys.len() as u64)
+        .sum()
+}
+
+/// Validates the transaction against the Kakarot chain id and the state of its sender.
+pub fn validate_transaction(transaction: &TransactionSigned, sender: &SenderState) -> Result<(), ValidationError> {
+    match transaction.chain_id() {
+        None => return Err(ValidationError::UnprotectedTransaction),
+        Some(chain_id) if chain_id != CHAIN_ID => return Err(ValidationError::InvalidChainId),
+        Some(_) => (),
+    }
+
+    if transaction.gas_limit() < intrinsic_gas(&transaction.transaction) {
+        return Err(ValidationError::IntrinsicGasTooLow);
+    }
+
+    if sender.balance < evm_fee_cap(&transaction.transaction) + U256::from(transaction.value()) {
+        return Err(ValidationError::InsufficientFunds);
+    }
+
+    // The nonce is checked last, so that a transaction rejected for a nonce too high is otherwise
+    // valid and can be queued until its predecessors are accepted.
+    match transaction.nonce().cmp(&sender.nonce) {
+        std::cmp::Ordering::Less => Err(ValidationError::NonceTooLow),
+        std::cmp::Ordering::Greater => Err(ValidationError::NonceTooHigh),
+        std::cmp::Ordering::Equal => Ok(()),
+    }
+}
+
+/// Validates a batch of transactions of the same sender, executed in order in a single Starknet
+/// transaction: the transactions share the nonce of the Starknet transaction and each transaction
+/// is validated against the balance left by its predecessors.
+pub fn validate_batch(transactions: &[TransactionSigned], sender: &SenderState) -> Result<(), ValidationError> {
+    let first = transactions.first().ok_or(ValidationError::EmptyBatch)?;
+    if transactions.iter().any(|transaction| transaction.nonce() != first.nonce()) {
+        return Err(ValidationError::BatchNonceMismatch);
+    }
+
+    let mut state = *sender;
+    for transaction in transactions {
+        validate_transaction(transaction, &state)?;
+        state.balance =
+            state.balance.saturating_sub(evm_fee_cap(&transaction.transaction) + U256::from(transaction.value()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use reth_primitives::{AccessList, Address, Bytes, Signature, TxEip1559, TxLegacy, H256};
+
+    use super::*;
+
+    fn signed(transaction: Transaction) -> TransactionSigned {
+        TransactionSigned::from_transaction_and_signature(transaction, Signature::default())
+    }
+
+    fn transfer() -> TxEip1559 {
+        TxEip1559 {
+            chain_id: CHAIN_ID,
+            nonce: 3,
+            gas_limit: 21_000,
+            max_fee_per_gas: 10,
+            to: TransactionKind::Call(Address::from_low_u64_be(1)),
+            value: 1_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_intrinsic_gas() {
+        // Given
+        let creation = Transaction::Eip1559(TxEip1559 {
+            to: TransactionKind::Create,
+            input: Bytes::from(vec![0x00, 0x01]),
+            access_list: AccessList(vec![AccessListItem {
+                address: Address::zero(),
+                storage_keys: vec![H256::zero(), H256::zero()],
+            }]),
+            ..Default::default()
+        });
+
+        // When
+        let gas = intrinsic_gas(&creation);
+
+        // Then
+        assert_eq!(21_000 + 32_000 + 4 + 16 + 2_400 + 2 * 1_900, gas);
+        assert_eq!(21_000, intrinsic_gas(&Transaction::Eip1559(transfer())));
+    }
+
+    #[test]
+    fn test_validate_transaction() {
+        let sender = SenderState { nonce: 3, balance: U256::from(21_000 * 10 + 1_000) };
+
+        assert_eq!(Ok(()), validate_transaction(&signed(Transaction::Eip1559(transfer())), &sender));
+        assert_eq!(
+            Err(ValidationError::UnprotectedTransaction),
+            validate_transaction(
+                &signed(Transaction::Legacy(TxLegacy { chain_id: None, ..Default::default() })),
+                &sender
+            )
+        );
+        assert_eq!(
+            Err(ValidationError::InvalidChainId),
+            validate_transaction(&signed(Transaction::Eip1559(TxEip1559 { chain_id: 1, ..transfer() })), &sender)
+        );
+        assert_eq!(
+            Err(ValidationError::NonceTooLow),
+            validate_transaction(&signed(Transaction::Eip1559(TxEip1559 { nonce: 2, ..transfer() })), &sender)
+        );
+        assert_eq!(
+            Err(ValidationError::NonceTooHigh),
+            validate_transaction(&signed(Transaction::Eip1559(TxEip1559 { nonce: 4, ..transfer() })), &sender)
+        );
+        assert_eq!(
+            Err(ValidationError::IntrinsicGasTooLow),
+            validate_transaction(&signed(Transaction::Eip1559(TxEip1559 { gas_limit: 20_999, ..transfer() })), &sender)
+        );
+        assert_eq!(
+            Err(ValidationError::InsufficientFunds),
+            validate_transaction(&signed(Transaction::Eip1559(TxEip1559 { value: 1_001, ..transfer() })), &sender)
+        );
+    }
+
+    #[test]
+    fn test_validate_batch() {
+        let sender = SenderState { nonce: 3, balance: U256::from(2 * (21_000 * 10 + 1_000)) };
+        let batch = |nonces: &[u64]| -> Vec<TransactionSigned> {
+            nonces.iter().map(|nonce| signed(Transaction::Eip1559(TxEip1559 { nonce: *nonce, ..transfer() }))).collect()
+        };
+
+        assert_eq!(Ok(()), validate_batch(&batch(&[3, 3]), &sender));
+        assert_eq!(Err(ValidationError::EmptyBatch), validate_batch(&[], &sender));
+        assert_eq!(Err(ValidationError::BatchNonceMismatch), validate_batch(&batch(&[3, 4]), &sender));
+        assert_eq!(Err(ValidationError::NonceTooHigh), validate_batch(&batch(&[4, 4]), &sender));
+        assert_eq!(Err(ValidationError::InsufficientFunds), validate_batch(&batch(&[3, 3, 3]), &sender));
+    }
+}
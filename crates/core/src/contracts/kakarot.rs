@@ -1,24 +1,44 @@
 use std::marker::PhantomData;
 
 use reth_primitives::Bytes;
-use starknet::core::types::{BlockId, FunctionCall};
-use starknet::providers::Provider;
+use starknet::core::types::{BlockId, BlockTag, FunctionCall, StarknetError};
+use starknet::core::utils::get_contract_address;
+use starknet::providers::{Provider, ProviderError};
 use starknet_crypto::FieldElement;
 
 use crate::client::constants::selectors::{COMPUTE_STARKNET_ADDRESS, ETH_CALL};
 use crate::client::errors::EthApiError;
-use crate::client::helpers::{decode_eth_call_return, vec_felt_to_bytes, DataDecodingError};
+use crate::client::helpers::{binary_search, decode_eth_call_return, vec_felt_to_bytes, DataDecodingError};
+use crate::models::receipt::EvmExecutionOutcome;
 
 pub struct KakarotContract<P> {
     pub address: FieldElement,
     pub proxy_account_class_hash: FieldElement,
+    /// Whether the Starknet addresses of the EVM addresses are derived locally.
+    local_address_derivation: bool,
     _phantom: PhantomData<P>,
 }
 
 impl<P: Provider + Send + Sync> KakarotContract<P> {
     #[must_use]
     pub fn new(address: FieldElement, proxy_account_class_hash: FieldElement) -> Self {
-        Self { address, proxy_account_class_hash, _phantom: PhantomData }
+        Self { address, proxy_account_class_hash, local_address_derivation: false, _phantom: PhantomData }
+    }
+
+    /// Returns the address of the proxy account Kakarot deploys for the EVM address, salted with
+    /// the EVM address.
+    pub fn starknet_address(&self, eth_address: &FieldElement) -> FieldElement {
+        get_contract_address(*eth_address, self.proxy_account_class_hash, &[], self.address)
+    }
+
+    /// Derives the Starknet addresses locally from now on if the derivation matches the
+    /// `compute_starknet_address` entrypoint, and returns whether it does.
+    pub async fn verify_address_derivation(&mut self, starknet_provider: &P) -> Result<bool, EthApiError<P::Error>> {
+        let eth_address = FieldElement::ONE;
+        let on_chain_address =
+            self.compute_starknet_address(starknet_provider, &eth_address, &BlockId::Tag(BlockTag::Latest)).await?;
+        self.local_address_derivation = self.starknet_address(&eth_address) == on_chain_address;
+        Ok(self.local_address_derivation)
     }
 
     pub async fn compute_starknet_address(
@@ -27,6 +47,10 @@ impl<P: Provider + Send + Sync> KakarotContract<P> {
         eth_address: &FieldElement,
         block_id: &BlockId,
     ) -> Result<FieldElement, EthApiError<P::Error>> {
+        if self.local_address_derivation {
+            return Ok(self.starknet_address(eth_address));
+        }
+
         // Prepare the calldata for the compute_starknet_address function call
         let calldata = vec![*eth_address];
         let request =
@@ -45,6 +69,27 @@ impl<P: Provider + Send + Sync> KakarotContract<P> {
         }
     }
 
+    /// Returns the number of the Starknet block in which the Kakarot contract was deployed, i.e.
+    /// the first block at which it has a class.
+    pub async fn deployment_block(&self, starknet_provider: &P) -> Result<u64, EthApiError<P::Error>> {
+        let is_deployed = |number: u64| async move {
+            match starknet_provider.get_class_hash_at(BlockId::Number(number), self.address).await {
+                Ok(_) => Ok(true),
+                Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Ok(false),
+                Err(err) => Err(EthApiError::from(err)),
+            }
+        };
+
+        let latest = starknet_provider.block_number().await?;
+        if !is_deployed(latest).await? {
+            return Err(ProviderError::StarknetError(StarknetError::ContractNotFound).into());
+        }
+        if is_deployed(0).await? {
+            return Ok(0);
+        }
+        binary_search(0, latest, is_deployed).await
+    }
+
     pub async fn eth_call(
         &self,
         starknet_provider: &P,
@@ -70,4 +115,32 @@ impl<P: Provider + Send + Sync> KakarotContract<P> {
         let result = vec_felt_to_bytes(return_data);
         Ok(result)
     }
+
+    /// Executes the EVM transaction with the given gas limit through the `eth_call` entrypoint,
+    /// without creating a transaction, and returns the outcome of the execution. A contract is
+    /// deployed when `to` is `None`.
+    pub async fn simulate(
+        &self,
+        starknet_provider: &P,
+        to: Option<FieldElement>,
+        gas_limit: u64,
+        value: FieldElement,
+        mut eth_calldata: Vec<FieldElement>,
+        block_id: &BlockId,
+    ) -> Result<EvmExecutionOutcome, EthApiError<P::Error>> {
+        let mut calldata = vec![
+            to.unwrap_or(FieldElement::ZERO),
+            gas_limit.into(),
+            FieldElement::ZERO,
+            value,
+            eth_calldata.len().into(),
+        ];
+
+        calldata.append(&mut eth_calldata);
+
+        let request = FunctionCall { contract_address: self.address, entry_point_selector: ETH_CALL, calldata };
+        let result = starknet_provider.call(request, block_id).await?;
+
+        Ok(EvmExecutionOutcome::from_call_result(&result)?)
+    }
 }
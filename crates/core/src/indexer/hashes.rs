@@ -0,0 +1,88 @@
+use std::path::Path;
+
+use reth_primitives::H256;
+use sled::{Batch, Db};
+
+use super::store::IndexerError;
+use crate::client::errors::ConfigError;
+
+/// Key prefix of the Ethereum hash => Starknet hash records.
+const ETHEREUM_HASH_PREFIX: u8 = b'e';
+/// Key prefix of the Starknet hash => Ethereum hash records.
+const STARKNET_HASH_PREFIX: u8 = b's';
+
+/// Embedded on-disk mapping between the Ethereum hash of a Kakarot transaction, the keccak256 of
+/// its signed RLP encoding, and the hash of the Starknet transaction which wraps it.
+#[derive(Debug, Clone)]
+pub struct TransactionHashStore {
+    db: Db,
+}
+
+impl TransactionHashStore {
+    /// Opens the store at the given path, creating it if needed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, IndexerError> {
+        Ok(Self { db: sled::open(path)? })
+    }
+
+    /// Opens a store which is deleted when dropped.
+    pub fn temporary() -> Result<Self, IndexerError> {
+        Ok(Self { db: sled::Config::new().temporary(true).open()? })
+    }
+
+    /// Opens the store at `KAKAROT_TX_HASHES_PATH`. The variable is mandatory: without a persistent
+    /// mapping, the transactions couldn't be looked up by Ethereum hash after a restart.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let path = std::env::var("KAKAROT_TX_HASHES_PATH")
+            .map_err(|_| ConfigError::EnvironmentVariableMissing("KAKAROT_TX_HASHES_PATH".into()))?;
+        Self::open(&path).map_err(|err| {
+            ConfigError::EnvironmentVariableSetWrong(format!("KAKAROT_TX_HASHES_PATH {path} can't be opened: {err}"))
+        })
+    }
+
+    /// Records the mapping between the two hashes of a transaction.
+    pub fn insert(&self, ethereum_hash: H256, starknet_hash: H256) -> Result<(), IndexerError> {
+        let mut batch = Batch::default();
+        batch.insert(key(ETHEREUM_HASH_PREFIX, ethereum_hash), starknet_hash.as_bytes());
+        batch.insert(key(STARKNET_HASH_PREFIX, starknet_hash), ethereum_hash.as_bytes());
+        self.db.apply_batch(batch)?;
+        Ok(())
+    }
+
+    /// Returns the hash of the Starknet transaction wrapping the Ethereum transaction.
+    pub fn starknet_hash(&self, ethereum_hash: H256) -> Result<Option<H256>, IndexerError> {
+        Ok(self.db.get(key(ETHEREUM_HASH_PREFIX, ethereum_hash))?.map(|value| H256::from_slice(&value)))
+    }
+
+    /// Returns the hash of the Ethereum transaction wrapped in the Starknet transaction.
+    pub fn ethereum_hash(&self, starknet_hash: H256) -> Result<Option<H256>, IndexerError> {
+        Ok(self.db.get(key(STARKNET_HASH_PREFIX, starknet_hash))?.map(|value| H256::from_slice(&value)))
+    }
+}
+
+fn key(prefix: u8, hash: H256) -> [u8; 33] {
+    let mut key = [0u8; 33];
+    key[0] = prefix;
+    key[1..].copy_from_slice(hash.as_bytes());
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_is_bidirectional() {
+        // Given
+        let store = TransactionHashStore::temporary().unwrap();
+        let ethereum_hash = H256::from_low_u64_be(1);
+        let starknet_hash = H256::from_low_u64_be(2);
+
+        // When
+        store.insert(ethereum_hash, starknet_hash).unwrap();
+
+        // Then
+        assert_eq!(Some(starknet_hash), store.starknet_hash(ethereum_hash).unwrap());
+        assert_eq!(Some(ethereum_hash), store.ethereum_hash(starknet_hash).unwrap());
+        assert_eq!(None, store.starknet_hash(starknet_hash).unwrap());
+    }
+}
@@ -0,0 +1,190 @@
+pub mod hashes;
+pub mod store;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use futures::future::join_all;
+use reth_primitives::{BlockNumberOrTag, H256};
+use reth_rpc_types::{BlockTransactions, Filter, FilterBlockOption, RichBlock};
+use starknet::core::types::{BlockId as StarknetBlockId, MaybePendingBlockWithTxHashes, Transaction};
+use starknet::providers::Provider;
+
+use self::store::{IndexStore, IndexedBlock};
+use crate::client::api::KakarotEthApi;
+use crate::client::constants::INDEXER_POLL_INTERVAL;
+use crate::client::errors::{ConfigError, EthApiError};
+use crate::models::block::BlockWithTxs;
+use crate::models::felt::Felt252Wrapper;
+use crate::models::transaction::StarknetTransaction;
+
+/// Configuration of the indexer.
+#[derive(Debug, Clone)]
+pub struct IndexerConfig {
+    /// Path of the on-disk store.
+    pub path: PathBuf,
+    /// First block to index when the store is empty, the Kakarot genesis block if unset.
+    pub start_block: Option<u64>,
+}
+
+impl IndexerConfig {
+    pub fn new(path: PathBuf, start_block: Option<u64>) -> Self {
+        Self { path, start_block }
+    }
+
+    /// Create a new `IndexerConfig` from environment variables. The indexer is disabled, and
+    /// `None` is returned, when `KAKAROT_INDEXER_PATH` is not set.
+    pub fn from_env() -> Result<Option<Self>, ConfigError> {
+        let Ok(path) = std::env::var("KAKAROT_INDEXER_PATH") else { return Ok(None) };
+        let start_block = match std::env::var("KAKAROT_INDEXER_START_BLOCK") {
+            Ok(start_block) => Some(start_block.parse().map_err(|_| {
+                ConfigError::EnvironmentVariableSetWrong(format!(
+                    "KAKAROT_INDEXER_START_BLOCK should be a block number, got {start_block}"
+                ))
+            })?),
+            Err(_) => None,
+        };
+        Ok(Some(Self::new(path.into(), start_block)))
+    }
+}
+
+/// Follows the Starknet chain and persists the converted Kakarot blocks in an [`IndexStore`].
+///
+/// On startup, the indexer resumes from the head of the store, or backfills from the configured
+/// start block. When an indexed block is no longer part of the canonical chain, blocks are
+/// removed from the store until its head is canonical again.
+pub struct Indexer<P: Provider + Send + Sync> {
+    kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    store: Arc<IndexStore>,
+    start_block: u64,
+}
+
+impl<P: Provider + Send + Sync + 'static> Indexer<P> {
+    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>, store: Arc<IndexStore>, start_block: u64) -> Self {
+        Self { kakarot_client, store, start_block }
+    }
+
+    /// Spawns the indexer on the Tokio runtime.
+    pub fn spawn(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(INDEXER_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(err) = self.sync().await {
+                    log::error!("Failed to index blocks: {err}");
+                }
+            }
+        })
+    }
+
+    /// Indexes the blocks between the head of the store and the head of the chain.
+    pub async fn sync(&self) -> Result<(), EthApiError<P::Error>> {
+        let latest = self.kakarot_client.block_number().await?.as_u64();
+
+        loop {
+            let next = self.store.head()?.map_or(self.start_block, |head| head + 1);
+            if next > latest {
+                break;
+            }
+
+            let block =
+                self.kakarot_client.get_eth_block_from_starknet_block(StarknetBlockId::Number(next), true).await?;
+            let parent_hash = match next.checked_sub(1) {
+                Some(parent) => self.store.block_hash(parent)?,
+                None => None,
+            };
+            if parent_hash.map_or(false, |parent_hash| parent_hash != block.inner.header.parent_hash) {
+                if !self.rollback().await? {
+                    // The chain changed between the two requests: retry on the next poll.
+                    break;
+                }
+                continue;
+            }
+
+            let indexed = self.indexed_block(next, block).await?;
+            self.store.insert_block(&indexed)?;
+        }
+
+        self.store.flush().await?;
+        Ok(())
+    }
+
+    /// Removes the blocks of the store which are no longer canonical. Returns false if the head of
+    /// the store was already canonical.
+    async fn rollback(&self) -> Result<bool, EthApiError<P::Error>> {
+        let mut removed = false;
+        while let Some(head) = self.store.head()? {
+            if self.store.block_hash(head)? == Some(self.canonical_block_hash(head).await?) {
+                break;
+            }
+            log::warn!("Removing block {head} from the index after a reorg");
+            self.store.remove_head()?;
+            removed = true;
+        }
+        Ok(removed)
+    }
+
+    async fn canonical_block_hash(&self, number: u64) -> Result<H256, EthApiError<P::Error>> {
+        let block =
+            self.kakarot_client.starknet_provider().get_block_with_tx_hashes(StarknetBlockId::Number(number)).await?;
+        let block_hash = match block {
+            MaybePendingBlockWithTxHashes::Block(block) => block.block_hash,
+            MaybePendingBlockWithTxHashes::PendingBlock(_) => {
+                return Err(EthApiError::ConversionError(format!("block {number} is pending")));
+            }
+        };
+        let block_hash: Felt252Wrapper = block_hash.into();
+        Ok(block_hash.into())
+    }
+
+    /// Records the mapping between the Ethereum and the Starknet hashes of the Kakarot transactions
+    /// of the block, so that they can be looked up by Ethereum hash.
+    async fn save_transaction_hashes(&self, number: u64) -> Result<(), EthApiError<P::Error>> {
+        let block = self.kakarot_client.starknet_provider().get_block_with_txs(StarknetBlockId::Number(number)).await?;
+        let transactions: Vec<Transaction> = self
+            .kakarot_client
+            .filter_kakarot_transactions(BlockWithTxs::new(block).transactions().into())
+            .await?
+            .into();
+
+        for transaction in transactions {
+            let transaction = StarknetTransaction::from(transaction);
+            let starknet_hash: H256 = transaction.transaction_hash()?.into();
+            // Transactions which can't be decoded keep their Starknet hash.
+            let Ok(ethereum_transactions) = transaction.ethereum_transactions() else { continue };
+            for ethereum_transaction in ethereum_transactions {
+                self.kakarot_client.save_transaction_hash(ethereum_transaction.hash(), starknet_hash)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches the receipts and the logs of the block.
+    async fn indexed_block(&self, number: u64, block: RichBlock) -> Result<IndexedBlock, EthApiError<P::Error>> {
+        self.save_transaction_hashes(number).await?;
+        let transaction_hashes = match &block.inner.transactions {
+            BlockTransactions::Full(transactions) => transactions.iter().map(|tx| tx.hash).collect(),
+            BlockTransactions::Hashes(hashes) => hashes.clone(),
+            BlockTransactions::Uncle => vec![],
+        };
+        let receipts =
+            join_all(transaction_hashes.into_iter().map(|hash| self.kakarot_client.transaction_receipt(hash)))
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .collect();
+
+        let filter = Filter {
+            block_option: FilterBlockOption::Range {
+                from_block: Some(BlockNumberOrTag::Number(number)),
+                to_block: Some(BlockNumberOrTag::Number(number)),
+            },
+            ..Default::default()
+        };
+        let logs = self.kakarot_client.get_logs(filter).await?;
+
+        Ok(IndexedBlock { block, receipts, logs })
+    }
+}
@@ -0,0 +1,334 @@
+use std::path::Path;
+
+use reth_primitives::{Address, H256};
+use reth_rpc_types::{BlockTransactions, Log, RichBlock, Transaction as EtherTransaction};
+use serde::de::DeserializeOwned;
+use sled::{Batch, Db};
+use thiserror::Error;
+
+use crate::models::receipt::KakarotTransactionReceipt;
+
+/// Key prefixes of the different kinds of records, all stored in the same tree so that a block
+/// can be written or removed in a single atomic batch.
+mod prefix {
+    /// Block number => hydrated block.
+    pub const BLOCK: u8 = b'b';
+    /// Block hash => block number.
+    pub const BLOCK_NUMBER: u8 = b'h';
+    /// Transaction hash => transaction.
+    pub const TRANSACTION: u8 = b't';
+    /// Transaction hash => receipt.
+    pub const RECEIPT: u8 = b'r';
+    /// Block number => logs of the block.
+    pub const LOGS: u8 = b'l';
+    /// Address, block number and transaction index => transaction hash.
+    pub const ADDRESS_TRANSACTION: u8 = b'a';
+}
+
+/// Key of the number of the highest indexed block.
+const HEAD_KEY: &[u8] = b"mhead";
+
+/// Error that can occur when reading or writing the index.
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    /// The embedded store failed.
+    #[error("index storage error: {0}")]
+    StorageError(#[from] sled::Error),
+    /// A record could not be (de)serialized.
+    #[error("index serialization error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+    /// The block is missing its number or hash.
+    #[error("cannot index block: missing {0}")]
+    IncompleteBlock(&'static str),
+}
+
+/// A Kakarot block, converted to Ethereum types, along with the receipts of its transactions and
+/// the logs they emitted.
+#[derive(Debug, Clone)]
+pub struct IndexedBlock {
+    /// The block, with hydrated transactions.
+    pub block: RichBlock,
+    pub receipts: Vec<KakarotTransactionReceipt>,
+    pub logs: Vec<Log>,
+}
+
+/// Embedded on-disk store of the indexed Kakarot blocks.
+///
+/// Blocks are indexed contiguously: the store keeps the number of its highest block (the head),
+/// and blocks can only be appended on top of it or removed from it.
+#[derive(Debug, Clone)]
+pub struct IndexStore {
+    db: Db,
+}
+
+impl IndexStore {
+    /// Opens the store at the given path, creating it if needed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, IndexerError> {
+        Ok(Self { db: sled::open(path)? })
+    }
+
+    /// Opens a store which is deleted when dropped.
+    pub fn temporary() -> Result<Self, IndexerError> {
+        Ok(Self { db: sled::Config::new().temporary(true).open()? })
+    }
+
+    /// Returns the number of the highest indexed block.
+    pub fn head(&self) -> Result<Option<u64>, IndexerError> {
+        Ok(self.db.get(HEAD_KEY)?.map(|value| decode_number(&value)))
+    }
+
+    /// Returns true if the block with the given number is indexed.
+    pub fn contains_block(&self, number: u64) -> Result<bool, IndexerError> {
+        Ok(self.db.contains_key(key(prefix::BLOCK, &number.to_be_bytes()))?)
+    }
+
+    /// Returns the indexed block with the given number, with hydrated transactions or their
+    /// hashes.
+    pub fn block_by_number(&self, number: u64, hydrated_tx: bool) -> Result<Option<RichBlock>, IndexerError> {
+        let Some(mut block) = self.get::<RichBlock>(&key(prefix::BLOCK, &number.to_be_bytes()))? else {
+            return Ok(None);
+        };
+        if !hydrated_tx {
+            if let BlockTransactions::Full(transactions) = &block.inner.transactions {
+                block.inner.transactions = BlockTransactions::Hashes(transactions.iter().map(|tx| tx.hash).collect());
+            }
+        }
+        Ok(Some(block))
+    }
+
+    /// Returns the number of the indexed block with the given hash.
+    pub fn block_number(&self, hash: H256) -> Result<Option<u64>, IndexerError> {
+        Ok(self.db.get(key(prefix::BLOCK_NUMBER, hash.as_bytes()))?.map(|value| decode_number(&value)))
+    }
+
+    /// Returns the hash of the indexed block with the given number.
+    pub fn block_hash(&self, number: u64) -> Result<Option<H256>, IndexerError> {
+        Ok(self.block_by_number(number, false)?.and_then(|block| block.inner.header.hash))
+    }
+
+    pub fn transaction(&self, hash: H256) -> Result<Option<EtherTransaction>, IndexerError> {
+        self.get(&key(prefix::TRANSACTION, hash.as_bytes()))
+    }
+
+    pub fn receipt(&self, hash: H256) -> Result<Option<KakarotTransactionReceipt>, IndexerError> {
+        self.get(&key(prefix::RECEIPT, hash.as_bytes()))
+    }
+
+    /// Returns the logs of the blocks between `from` and `to` (inclusive), or `None` if one of the
+    /// blocks isn't indexed.
+    pub fn logs(&self, from: u64, to: u64) -> Result<Option<Vec<Log>>, IndexerError> {
+        let mut logs = vec![];
+        for number in from..=to {
+            match self.get::<Vec<Log>>(&key(prefix::LOGS, &number.to_be_bytes()))? {
+                Some(block_logs) => logs.extend(block_logs),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(logs))
+    }
+
+    /// Returns the hashes of the indexed transactions sent from or to the address, from the oldest
+    /// to the most recent.
+    pub fn transactions_by_address(&self, address: Address) -> Result<Vec<H256>, IndexerError> {
+        self.db
+            .scan_prefix(key(prefix::ADDRESS_TRANSACTION, address.as_bytes()))
+            .values()
+            .map(|value| Ok(H256::from_slice(&value?)))
+            .collect()
+    }
+
+    /// Appends a block on top of the head of the store.
+    pub fn insert_block(&self, indexed: &IndexedBlock) -> Result<(), IndexerError> {
+        let (number, hash) = block_number_and_hash(&indexed.block)?;
+
+        let mut batch = Batch::default();
+        batch.insert(key(prefix::BLOCK, &number.to_be_bytes()), serde_json::to_vec(&indexed.block)?);
+        batch.insert(key(prefix::BLOCK_NUMBER, hash.as_bytes()), &number.to_be_bytes());
+        batch.insert(key(prefix::LOGS, &number.to_be_bytes()), serde_json::to_vec(&indexed.logs)?);
+
+        if let BlockTransactions::Full(transactions) = &indexed.block.inner.transactions {
+            for (index, transaction) in transactions.iter().enumerate() {
+                batch.insert(key(prefix::TRANSACTION, transaction.hash.as_bytes()), serde_json::to_vec(transaction)?);
+                for address in transaction_addresses(transaction) {
+                    batch.insert(address_transaction_key(address, number, index), transaction.hash.as_bytes());
+                }
+            }
+        }
+        for receipt in &indexed.receipts {
+            if let Some(transaction_hash) = receipt.transaction_hash {
+                batch.insert(key(prefix::RECEIPT, transaction_hash.as_bytes()), serde_json::to_vec(receipt)?);
+            }
+        }
+        batch.insert(HEAD_KEY, &number.to_be_bytes());
+
+        self.db.apply_batch(batch)?;
+        Ok(())
+    }
+
+    /// Removes the head block of the store, returning its number.
+    pub fn remove_head(&self) -> Result<Option<u64>, IndexerError> {
+        let Some(number) = self.head()? else { return Ok(None) };
+        let Some(block) = self.block_by_number(number, true)? else { return Ok(None) };
+        let (_, hash) = block_number_and_hash(&block)?;
+
+        let mut batch = Batch::default();
+        batch.remove(key(prefix::BLOCK, &number.to_be_bytes()));
+        batch.remove(key(prefix::BLOCK_NUMBER, hash.as_bytes()));
+        batch.remove(key(prefix::LOGS, &number.to_be_bytes()));
+
+        if let BlockTransactions::Full(transactions) = &block.inner.transactions {
+            for (index, transaction) in transactions.iter().enumerate() {
+                batch.remove(key(prefix::TRANSACTION, transaction.hash.as_bytes()));
+                batch.remove(key(prefix::RECEIPT, transaction.hash.as_bytes()));
+                for address in transaction_addresses(transaction) {
+                    batch.remove(address_transaction_key(address, number, index));
+                }
+            }
+        }
+
+        match number.checked_sub(1) {
+            Some(parent) if self.contains_block(parent)? => batch.insert(HEAD_KEY, &parent.to_be_bytes()),
+            _ => batch.remove(HEAD_KEY),
+        }
+
+        self.db.apply_batch(batch)?;
+        Ok(Some(number))
+    }
+
+    /// Flushes the pending writes to disk.
+    pub async fn flush(&self) -> Result<(), IndexerError> {
+        self.db.flush_async().await?;
+        Ok(())
+    }
+
+    fn get<T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>, IndexerError> {
+        self.db.get(key)?.map(|value| serde_json::from_slice(&value)).transpose().map_err(Into::into)
+    }
+}
+
+fn key(prefix: u8, suffix: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(1 + suffix.len());
+    key.push(prefix);
+    key.extend_from_slice(suffix);
+    key
+}
+
+fn address_transaction_key(address: Address, block_number: u64, index: usize) -> Vec<u8> {
+    let mut key = key(prefix::ADDRESS_TRANSACTION, address.as_bytes());
+    key.extend_from_slice(&block_number.to_be_bytes());
+    key.extend_from_slice(&(index as u64).to_be_bytes());
+    key
+}
+
+fn decode_number(value: &[u8]) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&value[..8]);
+    u64::from_be_bytes(bytes)
+}
+
+fn block_number_and_hash(block: &RichBlock) -> Result<(u64, H256), IndexerError> {
+    let number = block.inner.header.number.ok_or(IndexerError::IncompleteBlock("number"))?;
+    let hash = block.inner.header.hash.ok_or(IndexerError::IncompleteBlock("hash"))?;
+    Ok((number.to::<u64>(), hash))
+}
+
+/// Returns the sender and the recipient of the transaction.
+fn transaction_addresses(transaction: &EtherTransaction) -> impl Iterator<Item = Address> {
+    let recipient = transaction.to.filter(|to| *to != transaction.from);
+    std::iter::once(transaction.from).chain(recipient)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use reth_primitives::U256;
+    use reth_rpc_types::{Block, Rich};
+
+    use super::*;
+
+    fn indexed_block() -> IndexedBlock {
+        let block: Block =
+            serde_json::from_str(include_str!("../models/test_data/conversion/eth/block_with_txs.json")).unwrap();
+        let log: Log =
+            serde_json::from_str(include_str!("../models/test_data/conversion/eth/event_log3.json")).unwrap();
+        IndexedBlock { block: Rich { inner: block, extra_info: Default::default() }, receipts: vec![], logs: vec![log] }
+    }
+
+    #[test]
+    fn test_insert_block() {
+        // Given
+        let store = IndexStore::temporary().unwrap();
+        let indexed = indexed_block();
+        let (number, hash) = block_number_and_hash(&indexed.block).unwrap();
+
+        // When
+        store.insert_block(&indexed).unwrap();
+
+        // Then
+        assert_eq!(Some(number), store.head().unwrap());
+        assert_eq!(Some(number), store.block_number(hash).unwrap());
+        assert_eq!(
+            Some(indexed.block.inner.clone()),
+            store.block_by_number(number, true).unwrap().map(|block| block.inner)
+        );
+        assert_eq!(Some(indexed.logs), store.logs(number, number).unwrap());
+        assert_eq!(None, store.logs(number, number + 1).unwrap());
+
+        let BlockTransactions::Full(transactions) = indexed.block.inner.transactions else { panic!("hydrated block") };
+        assert_eq!(Some(transactions[0].clone()), store.transaction(transactions[0].hash).unwrap());
+        let sender_transactions = store.transactions_by_address(transactions[0].from).unwrap();
+        assert_eq!(transactions.iter().map(|tx| tx.hash).collect::<Vec<_>>(), sender_transactions);
+    }
+
+    #[test]
+    fn test_block_by_number_not_hydrated() {
+        // Given
+        let store = IndexStore::temporary().unwrap();
+        let indexed = indexed_block();
+        let (number, _) = block_number_and_hash(&indexed.block).unwrap();
+        store.insert_block(&indexed).unwrap();
+
+        // When
+        let block = store.block_by_number(number, false).unwrap().unwrap();
+
+        // Then
+        let hash = H256::from_str("0x0e2d5995c3b8db32a7052f70e7f1ad428b4958be3c89000d491bcca96e00eb9c").unwrap();
+        match block.inner.transactions {
+            BlockTransactions::Hashes(hashes) => assert_eq!(hash, hashes[0]),
+            _ => panic!("expected transaction hashes"),
+        }
+    }
+
+    #[test]
+    fn test_remove_head() {
+        // Given
+        let store = IndexStore::temporary().unwrap();
+        let parent = indexed_block();
+        let mut child = indexed_block();
+        child.block.inner.header.number = parent.block.inner.header.number.map(|number| number + U256::from(1));
+        child.block.inner.header.hash = Some(H256::from_low_u64_be(1));
+        child.block.inner.transactions = BlockTransactions::Full(vec![]);
+        store.insert_block(&parent).unwrap();
+        store.insert_block(&child).unwrap();
+        let (child_number, child_hash) = block_number_and_hash(&child.block).unwrap();
+
+        // When
+        let removed = store.remove_head().unwrap();
+
+        // Then
+        assert_eq!(Some(child_number), removed);
+        assert_eq!(Some(child_number - 1), store.head().unwrap());
+        assert_eq!(None, store.block_number(child_hash).unwrap());
+        assert!(store.block_by_number(child_number, true).unwrap().is_none());
+
+        // When
+        store.remove_head().unwrap();
+
+        // Then
+        assert_eq!(None, store.head().unwrap());
+        let BlockTransactions::Full(transactions) = parent.block.inner.transactions else { panic!("hydrated block") };
+        assert_eq!(None, store.transaction(transactions[0].hash).unwrap());
+        assert!(store.transactions_by_address(transactions[0].from).unwrap().is_empty());
+    }
+}
@@ -1,5 +1,7 @@
 #![feature(more_qualified_paths)]
 pub mod client;
 pub mod contracts;
+pub mod indexer;
 pub mod mock;
 pub mod models;
+pub mod txpool;
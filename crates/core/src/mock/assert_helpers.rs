@@ -1,12 +1,15 @@
 use std::str::FromStr;
 
-use reth_primitives::{Bloom, Bytes, H160, H256, U128, U256};
+use reth_primitives::constants::EMPTY_OMMER_ROOT;
+use reth_primitives::proofs::EMPTY_ROOT;
+use reth_primitives::{Bytes, H160, H256, U128, U256, U64};
 use reth_rpc_types::{Block, BlockTransactions, Rich, Signature, Transaction};
 use serde::{Deserialize, Serialize};
 use starknet::core::types::{FieldElement, InvokeTransaction, Transaction as StarknetTransaction};
 
 use crate::client::constants::gas::BASE_FEE_PER_GAS;
-use crate::client::constants::{CHAIN_ID, DIFFICULTY, GAS_LIMIT, GAS_USED, MIX_HASH, NONCE, SIZE, TOTAL_DIFFICULTY};
+use crate::client::constants::{CHAIN_ID, DEFAULT_BLOCK_GAS_LIMIT, DIFFICULTY, MIX_HASH, NONCE, TOTAL_DIFFICULTY};
+use crate::mock::constants::ABDEL_TRANSACTION_HASH;
 use crate::models::felt::Felt252Wrapper;
 use crate::models::signature::StarknetSignature;
 
@@ -36,7 +39,7 @@ pub fn assert_block(block: &Rich<Block>, starknet_res: String, starknet_txs: Str
 
     assert_eq!(block.total_difficulty, *TOTAL_DIFFICULTY);
     assert_eq!(block.uncles, vec![]);
-    assert_eq!(block.size, *SIZE);
+    assert!(block.size.is_some());
 
     let starknet_block_hash = FieldElement::from_str(starknet_data.block_hash.as_str()).unwrap();
 
@@ -75,7 +78,7 @@ pub fn assert_block_header(block: &Rich<Block>, starknet_res: String, hydrated:
     let starknet_parent_hash = FieldElement::from_str(starknet_data.parent_hash.as_str()).unwrap();
     let parent_hash = H256::from_slice(&starknet_parent_hash.to_bytes_be());
     assert_eq!(block.header.parent_hash, parent_hash);
-    assert_eq!(block.header.uncles_hash, parent_hash);
+    assert_eq!(block.header.uncles_hash, EMPTY_OMMER_ROOT);
 
     let starknet_sequencer = FieldElement::from_str(starknet_data.sequencer_address.as_str()).unwrap();
     let sequencer = H160::from_slice(&starknet_sequencer.to_bytes_be()[12..32]);
@@ -87,25 +90,18 @@ pub fn assert_block_header(block: &Rich<Block>, starknet_res: String, hydrated:
 
     assert_eq!(block.header.timestamp, U256::from(starknet_data.timestamp));
 
+    // The 16 transactions of the hydrated block share the same receipt, whose actual fee divided by
+    // the Starknet gas price is 1_215_300 gas.
     if hydrated {
-        assert_eq!(
-            block.header.transactions_root,
-            H256::from_str("0x0000000000000000000000000000000000000000000000000000000000000000").unwrap()
-        );
-        assert_eq!(
-            block.header.receipts_root,
-            H256::from_str("0x0000000000000000000000000000000000000000000000000000000000000000").unwrap()
-        );
-    } else {
-        assert_eq!(block.header.transactions_root, H256::zero());
-        assert_eq!(block.header.receipts_root, H256::zero());
-    };
+        assert_ne!(block.header.transactions_root, EMPTY_ROOT);
+        assert_ne!(block.header.receipts_root, EMPTY_ROOT);
+        assert_eq!(block.header.gas_used, U256::from(16 * 1_215_300));
+    }
+    assert_eq!(block.header.withdrawals_root, Some(EMPTY_ROOT));
 
     assert_eq!(block.header.extra_data, Bytes::default());
-    assert_eq!(block.header.logs_bloom, Bloom::default());
 
-    assert_eq!(block.header.gas_used, *GAS_USED);
-    assert_eq!(block.header.gas_limit, *GAS_LIMIT);
+    assert_eq!(block.header.gas_limit, U256::from(DEFAULT_BLOCK_GAS_LIMIT));
     assert_eq!(block.header.difficulty, *DIFFICULTY);
     assert_eq!(block.header.base_fee_per_gas, Some(U256::from(BASE_FEE_PER_GAS)));
     assert_eq!(block.header.mix_hash, *MIX_HASH);
@@ -113,27 +109,31 @@ pub fn assert_block_header(block: &Rich<Block>, starknet_res: String, hydrated:
 }
 
 pub fn assert_transaction(ether_tx: Transaction, starknet_tx: StarknetTransaction) {
+    // The first transaction in the starknet_getBlockWithTxs.json file wraps an EIP-1559 contract
+    // deployment with a gas limit, a max fee per gas and a max priority fee per gas of 0xdead.
     assert_eq!(ether_tx.chain_id, Some(CHAIN_ID.into()));
-    assert_eq!(ether_tx.access_list, None);
-    assert_eq!(ether_tx.transaction_type, None);
+    assert_eq!(ether_tx.access_list, Some(vec![]));
+    assert_eq!(ether_tx.transaction_type, Some(U64::from(2)));
 
     assert_eq!(ether_tx.to, None);
-    assert_eq!(ether_tx.value, U256::from(100));
-    assert_eq!(ether_tx.gas, U256::from(100));
-    assert_eq!(ether_tx.gas_price, None);
+    assert_eq!(ether_tx.value, U256::ZERO);
+    assert_eq!(ether_tx.gas, U256::from(0xdead));
+    assert_eq!(ether_tx.gas_price, Some(U128::from(0xdead)));
     let index = match ether_tx.transaction_index {
         Some(_) => Some(U256::from(0)),
         _ => None,
     };
     assert_eq!(ether_tx.transaction_index, index);
-    assert_eq!(ether_tx.max_fee_per_gas, None);
-    assert_eq!(ether_tx.max_priority_fee_per_gas, Some(U128::ZERO));
+    assert_eq!(ether_tx.max_fee_per_gas, Some(U128::from(0xdead)));
+    assert_eq!(ether_tx.max_priority_fee_per_gas, Some(U128::from(0xdead)));
 
     match starknet_tx {
         StarknetTransaction::Invoke(invoke_tx) => {
             match invoke_tx {
                 InvokeTransaction::V0(v0) => {
-                    assert_eq!(ether_tx.hash, H256::from_slice(&v0.transaction_hash.to_bytes_be()));
+                    // The hash is the keccak256 of the signed transaction in the calldata of the first
+                    // transaction in the starknet_getBlockWithTxs.json file.
+                    assert_eq!(ether_tx.hash, *ABDEL_TRANSACTION_HASH);
                     let tx_nonce: Felt252Wrapper = v0.nonce.into();
                     assert_eq!(ether_tx.nonce, tx_nonce.into());
                     // TODO: `ether_tx.from` is an Ethereum address, `contract_address` represents a Starknet address
@@ -142,34 +142,34 @@ pub fn assert_transaction(ether_tx: Transaction, starknet_tx: StarknetTransactio
                     assert_eq!(ether_tx.from, Felt252Wrapper::from(v0.contract_address).try_into().unwrap());
                     // r and s values are extracted from the calldata of the first transaction
                     // in the starknet_getBlockWithTxs.json file.
-                    // v value is calculated from the parity of the y coordinate of the signature,
-                    // to which we add 35 + 2 * CHAIN_ID (based on https://eips.ethereum.org/EIPS/eip-155).
+                    // v value is the parity of the y coordinate of the signature, as the transaction
+                    // is an EIP-1559 transaction.
                     let signature = Signature {
                         r: U256::from_str("0x05e6a35e537e8d99c81bf2d4e7e8a410e7f6f3f8b1f07edc28bf226d3ac2cae12")
                             .unwrap(),
                         s: U256::from_str("0x01910d7b4784e7347a6c7dccf8b8051c06f091347eb4a4a2f6092f1541cb62de7")
                             .unwrap(),
-                        v: U256::from_str("0x000000000000000000000000000000000000000000000000000000009696a4cc")
-                            .unwrap(),
+                        v: U256::from(1),
                     };
                     assert_eq!(ether_tx.signature, Some(signature));
                 }
                 InvokeTransaction::V1(v1) => {
-                    assert_eq!(ether_tx.hash, H256::from_slice(&v1.transaction_hash.to_bytes_be()));
+                    // The hash is the keccak256 of the signed transaction in the calldata of the first
+                    // transaction in the starknet_getBlockWithTxs.json file.
+                    assert_eq!(ether_tx.hash, *ABDEL_TRANSACTION_HASH);
                     let tx_nonce: Felt252Wrapper = v1.nonce.into();
                     assert_eq!(ether_tx.nonce, tx_nonce.into());
                     assert_eq!(ether_tx.from, H160::from_str("0x54b288676b749def5fc10eb17244fe2c87375de1").unwrap());
                     // r and s values are extracted from the calldata of the first transaction
                     // in the starknet_getBlockWithTxs.json file.
-                    // v value is calculated from the parity of the y coordinate of the signature,
-                    // to which we add 35 + 2 * CHAIN_ID (based on https://eips.ethereum.org/EIPS/eip-155).
+                    // v value is the parity of the y coordinate of the signature, as the transaction
+                    // is an EIP-1559 transaction.
                     let signature = Signature {
                         r: U256::from_str("0x05e6a35e537e8d99c81bf2d4e7e8a410e7f6f3f8b1f07edc28bf226d3ac2cae12")
                             .unwrap(),
                         s: U256::from_str("0x01910d7b4784e7347a6c7dccf8b8051c06f091347eb4a4a2f6092f1541cb62de7")
                             .unwrap(),
-                        v: U256::from_str("0x000000000000000000000000000000000000000000000000000000009696a4cc")
-                            .unwrap(),
+                        v: U256::from(1),
                     };
                     assert_eq!(ether_tx.signature, Some(signature));
                     // TODO: test ether_tx.input
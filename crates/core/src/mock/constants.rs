@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use lazy_static::lazy_static;
-use reth_primitives::Address;
+use reth_primitives::{Address, H256};
 use starknet_crypto::FieldElement;
 
 pub const PROXY_ACCOUNT_CLASS_HASH_HEX: &str = "0x0775033b738dfe34c48f43a839c3d882ebe521befb3447240f2d218f14816ef5";
@@ -23,6 +23,9 @@ lazy_static! {
     pub static ref ABDEL_STARKNET_ADDRESS: FieldElement = FieldElement::from_hex_be(ABDEL_STARKNET_ADDRESS_HEX).unwrap();
     /// Test value for Abdel ethereum address.
     pub static ref ABDEL_ETHEREUM_ADDRESS: Address = Address::from_str("0x54b288676b749def5fc10eb17244fe2c87375de1").unwrap();
+    /// Test value for the Ethereum hash of Abdel's transaction.
+    pub static ref ABDEL_TRANSACTION_HASH: H256 =
+        H256::from_str("0x0e2d5995c3b8db32a7052f70e7f1ad428b4958be3c89000d491bcca96e00eb9c").unwrap();
     /// Test value for proxy account class hash.
     pub static ref PROXY_ACCOUNT_CLASS_HASH: FieldElement =
         FieldElement::from_hex_be(PROXY_ACCOUNT_CLASS_HASH_HEX).unwrap();
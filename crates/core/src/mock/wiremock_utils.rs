@@ -4,11 +4,15 @@ use reqwest::StatusCode;
 use reth_primitives::{BlockId, H256};
 use serde::{Deserialize, Serialize};
 use starknet::core::types::{BlockId as StarknetBlockId, BlockTag};
-use wiremock::matchers::{body_json, method};
+use wiremock::matchers::{body_json, body_partial_json, method};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 use crate::models::block::EthBlockId;
 
+/// Priority of the mocks matching any request of a method, lower than the default priority of the
+/// mocks matching a request with its parameters.
+const FALLBACK_PRIORITY: u8 = 10;
+
 #[derive(Serialize, Debug)]
 pub struct StarknetRpcBaseData<'a, StarknetParams> {
     id: usize,
@@ -77,6 +81,9 @@ pub async fn setup_wiremock() -> String {
 
     mock_block_with_txs_hashes().mount(&mock_server).await;
 
+    // block_with_tx_hashes of the block of block_with_txs, to compare the views of a block
+    mock_block_with_tx_hashes_of_block_with_txs().mount(&mock_server).await;
+
     mock_block_with_txs_latest().mount(&mock_server).await;
 
     mock_block_with_txs_hashes_latest().mount(&mock_server).await;
@@ -99,6 +106,9 @@ pub async fn setup_wiremock() -> String {
     // * test_transaction_receipt_invoke_is_ok
     mock_transaction_receipt_invoke().mount(&mock_server).await;
 
+    // block_with_txs of the block of the receipt, to position the transaction in its block
+    mock_block_with_txs_receipt_block().mount(&mock_server).await;
+
     mock_transaction_by_hash().mount(&mock_server).await;
 
     mock_get_code().mount(&mock_server).await;
@@ -107,6 +117,14 @@ pub async fn setup_wiremock() -> String {
 
     mock_get_class_hash_at().mount(&mock_server).await;
 
+    // Receipts of the other transactions of a block, along with the fee estimate and the nonce of
+    // the gas price account, to compute the receipts of all the transactions of the block
+    mock_transaction_receipt_fallback().mount(&mock_server).await;
+
+    mock_estimate_fee().mount(&mock_server).await;
+
+    mock_get_nonce().mount(&mock_server).await;
+
     // Get kakarot contract bytecode
     // TODO: Use the latest mapping between starknet and EVM addresses
 
@@ -131,6 +149,17 @@ fn mock_block_with_txs() -> Mock {
     )
 }
 
+fn mock_block_with_txs_receipt_block() -> Mock {
+    let block_id = BlockId::Hash(
+        H256::from_str("0x000000000000000000000000000000000000000000000000000000000000000d").unwrap().into(),
+    );
+    let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into().unwrap();
+    Mock::given(method("POST")).and(body_json(StarknetRpcBaseData::block_with_txs([&starknet_block_id]))).respond_with(
+        response_template_with_status(StatusCode::OK)
+            .set_body_raw(include_str!("fixtures/responses/blocks/starknet_getBlockWithTxs.json"), "application/json"),
+    )
+}
+
 fn mock_block_with_txs_hashes() -> Mock {
     let block_id_tx_hashes = BlockId::Hash(
         H256::from_str("0x0197be2810df6b5eedd5d9e468b200d0b845b642b81a44755e19047f08cc8c6e").unwrap().into(),
@@ -144,6 +173,19 @@ fn mock_block_with_txs_hashes() -> Mock {
         ))
 }
 
+fn mock_block_with_tx_hashes_of_block_with_txs() -> Mock {
+    let block_id = BlockId::Hash(
+        H256::from_str("0x0449aa33ad836b65b10fa60082de99e24ac876ee2fd93e723a99190a530af0a9").unwrap().into(),
+    );
+    let starknet_block_id: StarknetBlockId = EthBlockId::new(block_id).try_into().unwrap();
+    Mock::given(method("POST"))
+        .and(body_json(StarknetRpcBaseData::block_with_tx_hashes([&starknet_block_id])))
+        .respond_with(response_template_with_status(StatusCode::OK).set_body_raw(
+            include_str!("fixtures/responses/blocks/starknet_getBlockWithTxHashes_19612.json"),
+            "application/json",
+        ))
+}
+
 fn mock_block_with_txs_latest() -> Mock {
     let latest_block = StarknetBlockId::Tag(BlockTag::Latest);
     Mock::given(method("POST")).and(body_json(StarknetRpcBaseData::block_with_txs([&latest_block]))).respond_with(
@@ -230,6 +272,34 @@ fn mock_transaction_receipt_invoke() -> Mock {
         ))
 }
 
+fn mock_transaction_receipt_fallback() -> Mock {
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({ "method": "starknet_getTransactionReceipt" })))
+        .respond_with(response_template_with_status(StatusCode::OK).set_body_raw(
+            include_str!("fixtures/responses/transactions/starknet_getTransactionReceipt.json"),
+            "application/json",
+        ))
+        .with_priority(FALLBACK_PRIORITY)
+}
+
+fn mock_estimate_fee() -> Mock {
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({ "method": "starknet_estimateFee" })))
+        .respond_with(
+            response_template_with_status(StatusCode::OK)
+                .set_body_raw(include_str!("fixtures/responses/starknet_estimateFee.json"), "application/json"),
+        )
+}
+
+fn mock_get_nonce() -> Mock {
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({ "method": "starknet_getNonce" })))
+        .respond_with(
+            response_template_with_status(StatusCode::OK)
+                .set_body_raw(include_str!("fixtures/responses/starknet_getNonce.json"), "application/json"),
+        )
+}
+
 fn mock_transaction_by_hash() -> Mock {
     Mock::given(method("POST"))
         .and(body_json(StarknetRpcBaseData::transaction_by_hash([
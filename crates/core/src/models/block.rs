@@ -1,6 +1,12 @@
 use async_trait::async_trait;
-use reth_primitives::{BlockId as EthereumBlockId, BlockNumberOrTag, Bloom, Bytes, H256, H64, U256};
-use reth_rpc_types::{Block, BlockTransactions, Header, RichBlock};
+use reth_primitives::constants::EMPTY_OMMER_ROOT;
+use reth_primitives::proofs::{calculate_receipt_root, calculate_transaction_root, EMPTY_ROOT};
+use reth_primitives::{
+    BlockId as EthereumBlockId, BlockNumberOrTag, Bloom, Bytes, Header as PrimitiveHeader, Log as PrimitiveLog,
+    Receipt, ReceiptWithBloom, TransactionSigned, H256, H64, U256, U64,
+};
+use reth_rlp::{length_of_length, Encodable};
+use reth_rpc_types::{Block, BlockTransactions, Header, Log, RichBlock};
 use starknet::core::types::{
     BlockId as StarknetBlockId, BlockTag, FieldElement, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
     Transaction,
@@ -9,11 +15,13 @@ use starknet::providers::Provider;
 
 use super::convertible::ConvertibleStarknetBlock;
 use super::felt::Felt252Wrapper;
+use super::receipt::KakarotTransactionReceipt;
+use super::transaction::StarknetTransaction;
 use super::ConversionError;
 use crate::client::api::KakarotEthApi;
-use crate::client::constants::{
-    DIFFICULTY, EARLIEST_BLOCK_NUMBER, GAS_LIMIT, GAS_USED, MIX_HASH, NONCE, SIZE, TOTAL_DIFFICULTY,
-};
+use crate::client::constants::{DIFFICULTY, EARLIEST_BLOCK_NUMBER, MIX_HASH, NONCE, TOTAL_DIFFICULTY};
+use crate::client::errors::EthApiError;
+use crate::client::helpers::logs_bloom;
 
 pub struct EthBlockId(EthereumBlockId);
 
@@ -123,14 +131,139 @@ impl BlockWithTxs {
     );
 }
 
+/// A Kakarot transaction of a block, along with its receipt.
+#[derive(Debug, Clone)]
+pub struct ExecutedTransaction {
+    /// Hash of the Starknet transaction wrapping the transaction.
+    pub starknet_hash: FieldElement,
+    pub transaction: TransactionSigned,
+    pub receipt: KakarotTransactionReceipt,
+}
+
+/// The fields of a block header which are derived from the Kakarot transactions of the block and
+/// their receipts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockExecution {
+    pub gas_used: U256,
+    pub logs_bloom: Bloom,
+    /// Root of the Merkle Patricia trie of the transactions, keyed by their index.
+    pub transactions_root: H256,
+    /// Root of the Merkle Patricia trie of the receipts, keyed by their index.
+    pub receipts_root: H256,
+    /// Length of the RLP encoding of the list of transactions of the block body.
+    pub transactions_length: usize,
+}
+
+impl BlockExecution {
+    pub fn new(transactions: &[ExecutedTransaction]) -> Self {
+        let mut gas_used = U256::ZERO;
+        let receipts: Vec<ReceiptWithBloom> = transactions
+            .iter()
+            .map(|tx| {
+                gas_used += tx.receipt.gas_used.unwrap_or_default();
+                let logs = tx
+                    .receipt
+                    .logs
+                    .iter()
+                    .map(|log| PrimitiveLog {
+                        address: log.address,
+                        topics: log.topics.clone(),
+                        data: log.data.clone(),
+                    })
+                    .collect();
+                let receipt = Receipt {
+                    tx_type: tx.transaction.tx_type(),
+                    success: tx.receipt.status_code == Some(U64::from(1)),
+                    cumulative_gas_used: u64::try_from(gas_used).unwrap_or(u64::MAX),
+                    logs,
+                };
+                ReceiptWithBloom { receipt, bloom: tx.receipt.logs_bloom }
+            })
+            .collect();
+
+        let signed_transactions: Vec<TransactionSigned> =
+            transactions.iter().map(|tx| tx.transaction.clone()).collect();
+        let logs: Vec<Log> = transactions.iter().flat_map(|tx| tx.receipt.logs.iter().cloned()).collect();
+
+        Self {
+            gas_used,
+            logs_bloom: logs_bloom(&logs),
+            transactions_root: calculate_transaction_root(&signed_transactions),
+            receipts_root: calculate_receipt_root(&receipts),
+            transactions_length: list_length(signed_transactions.iter().map(Encodable::length).sum()),
+        }
+    }
+
+    /// Returns the length of the RLP encoding of the block with the given header, which has no
+    /// ommers and an empty list of withdrawals.
+    pub fn block_size(&self, header: &Header) -> U256 {
+        let header = PrimitiveHeader {
+            parent_hash: header.parent_hash,
+            ommers_hash: header.uncles_hash,
+            beneficiary: header.miner,
+            state_root: header.state_root,
+            transactions_root: header.transactions_root,
+            receipts_root: header.receipts_root,
+            withdrawals_root: header.withdrawals_root,
+            logs_bloom: header.logs_bloom,
+            difficulty: header.difficulty,
+            number: header.number.and_then(|number| u64::try_from(number).ok()).unwrap_or_default(),
+            gas_limit: u64::try_from(header.gas_limit).unwrap_or(u64::MAX),
+            gas_used: u64::try_from(header.gas_used).unwrap_or(u64::MAX),
+            timestamp: u64::try_from(header.timestamp).unwrap_or_default(),
+            mix_hash: header.mix_hash,
+            nonce: header.nonce.map(|nonce| u64::from_be_bytes(nonce.0)).unwrap_or_default(),
+            base_fee_per_gas: header.base_fee_per_gas.and_then(|base_fee| u64::try_from(base_fee).ok()),
+            extra_data: header.extra_data.clone(),
+        };
+        let empty_list_length = list_length(0);
+        U256::from(list_length(header.length() + self.transactions_length + 2 * empty_list_length))
+    }
+}
+
+impl Default for BlockExecution {
+    /// The execution of a block without Kakarot transactions.
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+fn list_length(payload_length: usize) -> usize {
+    length_of_length(payload_length) + payload_length
+}
+
 #[async_trait]
 impl ConvertibleStarknetBlock for BlockWithTxHashes {
-    async fn to_eth_block<P: Provider + Send + Sync>(&self, client: &dyn KakarotEthApi<P>) -> RichBlock {
-        // TODO: Fetch real data
-        let gas_limit = *GAS_LIMIT;
+    async fn to_eth_block<P: Provider + Send + Sync>(
+        &self,
+        client: &dyn KakarotEthApi<P>,
+    ) -> Result<RichBlock, EthApiError<P::Error>> {
+        let parent_hash = H256::from_slice(&self.parent_hash().to_bytes_be());
+        let sequencer = Felt252Wrapper::from(self.sequencer_address()).troncate_to_ethereum_address();
+        let timestamp = U256::from(self.timestamp());
 
-        // TODO: Fetch real data
-        let gas_used = *GAS_USED;
+        let hash = self.block_hash().as_ref().map(|hash| H256::from_slice(&hash.to_bytes_be()));
+        let number = self.block_number().map(U256::from);
+
+        // The transactions are derived from the full transactions of the block, so that only
+        // Kakarot transactions are listed, by their Ethereum hash. The header fields derived from
+        // the receipts are the ones of the hydrated block, the receipts of a block being computed
+        // once and cached by block hash.
+        let block_id = self.block_hash().map_or(StarknetBlockId::Tag(BlockTag::Pending), StarknetBlockId::Hash);
+        let block = BlockWithTxs::new(client.starknet_provider().get_block_with_txs(block_id).await?);
+        let kakarot_transactions: Vec<Transaction> =
+            client.filter_kakarot_transactions(block.transactions().into()).await?.into();
+        let ethereum_transactions: Vec<TransactionSigned> = kakarot_transactions
+            .into_iter()
+            .map(|tx| StarknetTransaction::from(tx).ethereum_transactions())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+        let execution =
+            client.block_execution(block.transactions().into(), self.block_hash().zip(self.block_number())).await?;
+        let transactions =
+            BlockTransactions::Hashes(ethereum_transactions.iter().map(TransactionSigned::hash).collect());
 
         // TODO: Fetch real data
         let difficulty = *DIFFICULTY;
@@ -138,55 +271,36 @@ impl ConvertibleStarknetBlock for BlockWithTxHashes {
         // TODO: Fetch real data
         let nonce: Option<H64> = Some(H64::zero());
 
-        // TODO: Fetch real data
-        let size: Option<U256> = *SIZE;
-
-        // Bloom is a byte array of length 256
-        let logs_bloom = Bloom::default();
-        let extra_data = Bytes::default();
-
         // TODO: Fetch real data
         let base_fee_per_gas = client.base_fee_per_gas();
         // TODO: Fetch real data
         let mix_hash = *MIX_HASH;
 
-        let parent_hash = H256::from_slice(&self.parent_hash().to_bytes_be());
-        let sequencer = Felt252Wrapper::from(self.sequencer_address()).troncate_to_ethereum_address();
-        let timestamp = U256::from(self.timestamp());
-
-        let hash = self.block_hash().as_ref().map(|hash| H256::from_slice(&hash.to_bytes_be()));
-        let number = self.block_number().map(U256::from);
-
-        // TODO: Add filter to tx_hashes
-        let transactions = BlockTransactions::Hashes(
-            self.transactions().iter().map(|tx| H256::from_slice(&tx.to_bytes_be())).collect(),
-        );
-
         let header = Header {
             // PendingBlockWithTxHashes doesn't have a block hash
             hash,
             parent_hash,
-            uncles_hash: parent_hash,
+            // Kakarot blocks have no ommers
+            uncles_hash: EMPTY_OMMER_ROOT,
             miner: sequencer,
-            // PendingBlockWithTxHashes doesn't have a state root
+            // TODO: Fetch real data
             state_root: H256::zero(),
-            // PendingBlockWithTxHashes doesn't have a transactions root
-            transactions_root: H256::zero(),
-            // PendingBlockWithTxHashes doesn't have a receipts root
-            receipts_root: H256::zero(),
+            transactions_root: execution.transactions_root,
+            receipts_root: execution.receipts_root,
             // PendingBlockWithTxHashes doesn't have a block number
             number,
-            gas_used,
-            gas_limit,
-            extra_data,
-            logs_bloom,
+            gas_used: execution.gas_used,
+            gas_limit: client.block_gas_limit(),
+            extra_data: Bytes::default(),
+            logs_bloom: execution.logs_bloom,
             timestamp,
             difficulty,
             nonce,
             base_fee_per_gas: Some(base_fee_per_gas),
             mix_hash,
-            withdrawals_root: Some(H256::zero()),
+            withdrawals_root: Some(EMPTY_ROOT),
         };
+        let size = Some(execution.block_size(&header));
         let block = Block {
             header,
             total_difficulty: *TOTAL_DIFFICULTY,
@@ -195,18 +309,28 @@ impl ConvertibleStarknetBlock for BlockWithTxHashes {
             size,
             withdrawals: Some(vec![]),
         };
-        block.into()
+        Ok(block.into())
     }
 }
 
 #[async_trait]
 impl ConvertibleStarknetBlock for BlockWithTxs {
-    async fn to_eth_block<P: Provider + Send + Sync>(&self, client: &dyn KakarotEthApi<P>) -> RichBlock {
-        // TODO: Fetch real data
-        let gas_limit = *GAS_LIMIT;
+    async fn to_eth_block<P: Provider + Send + Sync>(
+        &self,
+        client: &dyn KakarotEthApi<P>,
+    ) -> Result<RichBlock, EthApiError<P::Error>> {
+        let parent_hash = H256::from_slice(&self.parent_hash().to_bytes_be());
 
-        // TODO: Fetch real data
-        let gas_used = *GAS_USED;
+        let sequencer = Felt252Wrapper::from(self.sequencer_address()).troncate_to_ethereum_address();
+
+        let timestamp = U256::from(self.timestamp());
+
+        let hash = self.block_hash().as_ref().map(|hash| H256::from_slice(&hash.to_bytes_be()));
+        let number = self.block_number().map(U256::from);
+
+        let transactions = client.filter_starknet_into_eth_txs(self.transactions().into(), hash, number).await?;
+        let execution =
+            client.block_execution(self.transactions().into(), self.block_hash().zip(self.block_number())).await?;
 
         // TODO: Fetch real data
         let difficulty = *DIFFICULTY;
@@ -214,53 +338,36 @@ impl ConvertibleStarknetBlock for BlockWithTxs {
         // TODO: Fetch real data
         let nonce: Option<H64> = *NONCE;
 
-        // TODO: Fetch real data
-        let size: Option<U256> = *SIZE;
-
-        // Bloom is a byte array of length 256
-        let logs_bloom = Bloom::default();
-        let extra_data: Bytes = Bytes::default();
-
         // TODO: Fetch real data
         let base_fee_per_gas = client.base_fee_per_gas();
         // TODO: Fetch real data
         let mix_hash = *MIX_HASH;
 
-        let parent_hash = H256::from_slice(&self.parent_hash().to_bytes_be());
-
-        let sequencer = Felt252Wrapper::from(self.sequencer_address()).troncate_to_ethereum_address();
-
-        let timestamp = U256::from(self.timestamp());
-
-        let hash = self.block_hash().as_ref().map(|hash| H256::from_slice(&hash.to_bytes_be()));
-        let number = self.block_number().map(U256::from);
-
-        let transactions = client.filter_starknet_into_eth_txs(self.transactions().into(), hash, number).await;
         let header = Header {
             // PendingBlockWithTxs doesn't have a block hash
             hash,
             parent_hash,
-            uncles_hash: parent_hash,
+            // Kakarot blocks have no ommers
+            uncles_hash: EMPTY_OMMER_ROOT,
             miner: sequencer,
-            // PendingBlockWithTxs doesn't have a state root
+            // TODO: Fetch real data
             state_root: H256::zero(),
-            // PendingBlockWithTxs doesn't have a transactions root
-            transactions_root: H256::zero(),
-            // PendingBlockWithTxs doesn't have a receipts root
-            receipts_root: H256::zero(),
+            transactions_root: execution.transactions_root,
+            receipts_root: execution.receipts_root,
             // PendingBlockWithTxs doesn't have a block number
             number,
-            gas_used,
-            gas_limit,
-            extra_data,
-            logs_bloom,
+            gas_used: execution.gas_used,
+            gas_limit: client.block_gas_limit(),
+            extra_data: Bytes::default(),
+            logs_bloom: execution.logs_bloom,
             timestamp,
             difficulty,
             nonce,
             base_fee_per_gas: Some(base_fee_per_gas),
             mix_hash,
-            withdrawals_root: Some(H256::zero()),
+            withdrawals_root: Some(EMPTY_ROOT),
         };
+        let size = Some(execution.block_size(&header));
         let block = Block {
             header,
             total_difficulty: *TOTAL_DIFFICULTY,
@@ -269,19 +376,28 @@ impl ConvertibleStarknetBlock for BlockWithTxs {
             size,
             withdrawals: Some(vec![]),
         };
-        block.into()
+        Ok(block.into())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use reth_primitives::{Address, U128, U8};
+    use reth_rpc_types::TransactionReceipt;
+    use starknet::core::types::TransactionStatus;
+    use starknet::providers::jsonrpc::JsonRpcMethod;
 
     use super::*;
+    use crate::client::cache::StarknetCache;
     use crate::client::tests::init_mock_client;
     use crate::mock::constants::{
         ABDEL_STARKNET_ADDRESS_HEX, OTHER_ADDRESS_HEX, OTHER_PROXY_ACCOUNT_CLASS_HASH_HEX, PROXY_ACCOUNT_CLASS_HASH_HEX,
     };
     use crate::mock::mock_starknet::{fixtures, AvailableFixtures};
+    use crate::wrap_kakarot;
 
     #[tokio::test]
     async fn test_to_eth_block_block_with_tx_hashes() {
@@ -290,11 +406,23 @@ mod tests {
             serde_json::from_str(include_str!("test_data/conversion/starknet/block_with_tx_hashes.json")).unwrap();
         let starknet_block_with_tx_hashes = BlockWithTxHashes::new(starknet_block_with_tx_hashes);
 
-        let fixtures = fixtures(vec![]);
-        let client = init_mock_client(Some(fixtures));
+        let fixtures = fixtures(vec![
+            wrap_kakarot!(JsonRpcMethod::GetBlockWithTxs),
+            AvailableFixtures::GetClassHashAt(ABDEL_STARKNET_ADDRESS_HEX.into(), PROXY_ACCOUNT_CLASS_HASH_HEX.into()),
+            AvailableFixtures::GetClassHashAt(OTHER_ADDRESS_HEX.into(), OTHER_PROXY_ACCOUNT_CLASS_HASH_HEX.into()),
+        ]);
+        // The receipts of the block are cached, so that they aren't fetched.
+        let cache = Arc::new(StarknetCache::default());
+        let block_hash = starknet_block_with_tx_hashes.block_hash().unwrap();
+        cache.insert_block_receipts(
+            starknet_block_with_tx_hashes.block_number().unwrap(),
+            Felt252Wrapper::from(block_hash).into(),
+            Arc::default(),
+        );
+        let client = init_mock_client(Some(fixtures)).with_cache(cache);
 
         // When
-        let eth_block_with_tx_hashes = starknet_block_with_tx_hashes.to_eth_block(&client).await.inner;
+        let eth_block_with_tx_hashes = starknet_block_with_tx_hashes.to_eth_block(&client).await.unwrap().inner;
 
         // Then
         let expected: Block =
@@ -302,6 +430,23 @@ mod tests {
         assert_eq!(expected, eth_block_with_tx_hashes);
     }
 
+    #[tokio::test]
+    async fn test_to_eth_block_block_with_tx_hashes_should_fail_when_block_is_unavailable() {
+        // Given
+        let starknet_block_with_tx_hashes: MaybePendingBlockWithTxHashes =
+            serde_json::from_str(include_str!("test_data/conversion/starknet/block_with_tx_hashes.json")).unwrap();
+        let starknet_block_with_tx_hashes = BlockWithTxHashes::new(starknet_block_with_tx_hashes);
+
+        let fixtures = fixtures(vec![]);
+        let client = init_mock_client(Some(fixtures));
+
+        // When
+        let eth_block_with_tx_hashes = starknet_block_with_tx_hashes.to_eth_block(&client).await;
+
+        // Then
+        assert!(eth_block_with_tx_hashes.is_err());
+    }
+
     #[tokio::test]
     async fn test_to_eth_block_block_with_txs() {
         // Given
@@ -314,14 +459,93 @@ mod tests {
             AvailableFixtures::GetClassHashAt(OTHER_ADDRESS_HEX.into(), OTHER_PROXY_ACCOUNT_CLASS_HASH_HEX.into()),
             AvailableFixtures::GetEvmAddress,
         ]);
-        let client = init_mock_client(Some(fixtures));
+        // The receipts of the block are cached, so that they aren't fetched.
+        let cache = Arc::new(StarknetCache::default());
+        let block_hash = starknet_block_with_txs.block_hash().unwrap();
+        cache.insert_block_receipts(
+            starknet_block_with_txs.block_number().unwrap(),
+            Felt252Wrapper::from(block_hash).into(),
+            Arc::default(),
+        );
+        let client = init_mock_client(Some(fixtures)).with_cache(cache);
 
         // When
-        let eth_block_with_txs = starknet_block_with_txs.to_eth_block(&client).await.inner;
+        let eth_block_with_txs = starknet_block_with_txs.to_eth_block(&client).await.unwrap().inner;
 
         // Then
         let expected: Block =
             serde_json::from_str(include_str!("test_data/conversion/eth/block_with_txs.json")).unwrap();
         assert_eq!(expected, eth_block_with_txs);
     }
+
+    #[test]
+    fn test_block_execution() {
+        // Given
+        let starknet_transaction: Transaction =
+            serde_json::from_str(include_str!("test_data/conversion/starknet/transaction.json")).unwrap();
+        let transaction = StarknetTransaction::from(starknet_transaction).ethereum_transaction().unwrap();
+        let log: Log = serde_json::from_value(serde_json::json!({
+            "address": "0x2b61c43a85bd35987c5311215e8288b823a6873e",
+            "topics": ["0x0000000000000000000000000000000000000000000000000000000000000001"],
+            "data": "0xdead",
+            "blockHash": null,
+            "blockNumber": null,
+            "transactionHash": null,
+            "transactionIndex": null,
+            "logIndex": null,
+            "removed": false
+        }))
+        .unwrap();
+        let receipt = TransactionReceipt {
+            transaction_hash: Some(transaction.hash()),
+            transaction_index: Some(U256::ZERO),
+            block_hash: None,
+            block_number: None,
+            from: Address::zero(),
+            to: transaction.to(),
+            cumulative_gas_used: U256::from(21_000),
+            gas_used: Some(U256::from(21_000)),
+            contract_address: None,
+            logs_bloom: logs_bloom(&[log.clone()]),
+            logs: vec![log],
+            state_root: None,
+            status_code: Some(U64::from(1)),
+            effective_gas_price: U128::ZERO,
+            transaction_type: U8::from(transaction.tx_type() as u8),
+        };
+        let executed = ExecutedTransaction {
+            starknet_hash: FieldElement::ZERO,
+            transaction,
+            receipt: KakarotTransactionReceipt::new(receipt, TransactionStatus::AcceptedOnL2, None),
+        };
+
+        // When
+        let execution = BlockExecution::new(&[executed.clone()]);
+
+        // Then
+        assert_eq!(U256::from(21_000), execution.gas_used);
+        assert_eq!(executed.receipt.logs_bloom, execution.logs_bloom);
+        assert_eq!(
+            H256::from_str("0xb6bf7dc934fb0a3e89260fed5eaa08355c09b0ce58fc3b7449115ea725143211").unwrap(),
+            execution.transactions_root
+        );
+        assert_eq!(
+            H256::from_str("0x971382681d24d6d2042ba783810e7a03f66801d610fe23f90f851ef364ef91f7").unwrap(),
+            execution.receipts_root
+        );
+        assert_eq!(708, execution.transactions_length);
+    }
+
+    #[test]
+    fn test_block_execution_without_transactions() {
+        // When
+        let execution = BlockExecution::default();
+
+        // Then
+        assert_eq!(U256::ZERO, execution.gas_used);
+        assert_eq!(Bloom::default(), execution.logs_bloom);
+        assert_eq!(EMPTY_ROOT, execution.transactions_root);
+        assert_eq!(EMPTY_ROOT, execution.receipts_root);
+        assert_eq!(1, execution.transactions_length);
+    }
 }
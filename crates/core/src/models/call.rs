@@ -52,37 +52,95 @@ impl From<Vec<StarknetCall>> for Calls {
     }
 }
 
-/// Converts a raw starknet transaction calldata to a vector of starknet calls.
+/// Layouts of the calldata of the `__execute__` entrypoint of Starknet accounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallsLayout {
+    /// Cairo 0 accounts: a call array of `{to, selector, data_offset, data_len}` followed by the
+    /// calldata of all the calls, `[call_array_len, ...call_array, calldata_len, ...calldata]`.
+    CallArray,
+    /// Cairo 1 accounts: a serialized `Array<Call>`, `[calls_len, ...(to, selector, calldata_len,
+    /// ...calldata)]`.
+    CallsArray,
+}
+
+/// Converts a raw starknet transaction calldata to a vector of starknet calls, trying the Cairo 0
+/// and then the Cairo 1 layout.
 impl TryFrom<Vec<FieldElement>> for Calls {
     type Error = ConversionError<()>;
 
     fn try_from(value: Vec<FieldElement>) -> Result<Self, Self::Error> {
-        // in account calls, the calldata is first each call as {contract address, selector, data offset,
-        // data length} and then all the calldata of each call, so each call takes 4 felts, and
-        // eventually the calldata of the first call is at offset =  1 (for call_len) + 4 * call_len + 1
-        // (for calldata_len)
-        let calls_len = u32::try_from(value[0])
-            .map_err(|e| ConversionError::ValueOutOfRange(format!("{}: call array length > u32::MAX", e)))?
-            as usize;
-
-        let mut offset = calls_len * 4 + 2;
-
-        let mut calls = vec![];
-        for i in 0..calls_len {
-            let calldata_len =
-                u32::try_from(value[i * 4 + 4]).map_err(|e| ConversionError::ValueOutOfRange(e.to_string()))? as usize;
-            let call = StarknetCall {
-                to: value[i * 4 + 2],
-                selector: value[i * 4 + 3],
-                calldata: value[offset..offset + calldata_len].to_vec(),
-            };
-            offset += calldata_len;
-            calls.push(call);
+        Self::decode(&value, CallsLayout::CallArray).or_else(|_| Self::decode(&value, CallsLayout::CallsArray))
+    }
+}
+
+fn decode_call_array(reader: &mut CalldataReader<'_>) -> Result<Vec<StarknetCall>, ConversionError<()>> {
+    let layout = CallsLayout::CallArray;
+    let calls_len = reader.length(layout)?;
+    let call_array =
+        reader.felts(calls_len.checked_mul(4).ok_or_else(|| invalid_calldata(layout, "too short"))?, layout)?;
+    let calldata_len = reader.length(layout)?;
+    let calldata = reader.felts(calldata_len, layout)?;
+
+    call_array
+        .chunks_exact(4)
+        .map(|call| {
+            let data_offset = felt_to_usize(call[2], layout)?;
+            let data_len = felt_to_usize(call[3], layout)?;
+            let data = data_offset
+                .checked_add(data_len)
+                .and_then(|end| calldata.get(data_offset..end))
+                .ok_or_else(|| invalid_calldata(layout, "call data out of the calldata"))?;
+            Ok(StarknetCall { to: call[0], selector: call[1], calldata: data.to_vec() })
+        })
+        .collect()
+}
+
+fn decode_calls_array(reader: &mut CalldataReader<'_>) -> Result<Vec<StarknetCall>, ConversionError<()>> {
+    let layout = CallsLayout::CallsArray;
+    let calls_len = reader.length(layout)?;
+
+    // Each call takes at least 3 felts, which bounds the allocation by the calldata length.
+    let mut calls = Vec::with_capacity(calls_len.min(reader.0.len() / 3));
+    for _ in 0..calls_len {
+        let to = reader.felt(layout)?;
+        let selector = reader.felt(layout)?;
+        let calldata_len = reader.length(layout)?;
+        let calldata = reader.felts(calldata_len, layout)?.to_vec();
+        calls.push(StarknetCall { to, selector, calldata });
+    }
+    Ok(calls)
+}
+
+/// Reads the calldata of an account call front to back, failing instead of reading past its end.
+struct CalldataReader<'a>(&'a [FieldElement]);
+
+impl<'a> CalldataReader<'a> {
+    fn felt(&mut self, layout: CallsLayout) -> Result<FieldElement, ConversionError<()>> {
+        Ok(self.felts(1, layout)?[0])
+    }
+
+    fn length(&mut self, layout: CallsLayout) -> Result<usize, ConversionError<()>> {
+        felt_to_usize(self.felt(layout)?, layout)
+    }
+
+    fn felts(&mut self, len: usize, layout: CallsLayout) -> Result<&'a [FieldElement], ConversionError<()>> {
+        if len > self.0.len() {
+            return Err(invalid_calldata(layout, "too short"));
         }
-        Ok(Calls(calls))
+        let (felts, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(felts)
     }
 }
 
+fn felt_to_usize(felt: FieldElement, layout: CallsLayout) -> Result<usize, ConversionError<()>> {
+    u32::try_from(felt).map(|value| value as usize).map_err(|_| invalid_calldata(layout, "length > u32::MAX"))
+}
+
+fn invalid_calldata(layout: CallsLayout, reason: &str) -> ConversionError<()> {
+    ConversionError::TransactionConversionError(format!("invalid {layout:?} account calldata: {reason}"))
+}
+
 impl TryFrom<&Calls> for TransactionSigned {
     type Error = DataDecodingError;
 
@@ -93,17 +151,44 @@ impl TryFrom<&Calls> for TransactionSigned {
             ));
         }
 
-        let call = value.0[0] // for now we decode signature only from the first call
-            .calldata
-            .iter()
-            .filter_map(|x| u8::try_from(*x).ok())
-            .collect::<Vec<u8>>();
-        TransactionSigned::decode(&mut call.as_slice())
-            .map_err(|e| DataDecodingError::SignatureDecodingError(e.to_string()))
+        let call =
+            value.0.first().ok_or_else(|| DataDecodingError::SignatureDecodingError("empty call array".to_string()))?;
+        decode_ethereum_transaction(call)
     }
 }
 
+/// Decodes the Ethereum transaction of each call, in the order of execution of the calls.
+impl TryFrom<&Calls> for Vec<TransactionSigned> {
+    type Error = DataDecodingError;
+
+    fn try_from(value: &Calls) -> std::result::Result<Self, Self::Error> {
+        value.0.iter().map(decode_ethereum_transaction).collect()
+    }
+}
+
+/// Decodes the signed Ethereum transaction passed as calldata of a Kakarot call, one byte per
+/// felt.
+fn decode_ethereum_transaction(call: &StarknetCall) -> Result<TransactionSigned, DataDecodingError> {
+    let call = call.calldata.iter().filter_map(|x| u8::try_from(*x).ok()).collect::<Vec<u8>>();
+    TransactionSigned::decode(&mut call.as_slice())
+        .map_err(|e| DataDecodingError::SignatureDecodingError(e.to_string()))
+}
+
 impl Calls {
+    /// Decodes the calls of the calldata in the given layout. The calldata must be entirely
+    /// consumed by the calls.
+    pub fn decode(calldata: &[FieldElement], layout: CallsLayout) -> Result<Self, ConversionError<()>> {
+        let mut reader = CalldataReader(calldata);
+        let calls = match layout {
+            CallsLayout::CallArray => decode_call_array(&mut reader)?,
+            CallsLayout::CallsArray => decode_calls_array(&mut reader)?,
+        };
+        if !reader.0.is_empty() {
+            return Err(invalid_calldata(layout, "trailing felts"));
+        }
+        Ok(Self(calls))
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -124,11 +209,13 @@ impl Calls {
 mod tests {
     use std::str::FromStr;
 
+    use proptest::prelude::*;
     use reth_primitives::{Address, U256};
     use serde::Deserialize;
 
     use super::*;
     use crate::client::constants::selectors::ETH_CALL;
+    use crate::client::helpers::raw_kakarot_multicall_calldata;
     use crate::mock::constants::ACCOUNT_ADDRESS;
 
     #[derive(Deserialize)]
@@ -136,6 +223,32 @@ mod tests {
         pub calldata: Vec<FieldElement>,
     }
 
+    /// Serializes the calls as a Cairo 1 `Array<Call>`.
+    fn calls_array(calls: &[StarknetCall]) -> Vec<FieldElement> {
+        let mut calldata = vec![FieldElement::from(calls.len())];
+        for call in calls {
+            calldata.extend([call.to, call.selector, FieldElement::from(call.calldata.len())]);
+            calldata.extend(call.calldata.iter().copied());
+        }
+        calldata
+    }
+
+    fn felt() -> impl Strategy<Value = FieldElement> {
+        prop_oneof![
+            (0u64..8).prop_map(FieldElement::from),
+            any::<u64>().prop_map(FieldElement::from),
+            any::<[u8; 31]>().prop_map(|bytes| FieldElement::from_byte_slice_be(&bytes).unwrap()),
+        ]
+    }
+
+    fn starknet_call() -> impl Strategy<Value = StarknetCall> {
+        (felt(), felt(), prop::collection::vec(felt(), 0..8)).prop_map(|(to, selector, calldata)| StarknetCall {
+            to,
+            selector,
+            calldata,
+        })
+    }
+
     #[test]
     fn test_from_call() {
         // Given
@@ -183,6 +296,78 @@ mod tests {
         assert_eq!(expected[2].calldata, calls.get(2).unwrap().calldata);
     }
 
+    #[test]
+    fn test_try_from_cairo_1_calls() {
+        // Given
+        let expected: Vec<SerdeCall> = serde_json::from_str(include_str!("test_data/call/call.json")).unwrap();
+        let calls: Vec<StarknetCall> = expected
+            .iter()
+            .map(|call| StarknetCall { to: *ACCOUNT_ADDRESS, selector: ETH_CALL, calldata: call.calldata.clone() })
+            .collect();
+
+        // When
+        let decoded: Calls = calls_array(&calls).try_into().unwrap();
+
+        // Then
+        assert_eq!(3, decoded.len());
+        for (call, expected) in decoded.0.iter().zip(&expected) {
+            assert_eq!(*ACCOUNT_ADDRESS, call.to);
+            assert_eq!(ETH_CALL, call.selector);
+            assert_eq!(expected.calldata, call.calldata);
+        }
+    }
+
+    #[test]
+    fn test_try_from_malformed_calls() {
+        // Given
+        let raw: Vec<FieldElement> = serde_json::from_str(include_str!("test_data/call/raw_call.json")).unwrap();
+        let mut out_of_bounds_offset = raw.clone();
+        out_of_bounds_offset[3] = FieldElement::from(100u8);
+
+        // When
+        let empty = Calls::try_from(vec![]);
+        let truncated = Calls::try_from(raw[..raw.len() - 1].to_vec());
+        let out_of_bounds_offset = Calls::try_from(out_of_bounds_offset);
+        let huge_length = Calls::try_from(vec![FieldElement::MAX]);
+
+        // Then
+        assert!(empty.is_err());
+        assert!(truncated.is_err());
+        assert!(out_of_bounds_offset.is_err());
+        assert!(huge_length.is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_fuzz_try_from_calls(calldata in prop::collection::vec(felt(), 0..64)) {
+            // Decoding arbitrary calldata returns an error instead of panicking.
+            let _ = Calls::try_from(calldata);
+        }
+
+        #[test]
+        fn test_fuzz_calls_layouts_round_trip(calls in prop::collection::vec(starknet_call(), 0..4)) {
+            // Given
+            let calldatas = calls.iter().map(|call| call.calldata.clone()).collect();
+            let cairo_0 = raw_kakarot_multicall_calldata(FieldElement::ONE, calldatas);
+            let cairo_1 = calls_array(&calls);
+
+            // When
+            let decoded_cairo_0 = Calls::decode(&cairo_0, CallsLayout::CallArray).unwrap();
+            let decoded_cairo_1 = Calls::decode(&cairo_1, CallsLayout::CallsArray).unwrap();
+
+            // Then
+            prop_assert_eq!(calls.len(), decoded_cairo_0.len());
+            prop_assert_eq!(calls.len(), decoded_cairo_1.len());
+            for ((call, cairo_0), cairo_1) in calls.iter().zip(&decoded_cairo_0.0).zip(&decoded_cairo_1.0) {
+                prop_assert_eq!(&call.calldata, &cairo_0.calldata);
+                prop_assert_eq!(
+                    (call.to, call.selector, &call.calldata),
+                    (cairo_1.to, cairo_1.selector, &cairo_1.calldata)
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_calls_get_signature_should_pass() {
         // Given
@@ -215,6 +400,24 @@ mod tests {
         TryInto::<TransactionSigned>::try_into(&calls).unwrap();
     }
 
+    #[test]
+    fn test_calls_get_transactions() {
+        // Given
+        let raw: Vec<FieldElement> = serde_json::from_str(include_str!("test_data/call/kakarot_call.json")).unwrap();
+        let calls: Calls = raw.try_into().unwrap();
+        let call = calls.get(0).unwrap();
+        let raw = raw_kakarot_multicall_calldata(call.to, vec![call.calldata.clone(), call.calldata.clone()]);
+        let batch: Calls = raw.try_into().unwrap();
+
+        // When
+        let transactions = TryInto::<Vec<TransactionSigned>>::try_into(&batch).unwrap();
+
+        // Then
+        let expected = TryInto::<TransactionSigned>::try_into(&calls).unwrap();
+        assert_eq!(2, batch.len());
+        assert_eq!(vec![expected.clone(), expected], transactions);
+    }
+
     #[test]
     fn test_calls_get_to() {
         // Given
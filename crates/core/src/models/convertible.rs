@@ -8,7 +8,10 @@ use crate::client::errors::EthApiError;
 
 #[async_trait]
 pub trait ConvertibleStarknetBlock {
-    async fn to_eth_block<P: Provider + Send + Sync>(&self, client: &dyn KakarotEthApi<P>) -> RichBlock;
+    async fn to_eth_block<P: Provider + Send + Sync>(
+        &self,
+        client: &dyn KakarotEthApi<P>,
+    ) -> Result<RichBlock, EthApiError<P::Error>>;
 }
 
 pub trait ConvertibleStarknetEvent {
@@ -25,6 +28,7 @@ pub trait ConvertibleStarknetEvent {
 
 #[async_trait]
 pub trait ConvertibleStarknetTransaction {
+    /// Converts the transaction into the first of the Ethereum transactions it wraps.
     async fn to_eth_transaction<P: Provider + Send + Sync>(
         &self,
         client: &dyn KakarotEthApi<P>,
@@ -32,4 +36,14 @@ pub trait ConvertibleStarknetTransaction {
         block_number: Option<U256>,
         transaction_index: Option<U256>,
     ) -> Result<EthTransaction, EthApiError<P::Error>>;
+
+    /// Converts the transaction into the Ethereum transactions it wraps, one per call of the
+    /// multicall, the first one at `transaction_index`.
+    async fn to_eth_transactions<P: Provider + Send + Sync>(
+        &self,
+        client: &dyn KakarotEthApi<P>,
+        block_hash: Option<H256>,
+        block_number: Option<U256>,
+        transaction_index: Option<U256>,
+    ) -> Result<Vec<EthTransaction>, EthApiError<P::Error>>;
 }
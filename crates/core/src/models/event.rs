@@ -3,7 +3,7 @@ use core::iter::once;
 use num_bigint::BigUint;
 use reth_primitives::{Address, Bytes, H256, U256};
 use reth_rpc_types::Log;
-use starknet::core::types::Event;
+use starknet::core::types::{Event, FieldElement};
 use starknet::providers::Provider;
 
 use super::felt::Felt252Wrapper;
@@ -19,6 +19,11 @@ impl StarknetEvent {
     pub fn new(sn_event: Event) -> Self {
         Self(sn_event)
     }
+
+    /// Returns true if the event is an EVM log, i.e. if it was emitted by Kakarot.
+    pub fn is_evm_log(&self, kakarot_address: FieldElement) -> bool {
+        self.0.from_address == kakarot_address
+    }
 }
 
 impl From<Event> for StarknetEvent {
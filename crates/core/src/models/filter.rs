@@ -0,0 +1,166 @@
+use reth_primitives::{BlockId as EthereumBlockId, BlockNumberOrTag, H256};
+use reth_rpc_types::{Filter, FilterBlockOption, Log};
+use starknet::core::types::BlockId as StarknetBlockId;
+
+use super::block::EthBlockId;
+use super::ConversionError;
+
+/// Wrapper around an Ethereum log `Filter`.
+///
+/// Kakarot packs the EVM address in the last key of its Starknet events and each topic in two
+/// 128-bit keys, so address and topics can only be matched once the event has been translated
+/// into an Ethereum `Log`.
+#[derive(Debug, Clone)]
+pub struct EthFilter(Filter);
+
+impl EthFilter {
+    pub fn new(filter: Filter) -> Self {
+        Self(filter)
+    }
+
+    /// Returns the Starknet block range covered by the filter.
+    /// Missing bounds default to the latest block, as per the Ethereum specification.
+    pub fn starknet_block_range(&self) -> Result<(StarknetBlockId, StarknetBlockId), ConversionError<()>> {
+        match &self.0.block_option {
+            FilterBlockOption::AtBlockHash(hash) => {
+                let from_block = EthBlockId::new(EthereumBlockId::Hash((*hash).into())).try_into()?;
+                let to_block = EthBlockId::new(EthereumBlockId::Hash((*hash).into())).try_into()?;
+                Ok((from_block, to_block))
+            }
+            FilterBlockOption::Range { from_block, to_block } => {
+                let from_block = from_block.unwrap_or(BlockNumberOrTag::Latest);
+                let to_block = to_block.unwrap_or(BlockNumberOrTag::Latest);
+                Ok((
+                    EthBlockId::new(EthereumBlockId::Number(from_block)).try_into()?,
+                    EthBlockId::new(EthereumBlockId::Number(to_block)).try_into()?,
+                ))
+            }
+        }
+    }
+
+    /// Returns true if the log matches the address and the topics of the filter.
+    pub fn matches(&self, log: &Log) -> bool {
+        self.0.address.matches(&log.address) && self.matches_topics(&log.topics)
+    }
+
+    /// Every topic position of the filter must match the topic of the log at the same position.
+    /// An empty position (`null` in the request) matches any topic, while a non-empty position
+    /// never matches a log with fewer topics.
+    fn matches_topics(&self, topics: &[H256]) -> bool {
+        self.0.topics.iter().enumerate().all(|(i, topic_set)| {
+            if topic_set.is_empty() {
+                return true;
+            }
+            topics.get(i).map_or(false, |topic| topic_set.matches(topic))
+        })
+    }
+}
+
+impl From<EthFilter> for Filter {
+    fn from(filter: EthFilter) -> Self {
+        filter.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn log3() -> Log {
+        serde_json::from_str(include_str!("test_data/conversion/eth/event_log3.json")).unwrap()
+    }
+
+    fn filter(value: serde_json::Value) -> EthFilter {
+        EthFilter::new(serde_json::from_value(value).unwrap())
+    }
+
+    #[test]
+    fn test_matches_empty_filter() {
+        // Given
+        let filter = filter(json!({}));
+
+        // When
+        let matches = filter.matches(&log3());
+
+        // Then
+        assert!(matches);
+    }
+
+    #[test]
+    fn test_matches_address() {
+        // Given
+        let matching = filter(json!({
+            "address": ["0x0000000000000000000000000000000000000001", "0x2b61c43a85bd35987c5311215e8288b823a6873e"]
+        }));
+        let not_matching = filter(json!({ "address": "0x0000000000000000000000000000000000000001" }));
+
+        // When
+        let log = log3();
+
+        // Then
+        assert!(matching.matches(&log));
+        assert!(!not_matching.matches(&log));
+    }
+
+    #[test]
+    fn test_matches_topics_with_wildcards() {
+        // Given
+        let matching = filter(json!({
+            "topics": [
+                null,
+                [
+                    "0x000000000000000000000000000000000000000000000000000000000000000c",
+                    "0x000000000000000000000000000000000000000000000000000000000000000a"
+                ],
+                "0x000000000000000000000000000000000000000000000000000000000000000b"
+            ]
+        }));
+        let not_matching = filter(json!({
+            "topics": [
+                "0x5998d146b8109b9444e9bb13ae9a548e7f38d2db6e0da72afe22cefa3065bc63",
+                "0x000000000000000000000000000000000000000000000000000000000000000b"
+            ]
+        }));
+
+        // When
+        let log = log3();
+
+        // Then
+        assert!(matching.matches(&log));
+        assert!(!not_matching.matches(&log));
+    }
+
+    #[test]
+    fn test_does_not_match_missing_topic() {
+        // Given
+        let filter = filter(json!({
+            "topics": [
+                null,
+                null,
+                null,
+                "0x000000000000000000000000000000000000000000000000000000000000000a"
+            ]
+        }));
+
+        // When
+        let matches = filter.matches(&log3());
+
+        // Then
+        assert!(!matches);
+    }
+
+    #[test]
+    fn test_starknet_block_range_defaults_to_latest() {
+        // Given
+        let filter = filter(json!({ "fromBlock": "0x1" }));
+
+        // When
+        let (from_block, to_block) = filter.starknet_block_range().unwrap();
+
+        // Then
+        assert_eq!(StarknetBlockId::Number(1), from_block);
+        assert_eq!(StarknetBlockId::Tag(starknet::core::types::BlockTag::Latest), to_block);
+    }
+}
@@ -4,6 +4,8 @@ pub mod call;
 pub mod convertible;
 pub mod event;
 pub mod felt;
+pub mod filter;
+pub mod receipt;
 pub mod signature;
 #[cfg(test)]
 pub mod tests;
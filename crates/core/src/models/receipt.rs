@@ -0,0 +1,247 @@
+use std::ops::{Deref, DerefMut};
+
+use reth_primitives::{Bytes, U64};
+use reth_rpc_types::TransactionReceipt;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{Event, FieldElement, TransactionStatus};
+
+use crate::client::constants::selectors::TRANSACTION_EXECUTED;
+use crate::client::helpers::{vec_felt_to_bytes, DataDecodingError};
+
+/// An Ethereum transaction receipt, extended with the status of the Starknet transaction wrapping
+/// the Ethereum transaction and the data returned by a reverted EVM execution.
+///
+/// A failed transaction was rejected by Starknet when `starknetStatus` is `REJECTED`, and reverted
+/// in the EVM when `revertData` is set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KakarotTransactionReceipt {
+    #[serde(flatten)]
+    pub inner: TransactionReceipt,
+    #[serde(default)]
+    pub starknet_status: Option<TransactionStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_data: Option<Bytes>,
+}
+
+impl KakarotTransactionReceipt {
+    /// Returns the receipt of an Ethereum transaction wrapped in a Starknet transaction with the
+    /// given status, whose status code reports the outcome of its EVM execution when known.
+    pub fn new(
+        inner: TransactionReceipt,
+        starknet_status: TransactionStatus,
+        outcome: Option<&EvmExecutionOutcome>,
+    ) -> Self {
+        let revert_data = outcome.and_then(EvmExecutionOutcome::revert_data).cloned();
+        let mut receipt = Self { inner, starknet_status: None, revert_data };
+        receipt.set_starknet_status(starknet_status);
+        receipt
+    }
+
+    /// Sets the status of the Starknet transaction wrapping the Ethereum transaction, along with
+    /// the status code of the receipt: 0 if the Starknet transaction was rejected or if the EVM
+    /// execution reverted, 1 otherwise.
+    pub fn set_starknet_status(&mut self, starknet_status: TransactionStatus) {
+        let success = !matches!(starknet_status, TransactionStatus::Rejected) && self.revert_data.is_none();
+        self.inner.status_code = Some(U64::from(u8::from(success)));
+        self.starknet_status = Some(starknet_status);
+    }
+}
+
+impl Deref for KakarotTransactionReceipt {
+    type Target = TransactionReceipt;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for KakarotTransactionReceipt {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+/// Outcome of the EVM execution of a Kakarot transaction, reported by the `transaction_executed`
+/// event of the Kakarot account which sent it. The event is defined by the externally owned account
+/// of the Kakarot contracts (`src/kakarot/accounts/eoa/library.cairo` in `lib/kakarot`) as
+/// `transaction_executed(response_len: felt, response: felt*, success: felt, gas_used: felt)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvmExecutionOutcome {
+    pub success: bool,
+    pub return_data: Bytes,
+}
+
+impl EvmExecutionOutcome {
+    /// Returns the outcomes reported by the account `sender_address` in the events of its Starknet
+    /// transaction, one per Kakarot call, in the order of the calls.
+    pub fn from_events(events: &[Event], sender_address: FieldElement) -> Vec<Self> {
+        events
+            .iter()
+            .filter(|event| event.from_address == sender_address && event.keys.first() == Some(&TRANSACTION_EXECUTED))
+            .filter_map(|event| Self::from_event_data(&event.data))
+            .collect()
+    }
+
+    /// Decodes the data of the event, `[response_len, ...response, success, gas_used]`.
+    fn from_event_data(data: &[FieldElement]) -> Option<Self> {
+        let (response_len, data) = data.split_first()?;
+        let response_len = usize::try_from(u64::try_from(*response_len).ok()?).ok()?;
+        let response = data.get(..response_len)?;
+        let success = *data.get(response_len)? != FieldElement::ZERO;
+
+        Some(Self { success, return_data: vec_felt_to_bytes(response.to_vec()) })
+    }
+
+    /// Decodes the outcome from the result of the `eth_call` entrypoint of Kakarot, which is
+    /// `[return_data_len, ...return_data]`, optionally followed by `success`. The execution
+    /// succeeded when `success` is not returned.
+    pub fn from_call_result(call_result: &[FieldElement]) -> Result<Self, DataDecodingError> {
+        let invalid_length = |expected| DataDecodingError::InvalidReturnArrayLength {
+            entrypoint: "eth_call".into(),
+            expected,
+            actual: call_result.len(),
+        };
+
+        let (return_data_len, data) = call_result.split_first().ok_or_else(|| invalid_length(1))?;
+        let return_data_len = u64::try_from(*return_data_len)
+            .ok()
+            .and_then(|len| usize::try_from(len).ok())
+            .ok_or_else(|| invalid_length(1))?;
+        let return_data = data.get(..return_data_len).ok_or_else(|| invalid_length(return_data_len + 1))?;
+        let success = match &data[return_data_len..] {
+            [] => true,
+            [success] => *success != FieldElement::ZERO,
+            _ => return Err(invalid_length(return_data_len + 2)),
+        };
+
+        Ok(Self { success, return_data: vec_felt_to_bytes(return_data.to_vec()) })
+    }
+
+    /// Returns the data returned by the execution if it reverted.
+    pub fn revert_data(&self) -> Option<&Bytes> {
+        (!self.success).then_some(&self.return_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt;
+
+    use super::*;
+    use crate::client::helpers::create_default_transaction_receipt;
+
+    fn transaction_executed(from_address: FieldElement, data: Vec<FieldElement>) -> Event {
+        Event { from_address, keys: vec![TRANSACTION_EXECUTED], data }
+    }
+
+    #[test]
+    fn test_outcomes_from_events() {
+        // Given
+        let sender = felt!("0x0123");
+        let events = vec![
+            transaction_executed(felt!("0x0456"), vec![FieldElement::ZERO, FieldElement::ONE, felt!("0x5208")]),
+            transaction_executed(sender, vec![FieldElement::ZERO, FieldElement::ONE, felt!("0x5208")]),
+            Event { from_address: sender, keys: vec![felt!("0x01")], data: vec![] },
+            transaction_executed(
+                sender,
+                vec![felt!("0x02"), felt!("0xde"), felt!("0xad"), FieldElement::ZERO, felt!("0x5208")],
+            ),
+        ];
+
+        // When
+        let outcomes = EvmExecutionOutcome::from_events(&events, sender);
+
+        // Then
+        assert_eq!(
+            vec![
+                EvmExecutionOutcome { success: true, return_data: Bytes::default() },
+                EvmExecutionOutcome { success: false, return_data: Bytes::from(vec![0xde, 0xad]) },
+            ],
+            outcomes
+        );
+    }
+
+    #[test]
+    fn test_outcomes_from_events_skip_truncated_events() {
+        // Given
+        let sender = felt!("0x0123");
+        let events = vec![transaction_executed(sender, vec![felt!("0x02"), felt!("0xde")])];
+
+        // When
+        let outcomes = EvmExecutionOutcome::from_events(&events, sender);
+
+        // Then
+        assert!(outcomes.is_empty());
+    }
+
+    #[test]
+    fn test_outcome_from_call_result() {
+        // Given
+        let succeeded = vec![felt!("0x02"), felt!("0xde"), felt!("0xad")];
+        let reverted = vec![felt!("0x02"), felt!("0xde"), felt!("0xad"), FieldElement::ZERO];
+        let truncated = vec![felt!("0x02"), felt!("0xde")];
+
+        // When
+        let succeeded = EvmExecutionOutcome::from_call_result(&succeeded).unwrap();
+        let reverted = EvmExecutionOutcome::from_call_result(&reverted).unwrap();
+        let truncated = EvmExecutionOutcome::from_call_result(&truncated);
+
+        // Then
+        assert_eq!(None, succeeded.revert_data());
+        assert_eq!(Some(&Bytes::from(vec![0xde, 0xad])), reverted.revert_data());
+        assert!(truncated.is_err());
+    }
+
+    #[test]
+    fn test_receipt_of_accepted_but_reverted_transaction() {
+        // Given
+        let outcome = EvmExecutionOutcome { success: false, return_data: Bytes::from(vec![0xde, 0xad]) };
+
+        // When
+        let receipt = KakarotTransactionReceipt::new(
+            create_default_transaction_receipt(),
+            TransactionStatus::AcceptedOnL2,
+            Some(&outcome),
+        );
+
+        // Then
+        assert_eq!(Some(U64::from(0)), receipt.status_code);
+        assert_eq!(Some(Bytes::from(vec![0xde, 0xad])), receipt.revert_data);
+        let value = serde_json::to_value(&receipt).unwrap();
+        assert_eq!("ACCEPTED_ON_L2", value["starknetStatus"]);
+        assert_eq!("0xdead", value["revertData"]);
+        assert_eq!(receipt, serde_json::from_value(value).unwrap());
+    }
+
+    #[test]
+    fn test_receipt_status_code() {
+        let success = EvmExecutionOutcome { success: true, return_data: Bytes::default() };
+        let status_code = |status, outcome| {
+            KakarotTransactionReceipt::new(create_default_transaction_receipt(), status, outcome).status_code
+        };
+
+        assert_eq!(Some(U64::from(1)), status_code(TransactionStatus::AcceptedOnL2, Some(&success)));
+        assert_eq!(Some(U64::from(1)), status_code(TransactionStatus::AcceptedOnL1, None));
+        assert_eq!(Some(U64::from(1)), status_code(TransactionStatus::Pending, None));
+        assert_eq!(Some(U64::from(0)), status_code(TransactionStatus::Rejected, None));
+    }
+
+    #[test]
+    fn test_set_starknet_status_keeps_the_evm_outcome() {
+        // Given
+        let outcome = EvmExecutionOutcome { success: false, return_data: Bytes::default() };
+        let mut receipt = KakarotTransactionReceipt::new(
+            create_default_transaction_receipt(),
+            TransactionStatus::Pending,
+            Some(&outcome),
+        );
+
+        // When
+        receipt.set_starknet_status(TransactionStatus::AcceptedOnL1);
+
+        // Then
+        assert_eq!(Some(U64::from(0)), receipt.status_code);
+        assert_eq!(Some(TransactionStatus::AcceptedOnL1), receipt.starknet_status);
+    }
+}
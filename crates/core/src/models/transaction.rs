@@ -1,13 +1,16 @@
 use async_trait::async_trait;
-use reth_primitives::{TransactionSigned, H256, U256};
+use reth_primitives::{
+    Address, Transaction as EthereumTransaction, TransactionSigned, TxEip1559, TxEip2930, TxLegacy, H256, U128, U256,
+    U64,
+};
 use reth_rpc_types::{Signature, Transaction as EthTransaction};
 use starknet::core::types::{BlockId as StarknetBlockId, BlockTag, FieldElement, InvokeTransaction, Transaction};
 use starknet::providers::Provider;
 
 use super::felt::Felt252Wrapper;
 use super::ConversionError;
-use crate::client::api::KakarotEthApi;
-use crate::client::constants::{self, CHAIN_ID};
+use crate::client::api::{KakarotEthApi, KakarotStarknetApi};
+use crate::client::constants;
 use crate::client::errors::EthApiError;
 use crate::models::call::Calls;
 use crate::models::convertible::ConvertibleStarknetTransaction;
@@ -47,6 +50,19 @@ impl StarknetTransaction {
     get_invoke_transaction_field!((nonce, nonce), Felt252Wrapper);
     get_invoke_transaction_field!((calldata, calldata), Vec<FieldElement>);
     get_invoke_transaction_field!((contract_address, sender_address), Felt252Wrapper);
+
+    /// Decodes the signed Ethereum transaction wrapped in the calldata of the Kakarot transaction.
+    pub fn ethereum_transaction(&self) -> Result<TransactionSigned, ConversionError<()>> {
+        let calls: Calls = self.calldata()?.try_into()?;
+        Ok((&calls).try_into()?)
+    }
+
+    /// Decodes the signed Ethereum transactions wrapped in the calldata of the Kakarot transaction,
+    /// one per call of the multicall, in their order of execution.
+    pub fn ethereum_transactions(&self) -> Result<Vec<TransactionSigned>, ConversionError<()>> {
+        let calls: Calls = self.calldata()?.try_into()?;
+        Ok((&calls).try_into()?)
+    }
 }
 
 pub struct StarknetTransactions(Vec<Transaction>);
@@ -72,6 +88,17 @@ impl ConvertibleStarknetTransaction for StarknetTransaction {
         block_number: Option<U256>,
         transaction_index: Option<U256>,
     ) -> Result<EthTransaction, EthApiError<P::Error>> {
+        let transactions = self.to_eth_transactions(client, block_hash, block_number, transaction_index).await?;
+        transactions.into_iter().next().ok_or_else(|| EthApiError::KakarotDataFilteringError("Transaction".into()))
+    }
+
+    async fn to_eth_transactions<P: Provider + Send + Sync>(
+        &self,
+        client: &dyn KakarotEthApi<P>,
+        block_hash: Option<H256>,
+        block_number: Option<U256>,
+        transaction_index: Option<U256>,
+    ) -> Result<Vec<EthTransaction>, EthApiError<P::Error>> {
         if !self.is_kakarot_tx(client).await? {
             return Err(EthApiError::KakarotDataFilteringError("Transaction".into()));
         }
@@ -79,57 +106,126 @@ impl ConvertibleStarknetTransaction for StarknetTransaction {
         let starknet_block_latest = StarknetBlockId::Tag(BlockTag::Latest);
         let sender_address: FieldElement = self.sender_address()?.into();
 
-        let hash: H256 = self.transaction_hash()?.into();
-
         let nonce: U256 = self.nonce()?.into();
 
         let from = client.get_evm_address(&sender_address, &starknet_block_latest).await?;
 
-        let max_priority_fee_per_gas = Some(client.max_priority_fee_per_gas());
+        let base_fee_per_gas: u128 = client.base_fee_per_gas().try_into().map_err(ConversionError::<u128>::from)?;
 
-        let calls: Calls = self.calldata()?.try_into()?;
-        let tx: TransactionSigned = (&calls).try_into()?;
-        let input = tx.input().to_owned();
-        let signature = tx.signature;
-        let to = tx.to();
-
-        let v = if signature.odd_y_parity { 1 } else { 0 } + 35 + 2 * CHAIN_ID;
-        let signature = Some(Signature { r: signature.r, s: signature.s, v: U256::from_limbs_slice(&[v]) });
-
-        Ok(EthTransaction {
-            hash,
-            nonce,
-            block_hash,
-            block_number,
-            transaction_index,
-            from,
-            to,                     // TODO fetch the to
-            value: U256::from(100), // TODO fetch the value
-            gas_price: None,        // TODO fetch the gas price
-            gas: U256::from(100),   // TODO fetch the gas amount
-            max_fee_per_gas: None,  // TODO fetch the max_fee_per_gas
-            max_priority_fee_per_gas,
-            input,
-            signature,
-            chain_id: Some(CHAIN_ID.into()),
-            access_list: None,      // TODO fetch the access list
-            transaction_type: None, // TODO fetch the transaction type
-        })
+        // The transactions of a multicall share the nonce of the Starknet transaction and have
+        // consecutive indices in their block.
+        Ok(self
+            .ethereum_transactions()?
+            .into_iter()
+            .enumerate()
+            .map(|(i, tx)| EthTransaction {
+                nonce,
+                block_hash,
+                block_number,
+                transaction_index: transaction_index.map(|index| index + U256::from(i)),
+                ..rpc_transaction(&tx, from, base_fee_per_gas)
+            })
+            .collect())
+    }
+}
+
+/// Returns the RPC representation of a signed Ethereum transaction sent by `from`, outside of any
+/// block.
+pub fn rpc_transaction(tx: &TransactionSigned, from: Address, base_fee_per_gas: u128) -> EthTransaction {
+    let (gas_price, max_fee_per_gas, max_priority_fee_per_gas) = fee_fields(&tx.transaction, base_fee_per_gas);
+
+    let access_list = match &tx.transaction {
+        EthereumTransaction::Legacy(_) => None,
+        EthereumTransaction::Eip2930(TxEip2930 { access_list, .. })
+        | EthereumTransaction::Eip1559(TxEip1559 { access_list, .. }) => Some(access_list.0.clone()),
+    };
+
+    let parity = u64::from(tx.signature.odd_y_parity);
+    let v = match &tx.transaction {
+        // EIP-155 replay protected legacy transaction
+        EthereumTransaction::Legacy(TxLegacy { chain_id: Some(chain_id), .. }) => parity + 35 + 2 * chain_id,
+        EthereumTransaction::Legacy(TxLegacy { chain_id: None, .. }) => parity + 27,
+        // Typed transactions only carry the parity of the y coordinate
+        EthereumTransaction::Eip2930(_) | EthereumTransaction::Eip1559(_) => parity,
+    };
+    let signature = Some(Signature { r: tx.signature.r, s: tx.signature.s, v: U256::from(v) });
+
+    EthTransaction {
+        hash: tx.hash(),
+        nonce: U256::from(tx.nonce()),
+        block_hash: None,
+        block_number: None,
+        transaction_index: None,
+        from,
+        to: tx.to(),
+        value: U256::from(tx.value()),
+        gas_price: Some(gas_price),
+        gas: U256::from(tx.gas_limit()),
+        max_fee_per_gas,
+        max_priority_fee_per_gas,
+        input: tx.input().to_owned(),
+        signature,
+        chain_id: tx.chain_id().map(U64::from),
+        access_list,
+        transaction_type: Some(U64::from(tx.tx_type() as u8)),
+    }
+}
+
+/// Returns the gas price, the max fee per gas and the max priority fee per gas of the transaction.
+/// The gas price of an EIP-1559 transaction is its effective gas price, `min(max_fee_per_gas,
+/// base_fee_per_gas + max_priority_fee_per_gas)`.
+fn fee_fields(transaction: &EthereumTransaction, base_fee_per_gas: u128) -> (U128, Option<U128>, Option<U128>) {
+    match transaction {
+        EthereumTransaction::Legacy(TxLegacy { gas_price, .. })
+        | EthereumTransaction::Eip2930(TxEip2930 { gas_price, .. }) => (U128::from(*gas_price), None, None),
+        EthereumTransaction::Eip1559(TxEip1559 { max_fee_per_gas, max_priority_fee_per_gas, .. }) => {
+            let gas_price = base_fee_per_gas.saturating_add(*max_priority_fee_per_gas).min(*max_fee_per_gas);
+            (U128::from(gas_price), Some(U128::from(*max_fee_per_gas)), Some(U128::from(*max_priority_fee_per_gas)))
+        }
     }
 }
 
 impl StarknetTransaction {
-    /// Checks if the transaction is a Kakarot transaction.
-    async fn is_kakarot_tx<P: Provider + Send + Sync>(
+    /// Checks if the transaction is a Kakarot transaction, i.e. if it was sent by a Kakarot
+    /// account.
+    pub async fn is_kakarot_tx<P: Provider + Send + Sync>(
         &self,
         client: &dyn KakarotEthApi<P>,
     ) -> Result<bool, EthApiError<P::Error>> {
-        let starknet_block_latest = StarknetBlockId::Tag(BlockTag::Latest);
         let sender_address: FieldElement = self.sender_address()?.into();
+        client.is_kakarot_account(sender_address).await
+    }
 
-        let class_hash = client.starknet_provider().get_class_hash_at(starknet_block_latest, sender_address).await?;
+    /// Returns the Ethereum hash of the transaction, from the hash mapping when it is known or by
+    /// decoding the wrapped Ethereum transaction. Transactions which can't be decoded keep their
+    /// Starknet hash.
+    pub fn ethereum_transaction_hash<P, C>(&self, client: &C) -> Result<H256, EthApiError<P::Error>>
+    where
+        P: Provider + Send + Sync,
+        C: KakarotStarknetApi<P> + ?Sized,
+    {
+        let starknet_hash: H256 = self.transaction_hash()?.into();
+        if let Some(ethereum_hash) = client.ethereum_transaction_hash(starknet_hash)? {
+            return Ok(ethereum_hash);
+        }
+        match self.ethereum_transaction() {
+            Ok(tx) => Ok(tx.hash()),
+            Err(_) => Ok(starknet_hash),
+        }
+    }
 
-        Ok(class_hash == client.proxy_account_class_hash())
+    /// Returns the Ethereum hashes of the transactions wrapped in the Kakarot transaction, one per
+    /// call of the multicall. Transactions which can't be decoded are listed by their hash as
+    /// returned by `ethereum_transaction_hash`.
+    pub fn ethereum_transaction_hashes<P, C>(&self, client: &C) -> Result<Vec<H256>, EthApiError<P::Error>>
+    where
+        P: Provider + Send + Sync,
+        C: KakarotStarknetApi<P> + ?Sized,
+    {
+        let Ok(transactions) = self.ethereum_transactions() else {
+            return Ok(vec![self.ethereum_transaction_hash(client)?]);
+        };
+        Ok(transactions.iter().map(TransactionSigned::hash).collect())
     }
 }
 
@@ -182,4 +278,23 @@ mod tests {
             serde_json::from_str(include_str!("test_data/conversion/eth/transaction.json")).unwrap();
         assert_eq!(expected, eth_transaction);
     }
+
+    #[test]
+    fn test_fee_fields() {
+        // Given
+        let legacy = EthereumTransaction::Legacy(TxLegacy { gas_price: 10, ..Default::default() });
+        let eip1559 = |max_fee_per_gas, max_priority_fee_per_gas| {
+            EthereumTransaction::Eip1559(TxEip1559 { max_fee_per_gas, max_priority_fee_per_gas, ..Default::default() })
+        };
+
+        // When
+        let legacy_fees = fee_fields(&legacy, 5);
+        let tipped_fees = fee_fields(&eip1559(10, 3), 5);
+        let capped_fees = fee_fields(&eip1559(6, 3), 5);
+
+        // Then
+        assert_eq!((U128::from(10), None, None), legacy_fees);
+        assert_eq!((U128::from(8), Some(U128::from(10)), Some(U128::from(3))), tipped_fees);
+        assert_eq!((U128::from(6), Some(U128::from(6)), Some(U128::from(3))), capped_fees);
+    }
 }
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use reth_primitives::{Address, BlockId, BlockNumberOrTag};
+use starknet::providers::Provider;
+
+use super::{PooledTransaction, TransactionPool};
+use crate::client::api::KakarotEthApi;
+use crate::client::constants::TXPOOL_POLL_INTERVAL;
+use crate::client::errors::EthApiError;
+
+/// Follows the nonces of the senders of the pooled transactions, evicting the stale transactions
+/// and submitting the queued transactions once their predecessors are accepted.
+pub struct TransactionPoolMaintainer<P: Provider + Send + Sync> {
+    kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    pool: Arc<TransactionPool>,
+}
+
+impl<P: Provider + Send + Sync + 'static> TransactionPoolMaintainer<P> {
+    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>, pool: Arc<TransactionPool>) -> Self {
+        Self { kakarot_client, pool }
+    }
+
+    /// Spawns the maintainer on the Tokio runtime.
+    pub fn spawn(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TXPOOL_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                for sender in self.pool.senders() {
+                    if let Err(err) = self.maintain(sender).await {
+                        log::error!("Failed to maintain the pooled transactions of {sender:?}: {err}");
+                    }
+                }
+            }
+        })
+    }
+
+    /// Prunes the transactions of the sender against its latest nonce, and submits its queued
+    /// transaction with that nonce. A promoted transaction which fails to be submitted is queued
+    /// again, until it is submitted or evicted.
+    pub async fn maintain(&self, sender: Address) -> Result<(), EthApiError<P::Error>> {
+        let nonce = self.kakarot_client.nonce(sender, BlockId::Number(BlockNumberOrTag::Latest)).await?;
+        let nonce =
+            u64::try_from(nonce).map_err(|_| EthApiError::ConversionError(format!("nonce {nonce} overflows")))?;
+
+        self.pool.prune(sender, nonce);
+        let Some(promoted) = self.pool.promote(sender, nonce) else { return Ok(()) };
+
+        let hash = promoted.transaction.hash();
+        if let Err(err) = self.kakarot_client.send_transaction(promoted.raw.clone()).await {
+            log::warn!("Failed to submit the pooled transaction {hash:?}, queuing it again: {err}");
+            if let Err(err) = self.pool.add(PooledTransaction { submitted: false, ..promoted }) {
+                log::error!("Dropping the pooled transaction {hash:?}: {err}");
+            }
+        }
+        Ok(())
+    }
+}
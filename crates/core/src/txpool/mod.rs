@@ -0,0 +1,442 @@
+pub mod maintainer;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reth_primitives::{Address, Bytes, Transaction, TransactionKind, TransactionSigned, TxEip1559, H256, U64};
+use reth_rpc_types::Transaction as EtherTransaction;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::client::constants::gas::BASE_FEE_PER_GAS;
+use crate::client::errors::ConfigError;
+use crate::models::transaction::rpc_transaction;
+
+/// Default duration after which a pooled transaction is evicted.
+pub const DEFAULT_LIFETIME: Duration = Duration::from_secs(3 * 60 * 60);
+/// Default maximum number of pooled transactions per sender.
+pub const DEFAULT_ACCOUNT_SLOTS: usize = 64;
+/// Minimum increase, in percent, of the fees of a transaction replacing a pooled transaction.
+pub const PRICE_BUMP_PERCENT: u128 = 10;
+
+/// Configuration of the transaction pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionPoolConfig {
+    /// Duration after which a pooled transaction is evicted.
+    pub lifetime: Duration,
+    /// Maximum number of pooled transactions per sender.
+    pub account_slots: usize,
+}
+
+impl Default for TransactionPoolConfig {
+    fn default() -> Self {
+        Self { lifetime: DEFAULT_LIFETIME, account_slots: DEFAULT_ACCOUNT_SLOTS }
+    }
+}
+
+impl TransactionPoolConfig {
+    /// Create a new `TransactionPoolConfig` from environment variables, falling back to the
+    /// defaults for the unset ones.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let mut config = Self::default();
+        if let Ok(lifetime) = std::env::var("KAKAROT_TXPOOL_LIFETIME") {
+            let seconds = lifetime.parse().map_err(|_| {
+                ConfigError::EnvironmentVariableSetWrong(format!(
+                    "KAKAROT_TXPOOL_LIFETIME should be a number of seconds, got {lifetime}"
+                ))
+            })?;
+            config.lifetime = Duration::from_secs(seconds);
+        }
+        if let Ok(account_slots) = std::env::var("KAKAROT_TXPOOL_ACCOUNT_SLOTS") {
+            config.account_slots = account_slots.parse().map_err(|_| {
+                ConfigError::EnvironmentVariableSetWrong(format!(
+                    "KAKAROT_TXPOOL_ACCOUNT_SLOTS should be a number of transactions, got {account_slots}"
+                ))
+            })?;
+        }
+        Ok(config)
+    }
+}
+
+/// Reasons for which a transaction is not added to the pool, with the error messages of geth.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PoolError {
+    #[error("already known")]
+    AlreadyKnown,
+    #[error("replacement transaction underpriced")]
+    ReplacementUnderpriced,
+    #[error("a transaction with the same nonce was already submitted")]
+    NonceAlreadySubmitted,
+    #[error("txpool is full")]
+    AccountFull,
+}
+
+/// A transaction held by the pool.
+#[derive(Debug, Clone)]
+pub struct PooledTransaction {
+    pub transaction: TransactionSigned,
+    pub sender: Address,
+    /// Raw bytes of the transaction, submitted once the transaction is promoted.
+    pub raw: Bytes,
+    /// Whether the transaction was submitted to Starknet. Transactions which were not are queued
+    /// until the preceding nonces of their sender are accepted.
+    pub submitted: bool,
+    added_at: Instant,
+}
+
+impl PooledTransaction {
+    pub fn new(transaction: TransactionSigned, sender: Address, raw: Bytes, submitted: bool) -> Self {
+        Self { transaction, sender, raw, submitted, added_at: Instant::now() }
+    }
+
+    fn nonce(&self) -> u64 {
+        self.transaction.nonce()
+    }
+}
+
+/// Transactions of the pool by sender and nonce, as returned by `txpool_content`. Pending
+/// transactions were submitted to Starknet, queued transactions wait for their predecessors.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TxpoolContent {
+    pub pending: BTreeMap<Address, BTreeMap<String, EtherTransaction>>,
+    pub queued: BTreeMap<Address, BTreeMap<String, EtherTransaction>>,
+}
+
+/// Summaries of the transactions of the pool by sender and nonce, as returned by
+/// `txpool_inspect`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolInspect {
+    pub pending: BTreeMap<Address, BTreeMap<String, String>>,
+    pub queued: BTreeMap<Address, BTreeMap<String, String>>,
+}
+
+/// Number of pending and queued transactions of the pool, as returned by `txpool_status`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxpoolStatus {
+    pub pending: U64,
+    pub queued: U64,
+}
+
+/// In-process pool of the raw transactions received by the RPC, keyed by sender and ordered by
+/// nonce.
+///
+/// Starknet rejects the transactions whose nonce is ahead of the nonce of their account, so such
+/// transactions are queued until their predecessors are accepted, and then promoted by the
+/// [`maintainer::TransactionPoolMaintainer`].
+pub struct TransactionPool {
+    config: TransactionPoolConfig,
+    transactions: Mutex<PooledTransactions>,
+}
+
+type PooledTransactions = HashMap<Address, BTreeMap<u64, PooledTransaction>>;
+
+impl TransactionPool {
+    pub fn new(config: TransactionPoolConfig) -> Self {
+        Self { config, transactions: Mutex::default() }
+    }
+
+    /// Adds the transaction to the pool. A transaction of the sender with the same nonce is
+    /// replaced if it was not submitted yet and the new transaction bumps both its fee cap and its
+    /// tip by at least [`PRICE_BUMP_PERCENT`].
+    pub fn add(&self, transaction: PooledTransaction) -> Result<(), PoolError> {
+        let mut transactions = self.transactions.lock().expect("txpool lock poisoned");
+        let sender_transactions = transactions.entry(transaction.sender).or_default();
+
+        match sender_transactions.get(&transaction.nonce()) {
+            Some(pooled) if pooled.transaction.hash() == transaction.transaction.hash() => {
+                return Err(PoolError::AlreadyKnown);
+            }
+            Some(pooled) if pooled.submitted => return Err(PoolError::NonceAlreadySubmitted),
+            Some(pooled) if !is_fee_bump(&pooled.transaction.transaction, &transaction.transaction.transaction) => {
+                return Err(PoolError::ReplacementUnderpriced);
+            }
+            None if sender_transactions.len() >= self.config.account_slots => return Err(PoolError::AccountFull),
+            _ => (),
+        }
+
+        sender_transactions.insert(transaction.nonce(), transaction);
+        Ok(())
+    }
+
+    /// Removes the transaction of the sender with the given nonce.
+    pub fn remove(&self, sender: Address, nonce: u64) -> Option<PooledTransaction> {
+        let mut transactions = self.transactions.lock().expect("txpool lock poisoned");
+        remove(&mut transactions, sender, nonce)
+    }
+
+    /// Evicts the transaction with the given hash, which Starknet rejected or dropped, so that its
+    /// nonce can be used again.
+    pub fn evict(&self, hash: H256) -> Option<PooledTransaction> {
+        let mut transactions = self.transactions.lock().expect("txpool lock poisoned");
+        let (sender, nonce) = transactions.iter().find_map(|(sender, sender_transactions)| {
+            sender_transactions
+                .iter()
+                .find(|(_, pooled)| pooled.transaction.hash() == hash)
+                .map(|(nonce, _)| (*sender, *nonce))
+        })?;
+        remove(&mut transactions, sender, nonce)
+    }
+
+    /// Evicts the transactions of the sender below its account nonce, which were accepted or
+    /// can no longer be, and the transactions older than the lifetime of the pool.
+    pub fn prune(&self, sender: Address, nonce: u64) {
+        let mut transactions = self.transactions.lock().expect("txpool lock poisoned");
+        let Some(sender_transactions) = transactions.get_mut(&sender) else { return };
+        sender_transactions
+            .retain(|pooled_nonce, pooled| *pooled_nonce >= nonce && pooled.added_at.elapsed() < self.config.lifetime);
+        if sender_transactions.is_empty() {
+            transactions.remove(&sender);
+        }
+    }
+
+    /// Removes and returns the queued transaction of the sender whose nonce is the account nonce,
+    /// which can now be submitted.
+    pub fn promote(&self, sender: Address, nonce: u64) -> Option<PooledTransaction> {
+        let mut transactions = self.transactions.lock().expect("txpool lock poisoned");
+        if transactions.get(&sender)?.get(&nonce)?.submitted {
+            return None;
+        }
+        remove(&mut transactions, sender, nonce)
+    }
+
+    /// Returns the nonce following the pooled transactions of the sender which directly succeed
+    /// the account nonce.
+    pub fn pending_nonce(&self, sender: Address, nonce: u64) -> u64 {
+        let transactions = self.transactions.lock().expect("txpool lock poisoned");
+        let Some(sender_transactions) = transactions.get(&sender) else { return nonce };
+        let mut next = nonce;
+        while sender_transactions.contains_key(&next) {
+            next += 1;
+        }
+        next
+    }
+
+    /// Returns the senders of the pooled transactions.
+    pub fn senders(&self) -> Vec<Address> {
+        self.transactions.lock().expect("txpool lock poisoned").keys().copied().collect()
+    }
+
+    pub fn content(&self) -> TxpoolContent {
+        let base_fee_per_gas = u128::from(BASE_FEE_PER_GAS);
+        let (pending, queued) =
+            self.group(|pooled| rpc_transaction(&pooled.transaction, pooled.sender, base_fee_per_gas));
+        TxpoolContent { pending, queued }
+    }
+
+    pub fn inspect(&self) -> TxpoolInspect {
+        let (pending, queued) = self.group(|pooled| summary(&pooled.transaction.transaction));
+        TxpoolInspect { pending, queued }
+    }
+
+    pub fn status(&self) -> TxpoolStatus {
+        let transactions = self.transactions.lock().expect("txpool lock poisoned");
+        let pending = transactions.values().flat_map(BTreeMap::values).filter(|pooled| pooled.submitted).count();
+        let queued = transactions.values().map(BTreeMap::len).sum::<usize>() - pending;
+        TxpoolStatus { pending: U64::from(pending), queued: U64::from(queued) }
+    }
+
+    /// Maps the pending and the queued transactions by sender and nonce.
+    #[allow(clippy::type_complexity)]
+    fn group<T>(
+        &self,
+        map: impl Fn(&PooledTransaction) -> T,
+    ) -> (BTreeMap<Address, BTreeMap<String, T>>, BTreeMap<Address, BTreeMap<String, T>>) {
+        let transactions = self.transactions.lock().expect("txpool lock poisoned");
+        let mut pending: BTreeMap<Address, BTreeMap<String, T>> = BTreeMap::new();
+        let mut queued: BTreeMap<Address, BTreeMap<String, T>> = BTreeMap::new();
+        for (sender, sender_transactions) in transactions.iter() {
+            for (nonce, pooled) in sender_transactions {
+                let group = if pooled.submitted { &mut pending } else { &mut queued };
+                group.entry(*sender).or_default().insert(nonce.to_string(), map(pooled));
+            }
+        }
+        (pending, queued)
+    }
+}
+
+/// Removes the transaction of the sender with the given nonce, and the sender once it has no
+/// pooled transaction left.
+fn remove(transactions: &mut PooledTransactions, sender: Address, nonce: u64) -> Option<PooledTransaction> {
+    let sender_transactions = transactions.get_mut(&sender)?;
+    let removed = sender_transactions.remove(&nonce);
+    if sender_transactions.is_empty() {
+        transactions.remove(&sender);
+    }
+    removed
+}
+
+/// Returns the fee cap and the tip of the transaction. Both are the gas price of the transactions
+/// which precede EIP-1559.
+fn fees(transaction: &Transaction) -> (u128, u128) {
+    match transaction {
+        Transaction::Legacy(tx) => (tx.gas_price, tx.gas_price),
+        Transaction::Eip2930(tx) => (tx.gas_price, tx.gas_price),
+        Transaction::Eip1559(TxEip1559 { max_fee_per_gas, max_priority_fee_per_gas, .. }) => {
+            (*max_fee_per_gas, *max_priority_fee_per_gas)
+        }
+    }
+}
+
+/// Checks that the replacement bumps the fee cap and the tip of the pooled transaction by at least
+/// [`PRICE_BUMP_PERCENT`].
+fn is_fee_bump(pooled: &Transaction, replacement: &Transaction) -> bool {
+    let bumped = |fee: u128| fee.saturating_mul(100 + PRICE_BUMP_PERCENT) / 100;
+    let (pooled_fee_cap, pooled_tip) = fees(pooled);
+    let (fee_cap, tip) = fees(replacement);
+    fee_cap >= bumped(pooled_fee_cap) && tip >= bumped(pooled_tip)
+}
+
+/// Summarizes the transaction the way geth does in `txpool_inspect`.
+fn summary(transaction: &Transaction) -> String {
+    let to = match transaction.kind() {
+        TransactionKind::Call(to) => format!("{to:?}"),
+        TransactionKind::Create => "contract creation".to_string(),
+    };
+    let (fee_cap, _) = fees(transaction);
+    format!("{to}: {} wei + {} gas × {fee_cap} wei", transaction.value(), transaction.gas_limit())
+}
+
+#[cfg(test)]
+mod tests {
+    use reth_primitives::{Signature, TxLegacy};
+
+    use super::*;
+
+    fn pooled(nonce: u64, max_fee_per_gas: u128, submitted: bool) -> PooledTransaction {
+        let transaction = Transaction::Eip1559(TxEip1559 {
+            nonce,
+            max_fee_per_gas,
+            max_priority_fee_per_gas: max_fee_per_gas,
+            gas_limit: 21_000,
+            to: TransactionKind::Call(Address::from_low_u64_be(1)),
+            ..Default::default()
+        });
+        let transaction = TransactionSigned::from_transaction_and_signature(transaction, Signature::default());
+        PooledTransaction::new(transaction, sender(), Bytes::default(), submitted)
+    }
+
+    fn sender() -> Address {
+        Address::from_low_u64_be(0xabde1)
+    }
+
+    #[test]
+    fn test_pending_nonce() {
+        // Given
+        let pool = TransactionPool::new(TransactionPoolConfig::default());
+        pool.add(pooled(3, 10, true)).unwrap();
+        pool.add(pooled(4, 10, false)).unwrap();
+        pool.add(pooled(6, 10, false)).unwrap();
+
+        // When
+        let pending_nonce = pool.pending_nonce(sender(), 3);
+
+        // Then
+        assert_eq!(5, pending_nonce);
+        assert_eq!(2, pool.pending_nonce(sender(), 2));
+        assert_eq!(0, pool.pending_nonce(Address::zero(), 0));
+        assert_eq!(TxpoolStatus { pending: U64::from(1), queued: U64::from(2) }, pool.status());
+    }
+
+    #[test]
+    fn test_replacement() {
+        // Given
+        let pool = TransactionPool::new(TransactionPoolConfig::default());
+        pool.add(pooled(3, 100, true)).unwrap();
+        pool.add(pooled(4, 100, false)).unwrap();
+
+        // When
+        let known = pool.add(pooled(4, 100, false));
+        let underpriced = pool.add(pooled(4, 109, false));
+        let submitted = pool.add(pooled(3, 200, false));
+        let replaced = pool.add(pooled(4, 110, false));
+
+        // Then
+        assert_eq!(Err(PoolError::AlreadyKnown), known);
+        assert_eq!(Err(PoolError::ReplacementUnderpriced), underpriced);
+        assert_eq!(Err(PoolError::NonceAlreadySubmitted), submitted);
+        assert_eq!(Ok(()), replaced);
+        assert_eq!(pooled(4, 110, false).transaction.hash(), pool.promote(sender(), 4).unwrap().transaction.hash());
+    }
+
+    #[test]
+    fn test_account_slots() {
+        // Given
+        let pool = TransactionPool::new(TransactionPoolConfig { account_slots: 1, ..Default::default() });
+        pool.add(pooled(3, 10, false)).unwrap();
+
+        // When
+        let full = pool.add(pooled(4, 10, false));
+
+        // Then
+        assert_eq!(Err(PoolError::AccountFull), full);
+    }
+
+    #[test]
+    fn test_prune_and_promote() {
+        // Given
+        let pool = TransactionPool::new(TransactionPoolConfig::default());
+        pool.add(pooled(3, 10, true)).unwrap();
+        pool.add(pooled(4, 10, false)).unwrap();
+        pool.add(pooled(5, 10, false)).unwrap();
+
+        // When
+        pool.prune(sender(), 4);
+        let promoted = pool.promote(sender(), 4);
+
+        // Then
+        assert_eq!(Some(4), promoted.map(|pooled| pooled.nonce()));
+        assert_eq!(None, pool.promote(sender(), 4));
+        assert_eq!(TxpoolStatus { pending: U64::from(0), queued: U64::from(1) }, pool.status());
+    }
+
+    #[test]
+    fn test_evict() {
+        // Given
+        let pool = TransactionPool::new(TransactionPoolConfig::default());
+        pool.add(pooled(3, 10, true)).unwrap();
+
+        // When
+        let evicted = pool.evict(pooled(3, 10, true).transaction.hash());
+
+        // Then
+        assert_eq!(Some(3), evicted.map(|pooled| pooled.nonce()));
+        assert!(pool.senders().is_empty());
+        assert_eq!(Ok(()), pool.add(pooled(3, 10, true)));
+    }
+
+    #[test]
+    fn test_prune_expired() {
+        // Given
+        let pool = TransactionPool::new(TransactionPoolConfig { lifetime: Duration::ZERO, ..Default::default() });
+        pool.add(pooled(4, 10, false)).unwrap();
+
+        // When
+        pool.prune(sender(), 3);
+
+        // Then
+        assert!(pool.senders().is_empty());
+    }
+
+    #[test]
+    fn test_inspect() {
+        // Given
+        let pool = TransactionPool::new(TransactionPoolConfig::default());
+        pool.add(pooled(3, 10, true)).unwrap();
+        let creation = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy { nonce: 5, gas_price: 7, gas_limit: 53_000, value: 1, ..Default::default() }),
+            Signature::default(),
+        );
+        pool.add(PooledTransaction::new(creation, sender(), Bytes::default(), false)).unwrap();
+
+        // When
+        let inspect = pool.inspect();
+
+        // Then
+        assert_eq!(
+            "0x0000000000000000000000000000000000000001: 0 wei + 21000 gas × 10 wei",
+            inspect.pending[&sender()]["3"]
+        );
+        assert_eq!("contract creation: 1 wei + 53000 gas × 7 wei", inspect.queued[&sender()]["5"]);
+    }
+}
@@ -1,12 +1,15 @@
 use jsonrpsee::core::RpcResult as Result;
 use jsonrpsee::proc_macros::rpc;
+use kakarot_rpc_core::models::receipt::KakarotTransactionReceipt;
 use reth_primitives::rpc::transaction::eip2930::AccessListWithGasUsed;
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, H256, H64, U128, U256, U64};
 use reth_rpc_types::{
-    CallRequest, EIP1186AccountProofResponse, FeeHistory, Index, RichBlock, SyncStatus, Transaction as EthTransaction,
-    TransactionReceipt, TransactionRequest, Work,
+    CallRequest, EIP1186AccountProofResponse, FeeHistory, Filter, Index, Log, RichBlock, SyncStatus,
+    Transaction as EthTransaction, TransactionRequest, Work,
 };
 
+use crate::filters::FilterChanges;
+
 #[rpc(server, namespace = "eth")]
 #[async_trait]
 pub trait EthApi {
@@ -81,9 +84,39 @@ pub trait EthApi {
         index: Index,
     ) -> Result<Option<EthTransaction>>;
 
-    /// Returns the receipt of a transaction by transaction hash.
+    /// Returns the receipt of a transaction by transaction hash, extended with the Starknet status
+    /// of the transaction.
     #[method(name = "getTransactionReceipt")]
-    async fn transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>>;
+    async fn transaction_receipt(&self, hash: H256) -> Result<Option<KakarotTransactionReceipt>>;
+
+    /// Returns logs matching given filter object.
+    #[method(name = "getLogs")]
+    async fn get_logs(&self, filter: Filter) -> Result<Vec<Log>>;
+
+    /// Creates a filter object, based on filter options, to notify when the state changes (logs).
+    #[method(name = "newFilter")]
+    async fn new_filter(&self, filter: Filter) -> Result<U256>;
+
+    /// Creates a filter in the node, to notify when a new block arrives.
+    #[method(name = "newBlockFilter")]
+    async fn new_block_filter(&self) -> Result<U256>;
+
+    /// Creates a filter in the node, to notify when new pending transactions arrive.
+    #[method(name = "newPendingTransactionFilter")]
+    async fn new_pending_transaction_filter(&self) -> Result<U256>;
+
+    /// Polling method for a filter, which returns an array of logs or hashes which occurred since
+    /// last poll.
+    #[method(name = "getFilterChanges")]
+    async fn filter_changes(&self, id: U256) -> Result<FilterChanges>;
+
+    /// Returns an array of all logs matching filter with given id.
+    #[method(name = "getFilterLogs")]
+    async fn filter_logs(&self, id: U256) -> Result<Vec<Log>>;
+
+    /// Uninstalls a filter with given id.
+    #[method(name = "uninstallFilter")]
+    async fn uninstall_filter(&self, id: U256) -> Result<bool>;
 
     /// Returns the balance of the account of given address.
     #[method(name = "getBalance")]
@@ -184,6 +217,16 @@ pub trait EthApi {
     #[method(name = "sendRawTransaction")]
     async fn send_raw_transaction(&self, bytes: Bytes) -> Result<H256>;
 
+    /// Sends signed transaction and waits for its receipt, or for its pending receipt when
+    /// `pending` is set. The timeout, in milliseconds, is capped by the timeout of the server.
+    #[method(name = "sendRawTransactionSync")]
+    async fn send_raw_transaction_sync(
+        &self,
+        bytes: Bytes,
+        timeout: Option<u64>,
+        pending: Option<bool>,
+    ) -> Result<KakarotTransactionReceipt>;
+
     /// Returns an Ethereum specific signature with: sign(keccak256("\x19Ethereum Signed Message:\n"
     /// + len(message) + message))).
     #[method(name = "sign")]
@@ -0,0 +1,15 @@
+use jsonrpsee::proc_macros::rpc;
+use reth_rpc_types::pubsub::{Params, SubscriptionKind, SubscriptionResult};
+
+#[rpc(server, namespace = "eth")]
+#[async_trait]
+pub trait EthPubSubApi {
+    /// Creates a subscription to the given kind of events: `newHeads`, `logs` (with an optional
+    /// filter) or `newPendingTransactions`.
+    #[subscription(
+        name = "subscribe" => "subscription",
+        unsubscribe = "unsubscribe",
+        item = SubscriptionResult
+    )]
+    async fn subscribe(&self, kind: SubscriptionKind, params: Option<Params>) -> jsonrpsee::core::SubscriptionResult;
+}
@@ -0,0 +1,28 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use kakarot_rpc_core::client::cache::StarknetCacheStats;
+use kakarot_rpc_core::client::submission_tracker::TransactionLifecycle;
+use reth_primitives::{Address, Bytes, H256};
+
+#[rpc(server, namespace = "kakarot")]
+#[async_trait]
+pub trait KakarotApi {
+    /// Returns the lifecycle of the Starknet submission of a transaction, including the reason of
+    /// its rejection by Starknet.
+    #[method(name = "getTransactionStatus")]
+    async fn get_transaction_status(&self, hash: H256) -> Result<Option<TransactionLifecycle>>;
+
+    /// Sends signed transactions of the same sender with the same nonce in a single Starknet
+    /// multicall, executing them atomically and in order. Returns the hashes of the transactions.
+    #[method(name = "sendRawTransactionBatch")]
+    async fn send_raw_transaction_batch(&self, transactions: Vec<Bytes>) -> Result<Vec<H256>>;
+
+    /// Returns the hit and miss counts of the caches of the Starknet lookups.
+    #[method(name = "getCacheStats")]
+    async fn get_cache_stats(&self) -> Result<StarknetCacheStats>;
+
+    /// Returns the hashes of the transactions sent from or to an address, from the oldest to the
+    /// most recent, as recorded by the local index. Returns `null` when the index is disabled.
+    #[method(name = "getTransactionsByAddress")]
+    async fn get_transactions_by_address(&self, address: Address) -> Result<Option<Vec<H256>>>;
+}
@@ -1,4 +1,7 @@
 pub mod alchemy_api;
 pub mod eth_api;
+pub mod eth_pubsub_api;
+pub mod kakarot_api;
 pub mod net_api;
+pub mod txpool_api;
 pub mod web3_api;
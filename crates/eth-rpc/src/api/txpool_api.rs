@@ -0,0 +1,20 @@
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+use kakarot_rpc_core::txpool::{TxpoolContent, TxpoolInspect, TxpoolStatus};
+
+#[rpc(server, namespace = "txpool")]
+#[async_trait]
+pub trait TxPoolApi {
+    /// Returns the pending and queued transactions of the pool, by sender and nonce.
+    #[method(name = "content")]
+    fn content(&self) -> Result<TxpoolContent>;
+
+    /// Returns the number of pending and queued transactions of the pool.
+    #[method(name = "status")]
+    fn status(&self) -> Result<TxpoolStatus>;
+
+    /// Returns a textual summary of the pending and queued transactions of the pool, by sender and
+    /// nonce.
+    #[method(name = "inspect")]
+    fn inspect(&self) -> Result<TxpoolInspect>;
+}
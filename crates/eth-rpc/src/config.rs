@@ -1,17 +1,51 @@
+use std::time::Duration;
+
 use eyre::{eyre, Result};
 
+use crate::filters::DEFAULT_FILTER_TIMEOUT;
+use crate::servers::eth_rpc::DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT;
+
 pub struct RPCConfig {
     pub socket_addr: String,
+    /// Address of the WebSocket server, serving the subscriptions. WebSocket is disabled if
+    /// unset.
+    pub ws_socket_addr: Option<String>,
+    /// Duration after which a filter which has not been polled is uninstalled.
+    pub filter_timeout: Duration,
+    /// Maximum duration `eth_sendRawTransactionSync` waits for the receipt of a transaction.
+    pub sync_timeout: Duration,
 }
 
 impl RPCConfig {
     pub fn new(socket_addr: String) -> RPCConfig {
-        RPCConfig { socket_addr }
+        RPCConfig {
+            socket_addr,
+            ws_socket_addr: None,
+            filter_timeout: DEFAULT_FILTER_TIMEOUT,
+            sync_timeout: DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT,
+        }
     }
 
     pub fn from_env() -> Result<Self> {
         let socket_addr = std::env::var("KAKAROT_HTTP_RPC_ADDRESS")
             .map_err(|_| eyre!("Missing mandatory environment variable: KAKAROT_HTTP_RPC_ADDRESS"))?;
-        Ok(RPCConfig::new(socket_addr))
+        let mut config = RPCConfig::new(socket_addr);
+        config.ws_socket_addr = std::env::var("KAKAROT_WS_RPC_ADDRESS").ok();
+
+        if let Ok(filter_timeout) = std::env::var("KAKAROT_FILTER_TIMEOUT") {
+            let seconds = filter_timeout
+                .parse::<u64>()
+                .map_err(|_| eyre!("Invalid KAKAROT_FILTER_TIMEOUT: expected a number of seconds"))?;
+            config.filter_timeout = Duration::from_secs(seconds);
+        }
+
+        if let Ok(sync_timeout) = std::env::var("KAKAROT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT") {
+            let millis = sync_timeout.parse::<u64>().map_err(|_| {
+                eyre!("Invalid KAKAROT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT: expected a number of milliseconds")
+            })?;
+            config.sync_timeout = Duration::from_millis(millis);
+        }
+
+        Ok(config)
     }
 }
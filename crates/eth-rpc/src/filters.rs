@@ -0,0 +1,324 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::types::ErrorObject;
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::errors::{rpc_err, EthRpcErrorCode};
+use kakarot_rpc_core::client::head_tracker::{pending_transaction_hashes, BlockRef, ChainEvent, HeadTrackerHandle};
+use kakarot_rpc_core::models::filter::EthFilter;
+use reth_primitives::{BlockNumberOrTag, H256, U256};
+use reth_rpc_types::{Filter, FilterBlockOption, Log};
+use serde::Serialize;
+use starknet::core::types::BlockId as StarknetBlockId;
+use starknet::providers::Provider;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::TryRecvError;
+
+/// Default duration after which a filter which has not been polled is uninstalled.
+pub const DEFAULT_FILTER_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Maximum number of blocks covered by a poll of a log or block filter. The following blocks are
+/// covered by the next polls.
+pub const MAX_FILTER_CHANGES_BLOCKS: u64 = 100;
+
+/// The changes returned by `eth_getFilterChanges`, depending on the kind of the filter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum FilterChanges {
+    Logs(Vec<Log>),
+    Hashes(Vec<H256>),
+}
+
+/// The kind of an installed filter, along with its polling cursor.
+#[derive(Debug, Clone)]
+enum FilterKind {
+    /// Log filter, with the last Starknet block included in a poll and the logs of reorged blocks
+    /// to return, flagged as removed, at the next poll.
+    Logs { filter: Filter, last_block: u64, removed_logs: Vec<Log> },
+    /// Block filter, with the last Starknet block included in a poll.
+    Blocks { last_block: u64 },
+    /// Pending transaction filter, with the hashes of the Kakarot transactions which were in the
+    /// pending block at the last poll.
+    PendingTransactions { seen: HashSet<H256> },
+}
+
+#[derive(Debug)]
+struct ActiveFilter {
+    kind: FilterKind,
+    last_poll: Instant,
+}
+
+/// Blocks claimed by a poll of a log or block filter, along with the removed logs to return.
+struct ClaimedBlocks {
+    filter: Option<Filter>,
+    from: u64,
+    to: u64,
+    removed_logs: Vec<Log>,
+}
+
+/// Registry of the filters installed through the `eth_new*Filter` methods.
+///
+/// Filters which are not polled for longer than the configured timeout are uninstalled. When the
+/// chain events of the head tracker are followed, the reorgs rewind the log and block filters to
+/// the first dropped block, and the log filters return the logs of the dropped blocks they already
+/// returned, flagged as removed.
+pub struct EthFilters<P: Provider + Send + Sync> {
+    kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    filters: Mutex<HashMap<U256, ActiveFilter>>,
+    next_id: AtomicU64,
+    timeout: Duration,
+    /// Chain events received since the last poll or installation of a filter.
+    chain_events: Option<Mutex<broadcast::Receiver<ChainEvent>>>,
+}
+
+impl<P: Provider + Send + Sync> EthFilters<P> {
+    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>, timeout: Duration) -> Self {
+        Self {
+            kakarot_client,
+            filters: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            timeout,
+            chain_events: None,
+        }
+    }
+
+    /// Follows the chain events of the head tracker, to report the reorgs to the filters.
+    #[must_use]
+    pub fn with_head_tracker(mut self, head_tracker: &HeadTrackerHandle) -> Self {
+        self.chain_events = Some(Mutex::new(head_tracker.subscribe()));
+        self
+    }
+
+    /// Installs a log filter, returning logs emitted after the current block.
+    pub async fn new_filter(&self, filter: Filter) -> Result<U256> {
+        if let FilterBlockOption::AtBlockHash(_) = filter.block_option {
+            return Err(rpc_err(EthRpcErrorCode::InvalidInput as i32, "blockHash is not supported for log filters"));
+        }
+        let last_block = self.kakarot_client.block_number().await?.as_u64();
+        Ok(self.install(FilterKind::Logs { filter, last_block, removed_logs: vec![] }))
+    }
+
+    /// Installs a block filter, returning blocks created after the current block.
+    pub async fn new_block_filter(&self) -> Result<U256> {
+        let last_block = self.kakarot_client.block_number().await?.as_u64();
+        Ok(self.install(FilterKind::Blocks { last_block }))
+    }
+
+    /// Installs a pending transaction filter. Transactions already in the pending block are
+    /// considered as seen.
+    pub async fn new_pending_transaction_filter(&self) -> Result<U256> {
+        let seen = pending_transaction_hashes(&self.kakarot_client).await?;
+        Ok(self.install(FilterKind::PendingTransactions { seen }))
+    }
+
+    /// Uninstalls a filter, returning true if the filter was installed.
+    pub fn uninstall_filter(&self, id: U256) -> bool {
+        self.filters.lock().expect("filters lock poisoned").remove(&id).is_some()
+    }
+
+    /// Returns the changes since the last poll of the filter, covering at most
+    /// `MAX_FILTER_CHANGES_BLOCKS` blocks. The blocks are claimed before being fetched, so that
+    /// concurrent polls of the same filter don't return the same changes.
+    pub async fn filter_changes(&self, id: U256) -> Result<FilterChanges> {
+        self.apply_chain_events();
+        if let FilterKind::PendingTransactions { seen } = self.poll(id)? {
+            let pending = pending_transaction_hashes(&self.kakarot_client).await?;
+            let hashes = pending.iter().filter(|hash| !seen.contains(hash)).copied().collect();
+            self.update_cursor(id, |kind| {
+                if let FilterKind::PendingTransactions { seen } = kind {
+                    *seen = pending;
+                }
+            });
+            return Ok(FilterChanges::Hashes(hashes));
+        }
+
+        let latest = self.kakarot_client.block_number().await?.as_u64();
+        let claimed = self.claim_blocks(id, latest)?;
+        let changes = self.claimed_changes(&claimed).await;
+        if changes.is_err() {
+            self.release_blocks(id, claimed);
+        }
+        changes
+    }
+
+    async fn claimed_changes(&self, claimed: &ClaimedBlocks) -> Result<FilterChanges> {
+        match &claimed.filter {
+            Some(filter) => {
+                let mut logs = claimed.removed_logs.clone();
+                logs.extend(self.logs_in_range(filter.clone(), claimed.from, claimed.to).await?);
+                Ok(FilterChanges::Logs(logs))
+            }
+            None => {
+                let mut hashes = vec![];
+                for block_number in claimed.from..=claimed.to {
+                    let block = self
+                        .kakarot_client
+                        .get_eth_block_from_starknet_block(StarknetBlockId::Number(block_number), false)
+                        .await?;
+                    hashes.extend(block.inner.header.hash);
+                }
+                Ok(FilterChanges::Hashes(hashes))
+            }
+        }
+    }
+
+    /// Returns all the logs matching a log filter.
+    pub async fn filter_logs(&self, id: U256) -> Result<Vec<Log>> {
+        match self.poll(id)? {
+            FilterKind::Logs { filter, .. } => Ok(self.kakarot_client.get_logs(filter).await?),
+            _ => Err(filter_not_found()),
+        }
+    }
+
+    fn install(&self, kind: FilterKind) -> U256 {
+        // The pending events concern the blocks preceding the filter.
+        self.apply_chain_events();
+        let id = U256::from(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let mut filters = self.filters.lock().expect("filters lock poisoned");
+        self.evict_expired(&mut filters);
+        filters.insert(id, ActiveFilter { kind, last_poll: Instant::now() });
+        id
+    }
+
+    /// Marks the filter as polled and returns a snapshot of its cursor.
+    fn poll(&self, id: U256) -> Result<FilterKind> {
+        let mut filters = self.filters.lock().expect("filters lock poisoned");
+        self.evict_expired(&mut filters);
+        let filter = filters.get_mut(&id).ok_or_else(filter_not_found)?;
+        filter.last_poll = Instant::now();
+        Ok(filter.kind.clone())
+    }
+
+    /// Advances the cursor of a log or block filter over the blocks following it, up to `latest`,
+    /// and takes its removed logs.
+    fn claim_blocks(&self, id: U256, latest: u64) -> Result<ClaimedBlocks> {
+        let mut filters = self.filters.lock().expect("filters lock poisoned");
+        let filter = filters.get_mut(&id).ok_or_else(filter_not_found)?;
+        let (filter, last_block, removed_logs) = match &mut filter.kind {
+            FilterKind::Logs { filter, last_block, removed_logs } => {
+                (Some(filter.clone()), last_block, std::mem::take(removed_logs))
+            }
+            FilterKind::Blocks { last_block } => (None, last_block, vec![]),
+            FilterKind::PendingTransactions { .. } => return Err(filter_not_found()),
+        };
+        let from = *last_block + 1;
+        let to = latest.min(*last_block + MAX_FILTER_CHANGES_BLOCKS);
+        *last_block = to.max(*last_block);
+        Ok(ClaimedBlocks { filter, from, to, removed_logs })
+    }
+
+    /// Gives back the blocks and the removed logs of a failed poll, unless the cursor moved since.
+    fn release_blocks(&self, id: U256, claimed: ClaimedBlocks) {
+        self.update_cursor(id, |kind| match kind {
+            FilterKind::Logs { last_block, removed_logs, .. } if *last_block == claimed.to => {
+                *last_block = claimed.from - 1;
+                removed_logs.splice(0..0, claimed.removed_logs);
+            }
+            FilterKind::Blocks { last_block } if *last_block == claimed.to => *last_block = claimed.from - 1,
+            _ => (),
+        });
+    }
+
+    /// Applies the chain events received since the last call to the filters.
+    fn apply_chain_events(&self) {
+        let Some(chain_events) = &self.chain_events else { return };
+        let mut chain_events = chain_events.lock().expect("filters lock poisoned");
+        loop {
+            match chain_events.try_recv() {
+                Ok(ChainEvent::Reorg { dropped, removed_logs }) => self.apply_reorg(&dropped, &removed_logs),
+                Ok(_) => (),
+                Err(TryRecvError::Lagged(skipped)) => {
+                    log::warn!("Filters missed {skipped} chain events, some removed logs won't be returned")
+                }
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// Rewinds the log and block filters to the first dropped block, queuing the removed logs
+    /// which the log filters already returned.
+    fn apply_reorg(&self, dropped: &[BlockRef], removed_logs: &[Log]) {
+        let Some(first_dropped) = dropped.iter().map(|block| block.number).min() else { return };
+        let mut filters = self.filters.lock().expect("filters lock poisoned");
+        for filter in filters.values_mut() {
+            match &mut filter.kind {
+                FilterKind::Logs { filter, last_block, removed_logs: queued } => {
+                    let returned = U256::from(*last_block);
+                    let eth_filter = EthFilter::new(filter.clone());
+                    queued.extend(
+                        removed_logs
+                            .iter()
+                            .filter(|log| log.block_number.map_or(false, |number| number <= returned))
+                            .filter(|log| is_in_block_range(filter, log) && eth_filter.matches(log))
+                            .cloned(),
+                    );
+                    *last_block = (*last_block).min(first_dropped.saturating_sub(1));
+                }
+                FilterKind::Blocks { last_block } => *last_block = (*last_block).min(first_dropped.saturating_sub(1)),
+                FilterKind::PendingTransactions { .. } => (),
+            }
+        }
+    }
+
+    /// Updates the cursor of the filter, if it wasn't uninstalled in the meantime.
+    fn update_cursor(&self, id: U256, update: impl FnOnce(&mut FilterKind)) {
+        if let Some(filter) = self.filters.lock().expect("filters lock poisoned").get_mut(&id) {
+            update(&mut filter.kind);
+        }
+    }
+
+    fn evict_expired(&self, filters: &mut HashMap<U256, ActiveFilter>) {
+        filters.retain(|_, filter| filter.last_poll.elapsed() < self.timeout);
+    }
+
+    /// Returns the logs matching the filter between `from` and `to` (inclusive), intersected with
+    /// the block range of the filter.
+    async fn logs_in_range(&self, mut filter: Filter, from: u64, to: u64) -> Result<Vec<Log>> {
+        let (from, to) = match filter.block_option {
+            FilterBlockOption::Range { from_block, to_block } => {
+                let from = match from_block {
+                    Some(BlockNumberOrTag::Number(number)) => number.max(from),
+                    _ => from,
+                };
+                let to = match to_block {
+                    Some(BlockNumberOrTag::Number(number)) => number.min(to),
+                    _ => to,
+                };
+                (from, to)
+            }
+            FilterBlockOption::AtBlockHash(_) => return Ok(vec![]),
+        };
+        if from > to {
+            return Ok(vec![]);
+        }
+
+        filter.block_option = FilterBlockOption::Range {
+            from_block: Some(BlockNumberOrTag::Number(from)),
+            to_block: Some(BlockNumberOrTag::Number(to)),
+        };
+        Ok(self.kakarot_client.get_logs(filter).await?)
+    }
+}
+
+/// Returns true if the log was emitted within the numbered bounds of the block range of the
+/// filter.
+fn is_in_block_range(filter: &Filter, log: &Log) -> bool {
+    let FilterBlockOption::Range { from_block, to_block } = filter.block_option else { return false };
+    let Some(number) = log.block_number else { return false };
+    let after_from = match from_block {
+        Some(BlockNumberOrTag::Number(from)) => number >= U256::from(from),
+        _ => true,
+    };
+    let before_to = match to_block {
+        Some(BlockNumberOrTag::Number(to)) => number <= U256::from(to),
+        _ => true,
+    };
+    after_from && before_to
+}
+
+fn filter_not_found() -> ErrorObject<'static> {
+    rpc_err(EthRpcErrorCode::InvalidInput as i32, "filter not found")
+}
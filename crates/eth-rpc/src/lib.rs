@@ -5,6 +5,7 @@ use std::net::{AddrParseError, SocketAddr};
 use config::RPCConfig;
 pub mod api;
 pub mod config;
+pub mod filters;
 pub mod rpc;
 pub mod servers;
 
@@ -23,6 +24,8 @@ pub enum RpcError {
     ParseError(#[from] AddrParseError),
 }
 
+/// Runs the JSON-RPC server, over HTTP as well as WebSocket for the subscriptions.
+///
 /// # Errors
 ///
 /// Will return `Err` if an error occurs when running the `ServerBuilder` start fails.
@@ -30,7 +33,7 @@ pub async fn run_server(
     kakarot_rpc_module: RpcModule<()>,
     rpc_config: RPCConfig,
 ) -> Result<(SocketAddr, ServerHandle), RpcError> {
-    let RPCConfig { socket_addr } = rpc_config;
+    let RPCConfig { socket_addr, .. } = rpc_config;
 
     let cors = CorsLayer::new().allow_methods(Any).allow_origin(Any).allow_headers(Any);
 
@@ -44,3 +47,21 @@ pub async fn run_server(
 
     Ok((addr, handle))
 }
+
+/// Runs a WebSocket-only JSON-RPC server on its own address.
+///
+/// # Errors
+///
+/// Will return `Err` if an error occurs when running the `ServerBuilder` start fails.
+pub async fn run_ws_server(
+    kakarot_rpc_module: RpcModule<()>,
+    ws_socket_addr: &str,
+) -> Result<(SocketAddr, ServerHandle), RpcError> {
+    let server = ServerBuilder::default().ws_only().build(ws_socket_addr.parse::<SocketAddr>()?).await?;
+
+    let addr = server.local_addr()?;
+
+    let handle = server.start(kakarot_rpc_module)?;
+
+    Ok((addr, handle))
+}
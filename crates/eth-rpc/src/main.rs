@@ -4,13 +4,23 @@ use dotenv::dotenv;
 use eyre::Result;
 use kakarot_rpc::config::RPCConfig;
 use kakarot_rpc::rpc::KakarotRpcModuleBuilder;
-use kakarot_rpc::run_server;
+use kakarot_rpc::{run_server, run_ws_server};
+use kakarot_rpc_core::client::block_tags::{BlockTagResolver, SafeBlockPolicy};
+use kakarot_rpc_core::client::cache::StarknetCache;
 use kakarot_rpc_core::client::config::{
     JsonRpcClientBuilder, Network, SequencerGatewayProviderBuilder, StarknetConfig,
 };
+use kakarot_rpc_core::client::head_tracker::{HeadTracker, HeadTrackerHandle};
+use kakarot_rpc_core::client::submission_tracker::SubmissionTracker;
 use kakarot_rpc_core::client::KakarotClient;
+use kakarot_rpc_core::contracts::kakarot::KakarotContract;
+use kakarot_rpc_core::indexer::hashes::TransactionHashStore;
+use kakarot_rpc_core::indexer::store::IndexStore;
+use kakarot_rpc_core::indexer::{Indexer, IndexerConfig};
+use kakarot_rpc_core::txpool::maintainer::TransactionPoolMaintainer;
+use kakarot_rpc_core::txpool::{TransactionPool, TransactionPoolConfig};
 use starknet::providers::jsonrpc::HttpTransport;
-use starknet::providers::{JsonRpcClient, SequencerGatewayProvider};
+use starknet::providers::{JsonRpcClient, Provider, SequencerGatewayProvider};
 use tracing_subscriber::util::SubscriberInitExt;
 
 enum StarknetProvider {
@@ -30,29 +40,58 @@ async fn main() -> Result<()> {
 
     let rpc_config = RPCConfig::from_env()?;
 
-    let starknet_provider: StarknetProvider = match &starknet_config.network {
-        Network::Madara | Network::Katana | Network::Sharingan => {
-            StarknetProvider::JsonRpcClient(JsonRpcClientBuilder::with_http(&starknet_config).unwrap().build())
-        }
-        Network::JsonRpcProvider(url) => {
-            StarknetProvider::JsonRpcClient(JsonRpcClientBuilder::new(HttpTransport::new(url.clone())).build())
-        }
-        _ => StarknetProvider::SequencerGatewayProvider(
-            SequencerGatewayProviderBuilder::new(&starknet_config.network).build(),
-        ),
-    };
+    let indexer_config = IndexerConfig::from_env()?;
+    let index = indexer_config.as_ref().map(|config| IndexStore::open(&config.path)).transpose()?.map(Arc::new);
+    let transaction_hashes = Arc::new(TransactionHashStore::from_env()?);
+    let transaction_pool = Arc::new(TransactionPool::new(TransactionPoolConfig::from_env()?));
 
-    let kakarot_rpc_module = match starknet_provider {
-        StarknetProvider::JsonRpcClient(starknet_provider) => {
-            let kakarot_client = Arc::new(KakarotClient::new(starknet_config, starknet_provider));
-            KakarotRpcModuleBuilder::new(kakarot_client).rpc_module()
+    // The indexer is given a provider of its own, see `build_kakarot_client`.
+    let kakarot_rpc_module = match (starknet_provider(&starknet_config), starknet_provider(&starknet_config)) {
+        (StarknetProvider::JsonRpcClient(starknet_provider), StarknetProvider::JsonRpcClient(indexer_provider)) => {
+            let (kakarot_client, head_tracker) = build_kakarot_client(
+                starknet_config,
+                starknet_provider,
+                indexer_provider,
+                transaction_hashes,
+                transaction_pool.clone(),
+                index,
+                indexer_config,
+            )
+            .await?;
+            KakarotRpcModuleBuilder::new(kakarot_client, head_tracker, &rpc_config)
+                .with_transaction_pool(transaction_pool)
+                .rpc_module()
         }
-        StarknetProvider::SequencerGatewayProvider(starknet_provider) => {
-            let kakarot_client = Arc::new(KakarotClient::new(starknet_config, starknet_provider));
-            KakarotRpcModuleBuilder::new(kakarot_client).rpc_module()
+        (
+            StarknetProvider::SequencerGatewayProvider(starknet_provider),
+            StarknetProvider::SequencerGatewayProvider(indexer_provider),
+        ) => {
+            let (kakarot_client, head_tracker) = build_kakarot_client(
+                starknet_config,
+                starknet_provider,
+                indexer_provider,
+                transaction_hashes,
+                transaction_pool.clone(),
+                index,
+                indexer_config,
+            )
+            .await?;
+            KakarotRpcModuleBuilder::new(kakarot_client, head_tracker, &rpc_config)
+                .with_transaction_pool(transaction_pool)
+                .rpc_module()
         }
+        _ => unreachable!("the providers are created from the same configuration"),
     }?;
 
+    let ws_server_handle = match &rpc_config.ws_socket_addr {
+        Some(ws_socket_addr) => {
+            let (ws_server_addr, ws_server_handle) = run_ws_server(kakarot_rpc_module.clone(), ws_socket_addr).await?;
+            println!("RPC WebSocket Server running on ws://{ws_server_addr}...");
+            Some(ws_server_handle)
+        }
+        None => None,
+    };
+
     let (server_addr, server_handle) = run_server(kakarot_rpc_module, rpc_config).await?;
 
     let url = format!("http://{server_addr}");
@@ -60,6 +99,92 @@ async fn main() -> Result<()> {
     println!("RPC Server running on {url}...");
 
     server_handle.stopped().await;
+    if let Some(ws_server_handle) = ws_server_handle {
+        ws_server_handle.stopped().await;
+    }
 
     Ok(())
 }
+
+/// Creates the Starknet provider of the network of the configuration.
+fn starknet_provider(starknet_config: &StarknetConfig) -> StarknetProvider {
+    match &starknet_config.network {
+        Network::Madara | Network::Katana | Network::Sharingan => {
+            StarknetProvider::JsonRpcClient(JsonRpcClientBuilder::with_http(starknet_config).unwrap().build())
+        }
+        Network::JsonRpcProvider(url) => {
+            StarknetProvider::JsonRpcClient(JsonRpcClientBuilder::new(HttpTransport::new(url.clone())).build())
+        }
+        _ => StarknetProvider::SequencerGatewayProvider(
+            SequencerGatewayProviderBuilder::new(&starknet_config.network).build(),
+        ),
+    }
+}
+
+/// Creates the Kakarot client, discovering its genesis block unless configured and verifying its
+/// local address derivation, and spawns the maintainer of its transaction pool, the tracker of its
+/// submissions, the resolver of its block tags, the tracker of the chain head, the invalidation of
+/// its cache on the reorgs reported by the head tracker and, when the index is enabled, the indexer
+/// feeding it. Returns the client along with a handle on the head tracker.
+///
+/// The indexer reads through a client of its own, built on `indexer_provider`, which translates
+/// everything from Starknet: reading from the index it feeds would return the data of the blocks
+/// it rolls back.
+async fn build_kakarot_client<P: Provider + Send + Sync + 'static>(
+    starknet_config: StarknetConfig,
+    starknet_provider: P,
+    indexer_provider: P,
+    transaction_hashes: Arc<TransactionHashStore>,
+    transaction_pool: Arc<TransactionPool>,
+    index: Option<Arc<IndexStore>>,
+    indexer_config: Option<IndexerConfig>,
+) -> Result<(Arc<KakarotClient<P>>, HeadTrackerHandle)> {
+    let block_tag_resolver = Arc::new(BlockTagResolver::new(SafeBlockPolicy::from_env()?));
+    let cache = Arc::new(StarknetCache::from_env()?);
+
+    let starknet_config = match starknet_config.genesis_block {
+        Some(_) => starknet_config,
+        None => {
+            let kakarot_contract =
+                KakarotContract::<P>::new(starknet_config.kakarot_address, starknet_config.proxy_account_class_hash);
+            let genesis_block = kakarot_contract.deployment_block(&starknet_provider).await.unwrap_or_else(|err| {
+                log::warn!("Failed to discover the Kakarot genesis block, falling back to block 0: {err}");
+                0
+            });
+            starknet_config.with_genesis_block(genesis_block)
+        }
+    };
+
+    let genesis_block = starknet_config.genesis_block.unwrap_or_default();
+
+    let submission_tracker = Arc::new(SubmissionTracker::default().with_transaction_pool(transaction_pool.clone()));
+    let kakarot_client = KakarotClient::new(starknet_config.clone(), starknet_provider)
+        .with_verified_address_derivation()
+        .await
+        .with_transaction_hashes(transaction_hashes.clone())
+        .with_transaction_pool(transaction_pool.clone())
+        .with_submission_tracker(submission_tracker.clone())
+        .with_block_tag_resolver(block_tag_resolver.clone())
+        .with_cache(cache.clone());
+
+    let kakarot_client = match (index, indexer_config) {
+        (Some(index), Some(indexer_config)) => {
+            let indexer_client = KakarotClient::new(starknet_config, indexer_provider)
+                .with_transaction_hashes(transaction_hashes)
+                .with_cache(cache.clone());
+            let start_block = indexer_config.start_block.unwrap_or(genesis_block);
+            Indexer::new(Arc::new(indexer_client), index.clone(), start_block).spawn();
+            Arc::new(kakarot_client.with_index(index))
+        }
+        _ => Arc::new(kakarot_client),
+    };
+
+    TransactionPoolMaintainer::new(kakarot_client.clone(), transaction_pool).spawn();
+    SubmissionTracker::spawn(submission_tracker, kakarot_client.clone());
+    BlockTagResolver::spawn(block_tag_resolver, kakarot_client.clone());
+    let head_tracker = HeadTracker::new(kakarot_client.clone());
+    let head_tracker_handle = head_tracker.handle();
+    StarknetCache::spawn(cache, head_tracker_handle.clone());
+    head_tracker.spawn();
+    Ok((kakarot_client, head_tracker_handle))
+}
@@ -5,24 +5,36 @@ use std::sync::Arc;
 use jsonrpsee::core::Error;
 use jsonrpsee::{Methods, RpcModule};
 use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::head_tracker::HeadTrackerHandle;
+use kakarot_rpc_core::txpool::TransactionPool;
 use starknet::providers::Provider;
 
 use crate::api::alchemy_api::AlchemyApiServer;
 use crate::api::eth_api::EthApiServer;
+use crate::api::eth_pubsub_api::EthPubSubApiServer;
+use crate::api::kakarot_api::KakarotApiServer;
 use crate::api::net_api::NetApiServer;
+use crate::api::txpool_api::TxPoolApiServer;
 use crate::api::web3_api::Web3ApiServer;
+use crate::config::RPCConfig;
 use crate::servers::alchemy_rpc::AlchemyRpc;
+use crate::servers::eth_pubsub_rpc::KakarotEthPubSub;
 use crate::servers::eth_rpc::KakarotEthRpc;
+use crate::servers::kakarot_rpc::KakarotRpc;
 use crate::servers::net_rpc::NetRpc;
+use crate::servers::txpool_rpc::TxPoolRpc;
 use crate::servers::web3_rpc::Web3Rpc;
 
 /// Represents RPC modules that are supported by reth
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum KakarotRpcModule {
     Eth,
+    EthPubSub,
     Alchemy,
     Web3,
     Net,
+    TxPool,
+    Kakarot,
 }
 
 pub struct KakarotRpcModuleBuilder<P: Provider + Send + Sync + 'static> {
@@ -31,22 +43,41 @@ pub struct KakarotRpcModuleBuilder<P: Provider + Send + Sync + 'static> {
 }
 
 impl<P: Provider + Send + Sync + 'static> KakarotRpcModuleBuilder<P> {
-    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>) -> Self {
-        let eth_rpc_module = KakarotEthRpc::new(kakarot_client.clone()).into_rpc();
-        let alchemy_rpc_module = AlchemyRpc::new(kakarot_client).into_rpc();
+    /// Creates the modules, the subscriptions being fed by the head tracker of the handle.
+    pub fn new(
+        kakarot_client: Arc<dyn KakarotEthApi<P>>,
+        head_tracker: HeadTrackerHandle,
+        rpc_config: &RPCConfig,
+    ) -> Self {
+        let eth_rpc_module = KakarotEthRpc::with_filter_timeout(kakarot_client.clone(), rpc_config.filter_timeout)
+            .with_head_tracker(&head_tracker)
+            .with_sync_timeout(rpc_config.sync_timeout)
+            .into_rpc();
+        let eth_pubsub_rpc_module = KakarotEthPubSub::new(kakarot_client.clone(), head_tracker).into_rpc();
+        let alchemy_rpc_module = AlchemyRpc::new(kakarot_client.clone()).into_rpc();
+        let kakarot_rpc_module = KakarotRpc::new(kakarot_client).into_rpc();
         let web3_rpc_module = Web3Rpc::default().into_rpc();
         let net_rpc_module = NetRpc::default().into_rpc();
 
         let mut modules: HashMap<KakarotRpcModule, Methods> = HashMap::new();
 
         modules.insert(KakarotRpcModule::Eth, eth_rpc_module.into());
+        modules.insert(KakarotRpcModule::EthPubSub, eth_pubsub_rpc_module.into());
         modules.insert(KakarotRpcModule::Alchemy, alchemy_rpc_module.into());
         modules.insert(KakarotRpcModule::Web3, web3_rpc_module.into());
         modules.insert(KakarotRpcModule::Net, net_rpc_module.into());
+        modules.insert(KakarotRpcModule::Kakarot, kakarot_rpc_module.into());
 
         Self { modules, _phantom: PhantomData }
     }
 
+    /// Serves the `txpool` namespace from the transaction pool of the Kakarot client.
+    #[must_use]
+    pub fn with_transaction_pool(mut self, transaction_pool: Arc<TransactionPool>) -> Self {
+        self.modules.insert(KakarotRpcModule::TxPool, TxPoolRpc::new(transaction_pool).into_rpc().into());
+        self
+    }
+
     pub fn rpc_module(&self) -> Result<RpcModule<()>, Error> {
         let mut rpc_module = RpcModule::new(());
 
@@ -0,0 +1,212 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use jsonrpsee::core::{async_trait, SubscriptionResult as Result};
+use jsonrpsee::types::error::INVALID_PARAMS_CODE;
+use jsonrpsee::{PendingSubscriptionSink, SubscriptionMessage, SubscriptionSink};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::errors::rpc_err;
+use kakarot_rpc_core::client::head_tracker::{ChainEvent, HeadTrackerHandle};
+use kakarot_rpc_core::models::filter::EthFilter;
+use reth_rpc_types::pubsub::{Params, SubscriptionKind, SubscriptionResult};
+use reth_rpc_types::{Filter, Log, Rich};
+use starknet::providers::Provider;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::api::eth_pubsub_api::EthPubSubApiServer;
+
+/// Maximum duration spent sending a single message to a subscriber before disconnecting it.
+pub const SUBSCRIPTION_SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The RPC module for the Ethereum subscriptions, served over WebSocket.
+///
+/// Subscriptions are fed by the chain events of the head tracker. A subscriber lagging too far
+/// behind the tracker is disconnected.
+pub struct KakarotEthPubSub<P: Provider + Send + Sync> {
+    pub kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    head_tracker: HeadTrackerHandle,
+}
+
+impl<P: Provider + Send + Sync + 'static> KakarotEthPubSub<P> {
+    #[must_use]
+    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>, head_tracker: HeadTrackerHandle) -> Self {
+        Self { kakarot_client, head_tracker }
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync + 'static> EthPubSubApiServer for KakarotEthPubSub<P> {
+    async fn subscribe(
+        &self,
+        pending: PendingSubscriptionSink,
+        kind: SubscriptionKind,
+        params: Option<Params>,
+    ) -> Result {
+        let filter = match (kind, params) {
+            (SubscriptionKind::Logs, Some(Params::Logs(filter))) => Some(EthFilter::new(*filter)),
+            (SubscriptionKind::Logs, _) => Some(EthFilter::new(Filter::default())),
+            (SubscriptionKind::NewHeads | SubscriptionKind::NewPendingTransactions, _) => None,
+            (SubscriptionKind::Syncing, _) => {
+                pending.reject(rpc_err(INVALID_PARAMS_CODE, "Unsupported subscription: syncing")).await;
+                return Ok(());
+            }
+        };
+
+        let sink = pending.accept().await?;
+        let events = self.head_tracker.subscribe();
+        tokio::spawn(forward_events(sink, events, kind, filter));
+
+        Ok(())
+    }
+}
+
+/// Forwards the chain events to the subscriber until it unsubscribes, lags behind or stops
+/// reading its messages.
+async fn forward_events(
+    sink: SubscriptionSink,
+    mut events: broadcast::Receiver<ChainEvent>,
+    kind: SubscriptionKind,
+    filter: Option<EthFilter>,
+) {
+    loop {
+        let event = tokio::select! {
+            _ = sink.closed() => return,
+            event = events.recv() => event,
+        };
+        let event = match event {
+            Ok(event) => event,
+            Err(RecvError::Lagged(skipped)) => {
+                log::warn!("Disconnecting subscription {:?} lagging {skipped} events behind", sink.subscription_id());
+                return;
+            }
+            Err(RecvError::Closed) => return,
+        };
+
+        for item in subscription_items(&event, kind, filter.as_ref()) {
+            let Ok(message) = SubscriptionMessage::from_json(&item) else { return };
+            match tokio::time::timeout(SUBSCRIPTION_SEND_TIMEOUT, sink.send(message)).await {
+                Ok(Ok(())) => {}
+                // The subscriber disconnected or is too slow to consume its messages.
+                _ => return,
+            }
+        }
+    }
+}
+
+/// Returns the items of the event which are relevant for the subscription. Logs of the blocks
+/// dropped by a reorg are sent again with `removed: true`.
+fn subscription_items(
+    event: &ChainEvent,
+    kind: SubscriptionKind,
+    filter: Option<&EthFilter>,
+) -> Vec<SubscriptionResult> {
+    let matching_logs = |logs: &[Log]| -> Vec<SubscriptionResult> {
+        logs.iter()
+            .filter(|log| filter.map_or(true, |filter| filter.matches(log)))
+            .map(|log| SubscriptionResult::Log(Box::new(log.clone())))
+            .collect()
+    };
+
+    match (event, kind) {
+        (ChainEvent::NewBlock { block, .. }, SubscriptionKind::NewHeads) => {
+            let header = Rich { inner: block.inner.header.clone(), extra_info: block.extra_info.clone() };
+            vec![SubscriptionResult::Header(Box::new(header))]
+        }
+        (ChainEvent::NewBlock { logs, .. }, SubscriptionKind::Logs) => matching_logs(logs),
+        (ChainEvent::Reorg { removed_logs, .. }, SubscriptionKind::Logs) => matching_logs(removed_logs),
+        (ChainEvent::PendingChanged { new_transactions }, SubscriptionKind::NewPendingTransactions) => {
+            new_transactions.iter().copied().map(SubscriptionResult::TransactionHash).collect()
+        }
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kakarot_rpc_core::client::helpers::logs_bloom;
+    use reth_primitives::{H256, U256};
+    use reth_rpc_types::Block;
+    use serde_json::json;
+
+    use super::*;
+
+    fn log(address: &str) -> Log {
+        serde_json::from_value(json!({
+            "address": address,
+            "topics": [],
+            "data": "0x",
+            "blockHash": null,
+            "blockNumber": null,
+            "transactionHash": null,
+            "transactionIndex": null,
+            "logIndex": null,
+            "removed": false
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_subscription_items_filters_logs() {
+        // Given
+        let address = "0x2b61c43a85bd35987c5311215e8288b823a6873e";
+        let logs = vec![log(address), log("0x0000000000000000000000000000000000000001")];
+        let block = Arc::new(Rich { inner: Default::default(), extra_info: Default::default() });
+        let event = ChainEvent::NewBlock { block, logs: Arc::new(logs) };
+        let filter = EthFilter::new(serde_json::from_value(json!({ "address": address })).unwrap());
+
+        // When
+        let items = subscription_items(&event, SubscriptionKind::Logs, Some(&filter));
+
+        // Then
+        assert_eq!(vec![SubscriptionResult::Log(Box::new(log(address)))], items);
+    }
+
+    #[test]
+    fn test_subscription_items_sends_the_header_of_new_blocks() {
+        // Given
+        let logs = vec![log("0x2b61c43a85bd35987c5311215e8288b823a6873e")];
+        let mut block: Rich<Block> = Rich { inner: Default::default(), extra_info: Default::default() };
+        block.inner.header.gas_used = U256::from(21_000);
+        block.inner.header.logs_bloom = logs_bloom(&logs);
+        let event = ChainEvent::NewBlock { block: Arc::new(block.clone()), logs: Arc::new(logs) };
+
+        // When
+        let heads = subscription_items(&event, SubscriptionKind::NewHeads, None);
+
+        // Then
+        let header = Rich { inner: block.inner.header, extra_info: block.extra_info };
+        assert_eq!(vec![SubscriptionResult::Header(Box::new(header))], heads);
+    }
+
+    #[test]
+    fn test_subscription_items_reemits_removed_logs() {
+        // Given
+        let address = "0x2b61c43a85bd35987c5311215e8288b823a6873e";
+        let mut removed_log = log(address);
+        removed_log.removed = true;
+        let event = ChainEvent::Reorg { dropped: vec![], removed_logs: Arc::new(vec![removed_log.clone()]) };
+
+        // When
+        let logs = subscription_items(&event, SubscriptionKind::Logs, None);
+        let heads = subscription_items(&event, SubscriptionKind::NewHeads, None);
+
+        // Then
+        assert_eq!(vec![SubscriptionResult::Log(Box::new(removed_log))], logs);
+        assert!(heads.is_empty());
+    }
+
+    #[test]
+    fn test_subscription_items_ignores_other_kinds() {
+        // Given
+        let event = ChainEvent::PendingChanged { new_transactions: Arc::new(vec![H256::from_low_u64_be(1)]) };
+
+        // When
+        let heads = subscription_items(&event, SubscriptionKind::NewHeads, None);
+        let pending = subscription_items(&event, SubscriptionKind::NewPendingTransactions, None);
+
+        // Then
+        assert!(heads.is_empty());
+        assert_eq!(vec![SubscriptionResult::TransactionHash(H256::from_low_u64_be(1))], pending);
+    }
+}
@@ -1,32 +1,62 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use jsonrpsee::core::{async_trait, RpcResult as Result};
 use jsonrpsee::types::error::{INTERNAL_ERROR_CODE, METHOD_NOT_FOUND_CODE};
 use kakarot_rpc_core::client::api::KakarotEthApi;
 use kakarot_rpc_core::client::constants::CHAIN_ID;
 use kakarot_rpc_core::client::errors::{rpc_err, EthApiError};
-use kakarot_rpc_core::models::block::EthBlockId;
+use kakarot_rpc_core::client::head_tracker::HeadTrackerHandle;
+use kakarot_rpc_core::models::receipt::KakarotTransactionReceipt;
 use reth_primitives::rpc::transaction::eip2930::AccessListWithGasUsed;
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, H256, H64, U128, U256, U64};
 use reth_rpc_types::{
-    CallRequest, EIP1186AccountProofResponse, FeeHistory, Index, RichBlock, SyncStatus,
-    Transaction as EtherTransaction, TransactionReceipt, TransactionRequest, Work,
+    CallRequest, EIP1186AccountProofResponse, FeeHistory, Filter, Index, Log, RichBlock, SyncStatus,
+    Transaction as EtherTransaction, TransactionRequest, Work,
 };
 use serde_json::Value;
 use starknet::core::types::BlockId as StarknetBlockId;
 use starknet::providers::Provider;
 
 use crate::api::eth_api::EthApiServer;
+use crate::filters::{EthFilters, FilterChanges, DEFAULT_FILTER_TIMEOUT};
+
+/// Default maximum duration `eth_sendRawTransactionSync` waits for the receipt of a transaction.
+pub const DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// The RPC module for the Ethereum protocol required by Kakarot.
 pub struct KakarotEthRpc<P: Provider + Send + Sync> {
     pub kakarot_client: Arc<dyn KakarotEthApi<P>>,
+    filters: EthFilters<P>,
+    sync_timeout: Duration,
 }
 
 impl<P: Provider + Send + Sync> KakarotEthRpc<P> {
     #[must_use]
     pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>) -> Self {
-        Self { kakarot_client }
+        Self::with_filter_timeout(kakarot_client, DEFAULT_FILTER_TIMEOUT)
+    }
+
+    /// Creates the module, uninstalling filters which are not polled within `filter_timeout`.
+    #[must_use]
+    pub fn with_filter_timeout(kakarot_client: Arc<dyn KakarotEthApi<P>>, filter_timeout: Duration) -> Self {
+        let filters = EthFilters::new(kakarot_client.clone(), filter_timeout);
+        Self { kakarot_client, filters, sync_timeout: DEFAULT_SEND_RAW_TRANSACTION_SYNC_TIMEOUT }
+    }
+
+    /// Reports the reorgs published by the head tracker to the filters.
+    #[must_use]
+    pub fn with_head_tracker(mut self, head_tracker: &HeadTrackerHandle) -> Self {
+        self.filters = self.filters.with_head_tracker(head_tracker);
+        self
+    }
+
+    /// Sets the maximum duration `eth_sendRawTransactionSync` waits for the receipt of a
+    /// transaction.
+    #[must_use]
+    pub fn with_sync_timeout(mut self, sync_timeout: Duration) -> Self {
+        self.sync_timeout = sync_timeout;
+        self
     }
 }
 
@@ -55,15 +85,23 @@ impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
     }
 
     async fn block_by_hash(&self, hash: H256, full: bool) -> Result<Option<RichBlock>> {
-        let block_id = EthBlockId::new(BlockId::Hash(hash.into()));
-        let starknet_block_id: StarknetBlockId = block_id.try_into().map_err(EthApiError::<P::Error>::from)?;
-        let block = self.kakarot_client.get_eth_block_from_starknet_block(starknet_block_id, full).await?;
+        let starknet_block_id = self.kakarot_client.starknet_block_id(BlockId::Hash(hash.into()))?;
+        let number = self.kakarot_client.map_block_id_to_block_number(&starknet_block_id).await?;
+        // Blocks preceding the deployment of Kakarot aren't part of its chain.
+        if number < self.kakarot_client.genesis_block_number() {
+            return Ok(None);
+        }
+        let block =
+            self.kakarot_client.get_eth_block_from_starknet_block(StarknetBlockId::Number(number), full).await?;
         Ok(Some(block))
     }
 
     async fn block_by_number(&self, number: BlockNumberOrTag, full: bool) -> Result<Option<RichBlock>> {
-        let block_id = EthBlockId::new(BlockId::Number(number));
-        let starknet_block_id: StarknetBlockId = block_id.try_into().map_err(EthApiError::<P::Error>::from)?;
+        let starknet_block_id = match self.kakarot_client.starknet_block_id(BlockId::Number(number)) {
+            Ok(starknet_block_id) => starknet_block_id,
+            Err(EthApiError::BlockBeforeGenesis(..)) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
         let block = self.kakarot_client.get_eth_block_from_starknet_block(starknet_block_id, full).await?;
         Ok(Some(block))
     }
@@ -119,11 +157,40 @@ impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
         Ok(Some(tx))
     }
 
-    async fn transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>> {
+    async fn transaction_receipt(&self, hash: H256) -> Result<Option<KakarotTransactionReceipt>> {
         let receipt = self.kakarot_client.transaction_receipt(hash).await?;
         Ok(receipt)
     }
 
+    async fn get_logs(&self, filter: Filter) -> Result<Vec<Log>> {
+        let logs = self.kakarot_client.get_logs(filter).await?;
+        Ok(logs)
+    }
+
+    async fn new_filter(&self, filter: Filter) -> Result<U256> {
+        self.filters.new_filter(filter).await
+    }
+
+    async fn new_block_filter(&self) -> Result<U256> {
+        self.filters.new_block_filter().await
+    }
+
+    async fn new_pending_transaction_filter(&self) -> Result<U256> {
+        self.filters.new_pending_transaction_filter().await
+    }
+
+    async fn filter_changes(&self, id: U256) -> Result<FilterChanges> {
+        self.filters.filter_changes(id).await
+    }
+
+    async fn filter_logs(&self, id: U256) -> Result<Vec<Log>> {
+        self.filters.filter_logs(id).await
+    }
+
+    async fn uninstall_filter(&self, id: U256) -> Result<bool> {
+        Ok(self.filters.uninstall_filter(id))
+    }
+
     async fn balance(&self, address: Address, block_id: Option<BlockId>) -> Result<U256> {
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
         let balance = self.kakarot_client.balance(address, block_id).await?;
@@ -181,7 +248,7 @@ impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
     }
 
     async fn gas_price(&self) -> Result<U256> {
-        let gas_price = self.kakarot_client.base_fee_per_gas();
+        let gas_price = self.kakarot_client.gas_price().await?;
         Ok(gas_price)
     }
 
@@ -230,6 +297,18 @@ impl<P: Provider + Send + Sync + 'static> EthApiServer for KakarotEthRpc<P> {
         Ok(transaction_hash)
     }
 
+    async fn send_raw_transaction_sync(
+        &self,
+        bytes: Bytes,
+        timeout: Option<u64>,
+        pending: Option<bool>,
+    ) -> Result<KakarotTransactionReceipt> {
+        let timeout =
+            timeout.map_or(self.sync_timeout, |timeout| Duration::from_millis(timeout).min(self.sync_timeout));
+        let receipt = self.kakarot_client.send_transaction_sync(bytes, timeout, pending.unwrap_or_default()).await?;
+        Ok(receipt)
+    }
+
     async fn sign(&self, _address: Address, _message: Bytes) -> Result<Bytes> {
         todo!()
     }
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use kakarot_rpc_core::client::api::KakarotEthApi;
+use kakarot_rpc_core::client::cache::StarknetCacheStats;
+use kakarot_rpc_core::client::submission_tracker::TransactionLifecycle;
+use reth_primitives::{Address, Bytes, H256};
+use starknet::providers::Provider;
+
+use crate::api::kakarot_api::KakarotApiServer;
+
+/// The RPC module for the Kakarot specific methods.
+pub struct KakarotRpc<P: Provider + Send + Sync> {
+    pub kakarot_client: Arc<dyn KakarotEthApi<P>>,
+}
+
+impl<P: Provider + Send + Sync> KakarotRpc<P> {
+    #[must_use]
+    pub fn new(kakarot_client: Arc<dyn KakarotEthApi<P>>) -> Self {
+        Self { kakarot_client }
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync + 'static> KakarotApiServer for KakarotRpc<P> {
+    async fn get_transaction_status(&self, hash: H256) -> Result<Option<TransactionLifecycle>> {
+        let lifecycle = self.kakarot_client.transaction_status(hash).await?;
+        Ok(lifecycle)
+    }
+
+    async fn send_raw_transaction_batch(&self, transactions: Vec<Bytes>) -> Result<Vec<H256>> {
+        let transaction_hashes = self.kakarot_client.send_transaction_batch(transactions).await?;
+        Ok(transaction_hashes)
+    }
+
+    async fn get_cache_stats(&self) -> Result<StarknetCacheStats> {
+        Ok(self.kakarot_client.cache_stats())
+    }
+
+    async fn get_transactions_by_address(&self, address: Address) -> Result<Option<Vec<H256>>> {
+        let transaction_hashes = self.kakarot_client.transactions_by_address(address)?;
+        Ok(transaction_hashes)
+    }
+}
@@ -1,4 +1,7 @@
 pub mod alchemy_rpc;
+pub mod eth_pubsub_rpc;
 pub mod eth_rpc;
+pub mod kakarot_rpc;
 pub mod net_rpc;
+pub mod txpool_rpc;
 pub mod web3_rpc;
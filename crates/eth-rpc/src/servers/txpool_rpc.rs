@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+use kakarot_rpc_core::txpool::{TransactionPool, TxpoolContent, TxpoolInspect, TxpoolStatus};
+
+use crate::api::txpool_api::TxPoolApiServer;
+
+/// The RPC module for the txpool namespace, serving the local transaction pool.
+pub struct TxPoolRpc {
+    pub transaction_pool: Arc<TransactionPool>,
+}
+
+impl TxPoolRpc {
+    #[must_use]
+    pub fn new(transaction_pool: Arc<TransactionPool>) -> Self {
+        Self { transaction_pool }
+    }
+}
+
+#[async_trait]
+impl TxPoolApiServer for TxPoolRpc {
+    fn content(&self) -> Result<TxpoolContent> {
+        Ok(self.transaction_pool.content())
+    }
+
+    fn status(&self) -> Result<TxpoolStatus> {
+        Ok(self.transaction_pool.status())
+    }
+
+    fn inspect(&self) -> Result<TxpoolInspect> {
+        Ok(self.transaction_pool.inspect())
+    }
+}
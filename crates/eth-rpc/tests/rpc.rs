@@ -6,14 +6,16 @@ mod tests {
     use std::str::FromStr;
 
     use kakarot_rpc::api::eth_api::EthApiServer;
+    use kakarot_rpc::filters::FilterChanges;
+    use kakarot_rpc_core::client::helpers::logs_bloom;
     use kakarot_rpc_core::mock::assert_helpers::{assert_block, assert_block_header, assert_transaction};
-    use reth_primitives::{BlockNumberOrTag, H160, H256, U256, U64};
+    use reth_primitives::{BlockNumberOrTag, H160, H256, U128, U256, U64, U8};
     use reth_rpc_types::Index;
     use serde_json::json;
-    use starknet::core::types::{FieldElement, Transaction as StarknetTransaction};
+    use starknet::core::types::{FieldElement, Transaction as StarknetTransaction, TransactionStatus};
     use starknet::macros::felt;
 
-    use crate::utils::setup_kakarot_eth_rpc;
+    use crate::utils::{setup_kakarot_eth_rpc, setup_kakarot_eth_rpc_with_gas_price_account};
 
     fn get_test_tx() -> serde_json::Value {
         json!({
@@ -825,6 +827,19 @@ mod tests {
         assert_block_header(&block, starknet_res.to_string(), false);
     }
 
+    #[tokio::test]
+    async fn test_get_block_by_hash_views_have_the_same_header() {
+        // The views are served by distinct clients, so that the receipts of the block computed for
+        // one view aren't cached for the other.
+        let hash = H256::from_str("0x0449aa33ad836b65b10fa60082de99e24ac876ee2fd93e723a99190a530af0a9").unwrap();
+
+        let block_with_tx_hashes = setup_kakarot_eth_rpc().await.block_by_hash(hash, false).await.unwrap().unwrap();
+        let block_with_txs = setup_kakarot_eth_rpc().await.block_by_hash(hash, true).await.unwrap().unwrap();
+
+        assert_eq!(block_with_txs.header, block_with_tx_hashes.header);
+        assert_ne!(U256::ZERO, block_with_tx_hashes.header.gas_used);
+    }
+
     #[tokio::test]
     async fn test_get_block_by_number_hydrated_is_ok() {
         let kakarot_rpc = setup_kakarot_eth_rpc().await;
@@ -930,11 +945,7 @@ mod tests {
 
         assert_eq!(
             transaction_receipt.transaction_hash,
-            Some(H256::from_slice(
-                &FieldElement::from_str("0x03204b4c0e379c3a5ccb80d08661d5a538e95e2960581c9faf7ebcf8ff5a7d3c")
-                    .unwrap()
-                    .to_bytes_be()
-            ))
+            Some(H256::from_str("0x0e2d5995c3b8db32a7052f70e7f1ad428b4958be3c89000d491bcca96e00eb9c").unwrap())
         );
 
         assert_eq!(
@@ -948,21 +959,37 @@ mod tests {
 
         assert_eq!(U256::from(transaction_receipt.block_number.unwrap()), U256::from(13));
         assert_eq!(transaction_receipt.status_code, Some(U64::from(1)));
+        assert_eq!(transaction_receipt.starknet_status, Some(TransactionStatus::AcceptedOnL2));
 
         assert_eq!(transaction_receipt.from, H160::from_str("0x54b288676b749def5fc10eb17244fe2c87375de1").unwrap());
 
-        // TODO
-        // assert_eq!(transaction_receipt.logs, None);
-        // assert_eq!(transaction_receipt.contract_address, Some(U64::from(1)));
-
-        // assert_eq!(transaction_receipt.transaction_index, None);
-        // assert_eq!(transaction_receipt.to, None);
-        // assert_eq!(transaction_receipt.cumulative_gas_used, U256::from(1000000));
-        // assert_eq!(transaction_receipt.gas_used, None);
-        // assert_eq!(transaction_receipt.logs_bloom, Bloom::default());
-        // assert_eq!(transaction_receipt.state_root, None);
-        // assert_eq!(transaction_receipt.effective_gas_price, U128::from(1000000));
-        // assert_eq!(transaction_receipt.transaction_type, U256::from(0));
+        // The transaction is the first Kakarot transaction of its block.
+        assert_eq!(transaction_receipt.transaction_index, Some(U256::ZERO));
+        assert_eq!(transaction_receipt.effective_gas_price, U128::from(0xdead));
+        // The actual fee of the transaction divided by the Starknet gas price of its block.
+        let gas_used = U256::from(1_215_300);
+        assert_eq!(transaction_receipt.gas_used, Some(gas_used));
+        assert_eq!(transaction_receipt.cumulative_gas_used, gas_used);
+        assert_eq!(transaction_receipt.transaction_type, U8::from(2));
+        for (i, log) in transaction_receipt.logs.iter().enumerate() {
+            assert_eq!(log.log_index, Some(U256::from(i)));
+            assert_eq!(log.transaction_index, Some(U256::ZERO));
+        }
+        assert_eq!(transaction_receipt.logs_bloom, logs_bloom(&transaction_receipt.logs));
+    }
+
+    #[tokio::test]
+    async fn test_transaction_receipt_without_gas_price_account_is_ok() {
+        let kakarot_rpc = setup_kakarot_eth_rpc_with_gas_price_account(None).await;
+        let hash = H256::from_str("0x03204b4c0e379c3a5ccb80d08661d5a538e95e2960581c9faf7ebcf8ff5a7d3c").unwrap();
+
+        let transaction_receipt = kakarot_rpc.transaction_receipt(hash).await.unwrap().unwrap();
+
+        // Without the Starknet gas price, the gas used is the actual fee of the transaction.
+        let gas_used = U256::from(0x04514f14cba800_u64);
+        assert_eq!(transaction_receipt.gas_used, Some(gas_used));
+        assert_eq!(transaction_receipt.cumulative_gas_used, gas_used);
+        assert_eq!(transaction_receipt.status_code, Some(U64::from(1)));
     }
 
     #[tokio::test]
@@ -1013,4 +1040,46 @@ mod tests {
         );
         assert_eq!(U256::from(transaction.block_number.unwrap()), U256::from(13));
     }
+
+    #[tokio::test]
+    async fn test_block_filter_lifecycle_is_ok() {
+        // Given
+        let kakarot_rpc = setup_kakarot_eth_rpc().await;
+        let id = kakarot_rpc.new_block_filter().await.unwrap();
+
+        // When
+        let changes = kakarot_rpc.filter_changes(id).await.unwrap();
+
+        // Then
+        assert_eq!(FilterChanges::Hashes(vec![]), changes);
+        assert!(kakarot_rpc.uninstall_filter(id).await.unwrap());
+        assert!(!kakarot_rpc.uninstall_filter(id).await.unwrap());
+        assert!(kakarot_rpc.filter_changes(id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_filter_ids_are_unique() {
+        // Given
+        let kakarot_rpc = setup_kakarot_eth_rpc().await;
+
+        // When
+        let first = kakarot_rpc.new_block_filter().await.unwrap();
+        let second = kakarot_rpc.new_block_filter().await.unwrap();
+
+        // Then
+        assert_ne!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_filter_logs_of_block_filter_is_err() {
+        // Given
+        let kakarot_rpc = setup_kakarot_eth_rpc().await;
+        let id = kakarot_rpc.new_block_filter().await.unwrap();
+
+        // When
+        let logs = kakarot_rpc.filter_logs(id).await;
+
+        // Then
+        assert!(logs.is_err());
+    }
 }
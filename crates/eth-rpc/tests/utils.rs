@@ -35,17 +35,30 @@ use url::Url;
 ///   }
 /// ```
 pub async fn setup_kakarot_eth_rpc() -> KakarotEthRpc<JsonRpcClient<HttpTransport>> {
+    let gas_price_account =
+        FieldElement::from_hex_be("0x744ed080b42c8883a7e31cd11a14b7ae9ef27698b785486bb75cd116c8f1485").unwrap();
+    setup_kakarot_eth_rpc_with_gas_price_account(Some(gas_price_account)).await
+}
+
+/// Run wiremock to fake starknet rpc and then run our own `kakarot_rpc_server`, probing the
+/// Starknet gas price through the given account, if any.
+pub async fn setup_kakarot_eth_rpc_with_gas_price_account(
+    gas_price_account: Option<FieldElement>,
+) -> KakarotEthRpc<JsonRpcClient<HttpTransport>> {
     let provider_url = setup_wiremock().await;
     let kakarot_address =
         FieldElement::from_hex_be("0x566864dbc2ae76c2d12a8a5a334913d0806f85b7a4dccea87467c3ba3616e75").unwrap();
     let proxy_account_class_hash =
         FieldElement::from_hex_be("0x0775033b738dfe34c48f43a839c3d882ebe521befb3447240f2d218f14816ef5").unwrap();
 
-    let config = StarknetConfig::new(
+    let mut config = StarknetConfig::new(
         Network::JsonRpcProvider(Url::parse(&provider_url).unwrap()),
         kakarot_address,
         proxy_account_class_hash,
     );
+    if let Some(gas_price_account) = gas_price_account {
+        config = config.with_gas_price_account(gas_price_account);
+    }
     let starknet_provider = JsonRpcClientBuilder::with_http(&config).unwrap().build();
 
     let kakarot_client = KakarotClient::new(config, starknet_provider);